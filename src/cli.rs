@@ -0,0 +1,293 @@
+use std::path::PathBuf;
+
+use runner::{Difficulty, GameMode, TICKS_PER_SECOND};
+
+pub const HELP: &str = "\
+RUNNER - dodge the obstacles for as long as you can.
+
+Usage:
+  runner [play] [options]     Play the game.
+  runner replay <file>        Watch a recorded run.
+  runner scores               Show the high scores.
+  runner help                 Show this message.
+
+Options for play:
+  --seed <seed>               Generate every run from this seed instead of a new random one each time.
+  --difficulty <difficulty>   Which of easy, normal, hard, or insane is picked on the start screen.
+                              Defaults to normal.
+  --mode <mode>               classic to win by reaching the victory score, or endless to keep going
+                              past it, getting harder and harder until you run out of lives.
+                              Defaults to classic, or to the config file's mode.
+  --config <file>             Tune the game with the settings in this config file.
+  --record <file>             Save a replay of everything you press here when you quit.
+  --stats <file>              Add the stats of each run to this file when it ends, as CSV if the
+                              file ends in .csv and as JSON lines otherwise.
+  --fps <fps>                 How many frames to draw each second. The game runs at the same speed
+                              whatever this is. Defaults to 30.
+  --width <width>             How many columns wide the screen is. Defaults to the terminal's width.
+  --height <height>           How many rows tall the screen is. Defaults to the terminal's height.
+
+Options for replay:
+  --fps <fps>                 How many frames to draw each second. Defaults to 30.
+  --stats <file>              Add the stats of the replayed run to this file when it ends.";
+
+pub enum Command {
+    Play(PlayArgs),
+    Replay {
+        replay_path: PathBuf,
+        max_frame_rate: u16,
+        stats_path_option: Option<PathBuf>,
+    },
+    Scores,
+    Help,
+}
+
+pub struct PlayArgs {
+    pub seed_option: Option<u64>,
+    pub difficulty: Difficulty,
+    pub mode_option: Option<GameMode>,
+    pub config_path_option: Option<PathBuf>,
+    pub record_path_option: Option<PathBuf>,
+    pub stats_path_option: Option<PathBuf>,
+    pub max_frame_rate: u16,
+    pub width_option: Option<u16>,
+    pub height_option: Option<u16>,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.first().map(|arg| arg.as_str()) {
+        None => parse_play_args(&[]),
+        Some("play") => parse_play_args(&args[1..]),
+        Some("replay") => parse_replay_args(&args[1..]),
+        Some("scores") => match args.get(1).map(|arg| arg.as_str()) {
+            None => Ok(Command::Scores),
+            Some("--help" | "-h") => Ok(Command::Help),
+            Some(arg) => Err(format!("scores doesn't take any options, got '{}'", arg)),
+        },
+        Some("help" | "--help" | "-h") => Ok(Command::Help),
+        Some(arg) if arg.starts_with("--") => parse_play_args(args),
+        Some(arg) => Err(format!("'{}' isn't a command", arg)),
+    }
+}
+
+fn parse_play_args(args: &[String]) -> Result<Command, String> {
+    let mut play_args = PlayArgs {
+        seed_option: None,
+        difficulty: Difficulty::Normal,
+        mode_option: None,
+        config_path_option: None,
+        record_path_option: None,
+        stats_path_option: None,
+        max_frame_rate: TICKS_PER_SECOND,
+        width_option: None,
+        height_option: None,
+    };
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(Command::Help),
+            "--seed" => {
+                let value = option_value(arg, &mut args)?;
+
+                play_args.seed_option = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| format!("the seed must be a whole number, got '{}'", value))?,
+                )
+            }
+            "--difficulty" => {
+                play_args.difficulty = option_value(arg, &mut args)?.parse::<Difficulty>()?
+            }
+            "--mode" => {
+                play_args.mode_option = Some(option_value(arg, &mut args)?.parse::<GameMode>()?)
+            }
+            "--config" => {
+                play_args.config_path_option = Some(PathBuf::from(option_value(arg, &mut args)?))
+            }
+            "--record" => {
+                play_args.record_path_option = Some(PathBuf::from(option_value(arg, &mut args)?))
+            }
+            "--stats" => {
+                play_args.stats_path_option = Some(PathBuf::from(option_value(arg, &mut args)?))
+            }
+            "--fps" => play_args.max_frame_rate = parse_frame_rate(option_value(arg, &mut args)?)?,
+            "--width" => {
+                play_args.width_option =
+                    Some(parse_screen_size("width", option_value(arg, &mut args)?)?)
+            }
+            "--height" => {
+                play_args.height_option =
+                    Some(parse_screen_size("height", option_value(arg, &mut args)?)?)
+            }
+            _ => return Err(unexpected_arg_error(arg, "play")),
+        }
+    }
+
+    Ok(Command::Play(play_args))
+}
+
+fn parse_replay_args(args: &[String]) -> Result<Command, String> {
+    let (replay_path, args) = match args.split_first() {
+        Some((arg, _)) if arg == "--help" || arg == "-h" => return Ok(Command::Help),
+        Some(split_args) => split_args,
+        None => return Err(String::from("replay needs the replay file to play")),
+    };
+
+    let mut max_frame_rate = TICKS_PER_SECOND;
+    let mut stats_path_option = None;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(Command::Help),
+            "--fps" => max_frame_rate = parse_frame_rate(option_value(arg, &mut args)?)?,
+            "--stats" => stats_path_option = Some(PathBuf::from(option_value(arg, &mut args)?)),
+            _ => return Err(unexpected_arg_error(arg, "replay")),
+        }
+    }
+
+    Ok(Command::Replay {
+        replay_path: PathBuf::from(replay_path),
+        max_frame_rate,
+        stats_path_option,
+    })
+}
+
+/// Takes the value that follows `option` off the args.
+fn option_value<'a>(
+    option: &str,
+    args: &mut impl Iterator<Item = &'a String>,
+) -> Result<&'a str, String> {
+    args.next()
+        .map(|value| value.as_str())
+        .ok_or_else(|| format!("{} needs a value", option))
+}
+
+fn unexpected_arg_error(arg: &str, command_name: &str) -> String {
+    if arg.starts_with("--") {
+        format!("'{}' isn't an option for {}", arg, command_name)
+    } else {
+        format!("expected an option, got '{}'", arg)
+    }
+}
+
+fn parse_frame_rate(value: &str) -> Result<u16, String> {
+    value
+        .parse::<u16>()
+        .ok()
+        .filter(|max_frame_rate| *max_frame_rate > 0)
+        .ok_or_else(|| format!("the fps must be a whole number above 0, got '{}'", value))
+}
+
+fn parse_screen_size(dimension_name: &str, value: &str) -> Result<u16, String> {
+    value.parse::<u16>().map_err(|_| {
+        format!(
+            "the {} must be a whole number, got '{}'",
+            dimension_name, value
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(
+            &args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<String>>(),
+        )
+    }
+
+    #[test]
+    fn play_is_the_default_command() {
+        assert!(matches!(parse(&[]), Ok(Command::Play(_))));
+
+        match parse(&["--seed", "7", "--difficulty", "hard", "--fps", "60"]) {
+            Ok(Command::Play(play_args)) => {
+                assert_eq!(play_args.seed_option, Some(7));
+                assert_eq!(play_args.difficulty, Difficulty::Hard);
+                assert_eq!(play_args.max_frame_rate, 60);
+            }
+            _ => panic!("expected to play"),
+        }
+    }
+
+    #[test]
+    fn replay_takes_the_file_to_play_then_its_options() {
+        match parse(&["replay", "run.txt", "--fps", "15"]) {
+            Ok(Command::Replay {
+                replay_path,
+                max_frame_rate,
+                stats_path_option,
+            }) => {
+                assert_eq!(replay_path, PathBuf::from("run.txt"));
+                assert_eq!(max_frame_rate, 15);
+                assert_eq!(stats_path_option, None);
+            }
+            _ => panic!("expected to replay"),
+        }
+
+        assert_eq!(
+            parse(&["replay"]).err(),
+            Some(String::from("replay needs the replay file to play"))
+        );
+    }
+
+    #[test]
+    fn unknown_commands_and_options_are_rejected() {
+        assert_eq!(
+            parse(&["fly"]).err(),
+            Some(String::from("'fly' isn't a command"))
+        );
+        assert_eq!(
+            parse(&["--speed", "2"]).err(),
+            Some(String::from("'--speed' isn't an option for play"))
+        );
+        assert_eq!(
+            parse(&["replay", "run.txt", "--seed", "2"]).err(),
+            Some(String::from("'--seed' isn't an option for replay"))
+        );
+        assert_eq!(
+            parse(&["scores", "--all"]).err(),
+            Some(String::from("scores doesn't take any options, got '--all'"))
+        );
+    }
+
+    #[test]
+    fn options_need_a_value() {
+        assert_eq!(
+            parse(&["--difficulty", "easy", "--seed"]).err(),
+            Some(String::from("--seed needs a value"))
+        );
+        assert_eq!(
+            parse(&["play", "7"]).err(),
+            Some(String::from("expected an option, got '7'"))
+        );
+    }
+
+    #[test]
+    fn each_command_shows_the_help() {
+        assert!(matches!(parse(&["play", "--help"]), Ok(Command::Help)));
+        assert!(matches!(parse(&["--seed", "7", "-h"]), Ok(Command::Help)));
+        assert!(matches!(parse(&["replay", "--help"]), Ok(Command::Help)));
+        assert!(matches!(
+            parse(&["replay", "run.txt", "-h"]),
+            Ok(Command::Help)
+        ));
+        assert!(matches!(parse(&["scores", "--help"]), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn values_that_dont_make_sense_are_rejected() {
+        assert!(parse(&["--seed", "lucky"]).is_err());
+        assert!(parse(&["--fps", "0"]).is_err());
+        assert!(parse(&["--width", "-1"]).is_err());
+        assert!(parse(&["--mode", "forever"]).is_err());
+    }
+}
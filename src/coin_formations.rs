@@ -0,0 +1,70 @@
+use crate::{GameConfig, RunnerInput, RunnerState};
+
+/// How many cells above the ground a raised line of coins is. The player has to jump to reach it.
+pub const RAISED_COIN_LINE_HEIGHT: i64 = 3;
+
+/// A way of laying out coins ahead of the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoinFormation {
+    /// A row of coins along the ground that the player picks up just by running through it.
+    Line { length: u64 },
+    /// A row of coins up in the air.
+    RaisedLine { length: u64 },
+    /// Coins along the path of a single full jump, so the player picks up all of them by jumping just before the
+    /// first one.
+    Arc,
+}
+impl CoinFormation {
+    /// Where each coin goes, as an offset in cells from the start of the formation and a height in cells above the
+    /// ground. Arcs are traced out at the speed the screen scrolls at once the distance reaches `distance`.
+    pub fn coin_cells(&self, config: &GameConfig, distance: u64) -> Vec<(i64, i64)> {
+        match *self {
+            CoinFormation::Line { length } => (0..length as i64).map(|x| (x, 0)).collect(),
+            CoinFormation::RaisedLine { length } => (0..length as i64)
+                .map(|x| (x, RAISED_COIN_LINE_HEIGHT))
+                .collect(),
+            CoinFormation::Arc => {
+                let scroll_wait_ticks = config.camera_scroll_wait_ticks(distance);
+                let mut runner = RunnerState::on_ground();
+                let mut cells: Vec<(i64, i64)> = vec![];
+
+                // The jump is held until the player lands, with a coin at the highest the player gets in each
+                // column they pass through.
+                for tick in 0.. {
+                    runner = runner.step(config, RunnerInput::Jump, false);
+
+                    if runner.height <= 0 {
+                        break;
+                    }
+
+                    let x_offset = (tick / scroll_wait_ticks) as i64;
+
+                    match cells.last_mut() {
+                        Some((last_x_offset, height)) if *last_x_offset == x_offset => {
+                            *height = (*height).max(runner.height)
+                        }
+                        _ => cells.push((x_offset, runner.height)),
+                    }
+                }
+
+                cells
+            }
+        }
+    }
+
+    /// How many cells across the formation is.
+    pub fn width(&self, config: &GameConfig, distance: u64) -> u64 {
+        self.coin_cells(config, distance)
+            .iter()
+            .map(|(x_offset, _)| *x_offset as u64 + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Every formation coins are spawned in.
+pub const COIN_FORMATIONS: &[CoinFormation] = &[
+    CoinFormation::Line { length: 5 },
+    CoinFormation::RaisedLine { length: 4 },
+    CoinFormation::Arc,
+];
@@ -0,0 +1,117 @@
+/// The highest the combo multiplier goes.
+pub const MAX_COMBO_MULTIPLIER: u64 = 3;
+/// How many ticks the combo lasts without a bonus before the multiplier drops by one.
+pub const COMBO_DECAY_TICKS: u64 = 60;
+
+/// Something the player did that's worth points on top of the distance they've made it.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Bonus {
+    /// Made it past an obstacle with a cell or less to spare.
+    NearMiss,
+    /// Jumped in the air and landed again without being hit.
+    CleanDoubleJump,
+}
+impl Bonus {
+    /// How many points the bonus is worth before the combo multiplier.
+    pub fn points(&self) -> u64 {
+        match self {
+            Bonus::NearMiss => 5,
+            Bonus::CleanDoubleJump => 10,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Bonus::NearMiss => "NEAR MISS",
+            Bonus::CleanDoubleJump => "CLEAN DOUBLE JUMP",
+        }
+    }
+}
+
+/// Multiplies the bonus points of everything the player does in a row without being hit.
+///
+/// Every bonus and every obstacle cleared raises the multiplier by one, up to `MAX_COMBO_MULTIPLIER`. It drops back by
+/// one whenever the combo decays, and back to the start when the player's hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Combo {
+    multiplier: u64,
+}
+impl Combo {
+    pub fn new() -> Self {
+        Self { multiplier: 1 }
+    }
+
+    pub fn multiplier(&self) -> u64 {
+        self.multiplier
+    }
+
+    /// Gives the points the bonus is worth at the current multiplier, and raises the multiplier for the next one.
+    pub fn score(&mut self, bonus: Bonus) -> u64 {
+        let points = bonus.points() * self.multiplier;
+
+        self.extend();
+
+        points
+    }
+
+    /// Raises the multiplier without scoring anything.
+    pub fn extend(&mut self) {
+        self.multiplier = (self.multiplier + 1).min(MAX_COMBO_MULTIPLIER);
+    }
+
+    pub fn decay(&mut self) {
+        self.multiplier = (self.multiplier - 1).max(1);
+    }
+
+    pub fn break_combo(&mut self) {
+        self.multiplier = 1;
+    }
+}
+impl Default for Combo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bonuses_score_more_the_longer_the_combo_goes() {
+        let mut combo = Combo::new();
+
+        assert_eq!(combo.score(Bonus::NearMiss), Bonus::NearMiss.points());
+        assert_eq!(combo.score(Bonus::NearMiss), Bonus::NearMiss.points() * 2);
+
+        for _ in 0..MAX_COMBO_MULTIPLIER {
+            combo.extend();
+        }
+        assert_eq!(combo.multiplier(), MAX_COMBO_MULTIPLIER);
+        assert_eq!(
+            combo.score(Bonus::CleanDoubleJump),
+            Bonus::CleanDoubleJump.points() * MAX_COMBO_MULTIPLIER
+        );
+    }
+
+    #[test]
+    fn combos_fall_away_a_step_at_a_time_and_all_at_once_when_broken() {
+        let mut combo = Combo::new();
+
+        for _ in 0..MAX_COMBO_MULTIPLIER {
+            combo.extend();
+        }
+
+        for multiplier in (1..MAX_COMBO_MULTIPLIER).rev() {
+            combo.decay();
+            assert_eq!(combo.multiplier(), multiplier);
+        }
+
+        combo.decay();
+        assert_eq!(combo.multiplier(), 1);
+
+        combo.extend();
+        combo.break_combo();
+        assert_eq!(combo, Combo::new());
+    }
+}
@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+use thomas::Component;
+
+use crate::Wallet;
+
+/// The wallet as it stands this session, along with where it's kept between sessions.
+#[derive(Component)]
+pub struct Bank {
+    pub wallet: Wallet,
+    /// Coins are only kept for this session when there isn't a path.
+    pub path_option: Option<PathBuf>,
+}
@@ -0,0 +1,11 @@
+use thomas::{Component, IntCoords2d};
+
+use crate::TickTimer;
+
+/// The points for a bonus, floating up from where the player earned them.
+#[derive(Component)]
+pub struct BonusPopup {
+    /// Where the popup started out, before it floated up.
+    pub origin: IntCoords2d,
+    pub timer: TickTimer,
+}
@@ -1,4 +1,4 @@
 use thomas::Component;
 
 #[derive(Component)]
-pub struct CleanupOnScreenExit {}
\ No newline at end of file
+pub struct CleanupOnScreenExit {}
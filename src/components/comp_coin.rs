@@ -0,0 +1,4 @@
+use thomas::Component;
+
+#[derive(Component)]
+pub struct Coin {}
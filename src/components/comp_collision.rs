@@ -0,0 +1,27 @@
+use thomas::{Component, Layer, TerminalCollider};
+
+use crate::EntityId;
+
+/// Two active colliders that were in the same cell at the start of the tick. It's the game's own take on a Thomas
+/// `TerminalCollision`, which can only name entities in a Thomas `Game`'s world.
+#[derive(Component)]
+pub struct Collision {
+    pub bodies: [(EntityId, TerminalCollider); 2],
+}
+impl Collision {
+    /// Whether one body is on the first layer and the other is on the second.
+    pub fn is_collision_between(&self, layer1: Layer, layer2: Layer) -> bool {
+        let [(_, collider1), (_, collider2)] = &self.bodies;
+
+        (collider1.layer == layer1 && collider2.layer == layer2)
+            || (collider1.layer == layer2 && collider2.layer == layer1)
+    }
+
+    /// The entity of the first body on the layer.
+    pub fn get_entity_on_layer(&self, layer: Layer) -> Option<EntityId> {
+        self.bodies
+            .iter()
+            .find(|(_, collider)| collider.layer == layer)
+            .map(|(entity, _)| *entity)
+    }
+}
@@ -0,0 +1,13 @@
+use thomas::Component;
+
+use crate::{Combo, TickTimer};
+
+/// Keeps the combo of the run being played.
+#[derive(Component)]
+pub struct ComboTracker {
+    pub combo: Combo,
+    /// Restarts with every bonus. The combo decays each time it runs out.
+    pub decay_timer: TickTimer,
+    /// Whether the player has jumped in the air since they last landed, and hasn't been hit since.
+    pub is_double_jump_pending: bool,
+}
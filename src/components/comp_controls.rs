@@ -0,0 +1,125 @@
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use thomas::Component;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Action {
+    Jump,
+    Slide,
+    Pause,
+    Restart,
+    Left,
+    Right,
+    Shop,
+    /// Set whenever any key at all is pressed.
+    AnyKey,
+}
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::Jump,
+        Action::Slide,
+        Action::Pause,
+        Action::Restart,
+        Action::Left,
+        Action::Right,
+        Action::Shop,
+        Action::AnyKey,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Jump => "jump",
+            Action::Slide => "slide",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Shop => "shop",
+            Action::AnyKey => "any-key",
+        }
+    }
+}
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| format!("'{}' isn't an action", s))
+    }
+}
+
+/// The actions being performed on the current tick of the game's clock.
+///
+/// Keys are read once a frame, but the clock can tick more or less than once a frame, so the keys held on each frame
+/// are kept until the next tick. That way a key that's tapped on a frame without a tick still counts.
+#[derive(Component)]
+pub struct Controls {
+    pressed_actions: HashSet<Action>,
+    prev_pressed_actions: HashSet<Action>,
+    held_actions: HashSet<Action>,
+    /// Everything held on a frame since the last tick.
+    pending_actions: HashSet<Action>,
+}
+impl Controls {
+    pub fn new() -> Self {
+        Self {
+            pressed_actions: HashSet::new(),
+            prev_pressed_actions: HashSet::new(),
+            held_actions: HashSet::new(),
+            pending_actions: HashSet::new(),
+        }
+    }
+
+    /// Whether the action started this tick.
+    pub fn is_action_down(&self, action: &Action) -> bool {
+        self.pressed_actions.contains(action) && !self.prev_pressed_actions.contains(action)
+    }
+
+    /// Whether the action is being performed. This will be `true` on every tick the action is held.
+    pub fn is_action_pressed(&self, action: &Action) -> bool {
+        self.pressed_actions.contains(action)
+    }
+
+    /// Every action being performed this tick, in a stable order.
+    pub fn pressed_actions(&self) -> Vec<Action> {
+        let mut pressed_actions: Vec<Action> = self.pressed_actions.iter().copied().collect();
+        pressed_actions.sort();
+
+        pressed_actions
+    }
+
+    /// Moves on to the next tick with exactly the provided actions being performed.
+    pub fn update(&mut self, pressed_actions: impl IntoIterator<Item = Action>) {
+        self.prev_pressed_actions = std::mem::take(&mut self.pressed_actions);
+        self.pressed_actions = pressed_actions.into_iter().collect();
+    }
+
+    /// Sets the actions being held on the current frame. They're performed on the next tick.
+    pub fn hold(&mut self, held_actions: impl IntoIterator<Item = Action>) {
+        self.held_actions = held_actions.into_iter().collect();
+        self.pending_actions.extend(&self.held_actions);
+    }
+
+    /// Moves on to the next tick with everything held since the last one being performed.
+    pub fn advance(&mut self) {
+        let pressed_actions: HashSet<Action> = self
+            .pending_actions
+            .drain()
+            .chain(self.held_actions.iter().copied())
+            .collect();
+
+        self.update(pressed_actions);
+    }
+}
+impl Default for Controls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,13 @@
+use thomas::Component;
+
+use crate::TickTimer;
+
+/// An enemy that hovers near the right edge of the screen for a while, firing at the player.
+#[derive(Component)]
+pub struct Enemy {
+    /// Counts up from when the enemy appeared. It leaves once it's been around for `ENEMY_STAY_TICKS`.
+    pub timer: TickTimer,
+    pub shot_timer: TickTimer,
+    /// The height the enemy's lined up with while it winds up a shot.
+    pub lane_option: Option<i64>,
+}
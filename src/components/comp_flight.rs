@@ -0,0 +1,11 @@
+use thomas::Component;
+
+use crate::{FlightPath, TickTimer};
+
+/// How a moving air obstacle goes up and down on its way in.
+#[derive(Component)]
+pub struct Flight {
+    pub path: FlightPath,
+    /// Counts up from when the obstacle was spawned.
+    pub timer: TickTimer,
+}
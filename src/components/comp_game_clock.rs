@@ -0,0 +1,10 @@
+use thomas::{Component, Timer};
+
+/// Keeps the game's clock ticking `TICKS_PER_SECOND` times a second of real time, however many frames are drawn in
+/// that time.
+#[derive(Component)]
+pub struct GameClock {
+    pub timer: Timer,
+    /// How many ticks the clock has run since `timer` started, including any it skipped.
+    pub ticks_run: u64,
+}
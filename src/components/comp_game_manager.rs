@@ -1,21 +1,41 @@
-use thomas::{Component, Timer};
+use thomas::Component;
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum GameState {
     WaitingToStart,
     Playing,
     Paused,
     Victory,
     Defeat,
+    /// Looking around the shop, between runs.
+    Shopping,
 }
 
 #[derive(Component)]
 pub struct GameManager {
-    pub camera_scroll_timer: Timer,
+    /// How far the player has made it, plus any bonus points.
     pub score: u64,
+    /// The points on top of the distance the player has made it.
+    pub bonus_score: u64,
+    /// How many coins the player has picked up this run.
+    pub coins: u64,
     pub game_state: GameState,
+    /// How many milestones an endless run has passed.
+    pub milestones_reached: u64,
 }
 impl GameManager {
+    /// How far the player has made it. The game gets harder with this rather than the score, so bonus points don't
+    /// speed it up.
+    pub fn distance(&self) -> u64 {
+        self.score - self.bonus_score
+    }
+
+    /// Adds points on top of the distance the player has made it.
+    pub fn add_bonus_score(&mut self, points: u64) {
+        self.score += points;
+        self.bonus_score += points;
+    }
+
     pub fn is_waiting_to_start(&self) -> bool {
         self.game_state == GameState::WaitingToStart
     }
@@ -28,11 +48,12 @@ impl GameManager {
         self.game_state == GameState::Paused
     }
 
+    pub fn is_shopping(&self) -> bool {
+        self.game_state == GameState::Shopping
+    }
+
     /// Game over is when the game state is either Victory or Defeat.
     pub fn is_game_over(&self) -> bool {
-        match self.game_state {
-            GameState::Victory | GameState::Defeat => true,
-            _ => false,
-        }
+        matches!(self.game_state, GameState::Victory | GameState::Defeat)
     }
 }
@@ -1,9 +1,42 @@
-use thomas::{Timer, Component};
+use rand::Rng;
+use thomas::Component;
+
+use crate::{components::PickupKind, TickTimer};
 
 #[derive(Component)]
 pub struct GameObjectManager {
-  pub obstacle_generation_timer: Timer,
-  pub extra_life_generation_timer: Timer,
-  pub next_obstacle_wait_time: u128,
-  pub scroll_timer: Timer,
-}
\ No newline at end of file
+    pub obstacle_generation_timer: TickTimer,
+    pub pickup_generation_timer: TickTimer,
+    pub coin_generation_timer: TickTimer,
+    pub enemy_generation_timer: TickTimer,
+    pub next_obstacle_wait_ticks: u64,
+    /// The ID the next obstacle spawned gets.
+    pub next_obstacle_id: u64,
+    /// How likely each kind of pickup is to be the one that appears, relative to the others.
+    pub pickup_spawn_weights: Vec<(PickupKind, u32)>,
+}
+impl GameObjectManager {
+    /// Picks which kind of pickup appears next, by weighted roll.
+    pub fn pick_pickup_kind(&self, rng: &mut impl Rng) -> PickupKind {
+        let total_weight: u32 = self
+            .pickup_spawn_weights
+            .iter()
+            .map(|(_, weight)| weight)
+            .sum();
+
+        let mut roll = rng.gen_range(0..total_weight);
+
+        self.pickup_spawn_weights
+            .iter()
+            .find(|(_, weight)| {
+                if roll < *weight {
+                    true
+                } else {
+                    roll -= weight;
+                    false
+                }
+            })
+            .map(|(kind, _)| *kind)
+            .expect("The roll is less than the total weight of the pickups.")
+    }
+}
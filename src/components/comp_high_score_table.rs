@@ -0,0 +1,15 @@
+use std::path::PathBuf;
+
+use thomas::Component;
+
+use crate::HighScores;
+
+/// The high scores as they stand this session, along with where they're kept between sessions.
+#[derive(Component)]
+pub struct HighScoreTable {
+    pub high_scores: HighScores,
+    /// High scores are only kept for this session when there isn't a path.
+    pub path_option: Option<PathBuf>,
+    /// Where the last finished run placed on the table, if it made it on.
+    pub latest_place_option: Option<usize>,
+}
@@ -1,7 +1,10 @@
-use thomas::{Timer, Component};
+use thomas::Component;
+
+use crate::TickTimer;
 
 #[derive(Component)]
 pub struct Moveable {
-    pub move_timer: Timer,
-    pub move_interval: u128,
+    pub move_timer: TickTimer,
+    /// How many ticks pass between each move.
+    pub move_interval: u64,
 }
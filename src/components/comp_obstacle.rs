@@ -0,0 +1,14 @@
+use thomas::Component;
+
+use crate::ObstacleType;
+
+#[derive(Component)]
+pub struct Obstacle {
+    /// Shared by every cell of the same obstacle.
+    pub obstacle_id: u64,
+    pub obstacle_type: ObstacleType,
+    /// Whether the player has made it past the cell without hitting the obstacle.
+    pub is_dodged: bool,
+    /// Whether the cell has come within a cell of the player.
+    pub is_near_missed: bool,
+}
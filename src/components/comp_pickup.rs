@@ -0,0 +1,73 @@
+use thomas::{Component, Rgb};
+
+use crate::PLAYER_LIFE_DISPLAY;
+
+/// What a pickup does for the player once they run into it.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PickupKind {
+    ExtraLife,
+    /// Takes the next hit in place of a life.
+    Shield,
+    /// Pulls the pickups around the player in towards them.
+    Magnet,
+    /// Makes game time pass at half speed.
+    SlowMotion,
+    ScoreMultiplier,
+    /// Lets the player jump once more than usual before they land.
+    ExtraAirJump,
+}
+impl PickupKind {
+    pub const ALL: [PickupKind; 6] = [
+        PickupKind::ExtraLife,
+        PickupKind::Shield,
+        PickupKind::Magnet,
+        PickupKind::SlowMotion,
+        PickupKind::ScoreMultiplier,
+        PickupKind::ExtraAirJump,
+    ];
+
+    /// The name as it's shown in the HUD.
+    pub fn title(&self) -> &'static str {
+        match self {
+            PickupKind::ExtraLife => "Life",
+            PickupKind::Shield => "Shield",
+            PickupKind::Magnet => "Magnet",
+            PickupKind::SlowMotion => "Slow-mo",
+            PickupKind::ScoreMultiplier => "Score x2",
+            PickupKind::ExtraAirJump => "Air jump",
+        }
+    }
+
+    pub fn display(&self) -> char {
+        match self {
+            PickupKind::ExtraLife => PLAYER_LIFE_DISPLAY,
+            PickupKind::Shield => '♦',
+            PickupKind::Magnet => '∩',
+            PickupKind::SlowMotion => '≈',
+            PickupKind::ScoreMultiplier => '×',
+            PickupKind::ExtraAirJump => '↑',
+        }
+    }
+
+    pub fn color(&self) -> Rgb {
+        match self {
+            PickupKind::ExtraLife => Rgb(232, 23, 255),
+            PickupKind::Shield => Rgb(80, 200, 255),
+            PickupKind::Magnet => Rgb(255, 120, 40),
+            PickupKind::SlowMotion => Rgb(120, 255, 200),
+            PickupKind::ScoreMultiplier => Rgb(255, 215, 0),
+            PickupKind::ExtraAirJump => Rgb(160, 255, 80),
+        }
+    }
+
+    /// Whether the pickup keeps working for a while as a power-up, rather than being used up as soon as it's picked
+    /// up.
+    pub fn is_power_up(&self) -> bool {
+        *self != PickupKind::ExtraLife
+    }
+}
+
+#[derive(Component)]
+pub struct Pickup {
+    pub kind: PickupKind,
+}
@@ -0,0 +1,13 @@
+use thomas::Component;
+
+/// A gap in the ground, starting at the pit's transform and running `width` cells to the right.
+#[derive(Component)]
+pub struct Pit {
+    pub width: u64,
+}
+impl Pit {
+    /// Whether the pit starting at `pit_x` is under `x`.
+    pub fn is_under(&self, pit_x: i64, x: i64) -> bool {
+        x >= pit_x && x < pit_x + self.width as i64
+    }
+}
@@ -1,13 +1,42 @@
-use thomas::{Component, Timer};
+use thomas::Component;
+
+use crate::{PlayerMotion, TickTimer, PLAYER_BLINK_INTERVAL_TICKS};
 
 #[derive(Component)]
 pub struct Player {
-    pub jump_timer: Timer,
-    pub num_times_jumped_since_landing: u8,
-    pub gravity_timer :Timer,
-    pub velocity_timer: Timer,
-    pub vertical_velocity: i64,
+    pub motion: PlayerMotion,
+    /// Whether jump was held down on the last tick. Letting go of it cuts a jump short.
+    pub is_jump_held: bool,
     pub is_on_ground: bool,
     pub distance_traveled: u64,
     pub lives: u8,
+    /// The most lives the player can have, which they start each run with.
+    pub max_lives: u8,
+    /// How many more times the player can jump before they land again, not counting any power-ups.
+    pub max_air_jumps: u8,
+    /// Whether the player has just lost a life, and can't be hit again until the invulnerability timer runs out.
+    pub is_invulnerable: bool,
+    pub invulnerability_timer: TickTimer,
+}
+impl Player {
+    /// Takes a life, and keeps the player from losing another for a while.
+    pub fn lose_life(&mut self) {
+        self.lives = self.lives.saturating_sub(1);
+        self.start_invulnerability();
+    }
+
+    pub fn start_invulnerability(&mut self) {
+        self.is_invulnerable = true;
+        self.invulnerability_timer.restart();
+    }
+
+    /// Whether the player is hidden at this point in the blinking they do while they're invulnerable.
+    pub fn is_blinked_out(&self) -> bool {
+        self.is_invulnerable
+            && (self.invulnerability_timer.elapsed_ticks() / PLAYER_BLINK_INTERVAL_TICKS) % 2 == 1
+    }
 }
+
+/// The cell above the player, which goes everywhere they go and gets hit the same way, except while they slide.
+#[derive(Component)]
+pub struct PlayerHead {}
@@ -0,0 +1,58 @@
+use thomas::Component;
+
+use crate::{components::PickupKind, TickTimer};
+
+/// A power-up the player has picked up, and how long it's been going for.
+#[derive(Clone, Debug)]
+pub struct ActivePowerUp {
+    pub kind: PickupKind,
+    pub timer: TickTimer,
+}
+
+/// The power-ups the player has going. Each lasts until its timer runs out, though a shield is also used up by taking a
+/// hit.
+#[derive(Component)]
+pub struct PowerUps {
+    pub active: Vec<ActivePowerUp>,
+    /// How many ticks each power-up lasts.
+    pub duration_ticks: u64,
+    /// Whether game time is standing still this tick, as it does every other tick in slow motion.
+    pub is_tick_skipped: bool,
+}
+impl PowerUps {
+    pub fn new(duration_ticks: u64) -> Self {
+        Self {
+            active: vec![],
+            duration_ticks,
+            is_tick_skipped: false,
+        }
+    }
+
+    /// Starts the power-up, or starts it over if it's already going.
+    pub fn activate(&mut self, kind: PickupKind) {
+        match self
+            .active
+            .iter_mut()
+            .find(|power_up| power_up.kind == kind)
+        {
+            Some(power_up) => power_up.timer.restart(),
+            None => self.active.push(ActivePowerUp {
+                kind,
+                timer: TickTimer::start_new(),
+            }),
+        }
+    }
+
+    pub fn deactivate(&mut self, kind: PickupKind) {
+        self.active.retain(|power_up| power_up.kind != kind);
+    }
+
+    pub fn is_active(&self, kind: PickupKind) -> bool {
+        self.active.iter().any(|power_up| power_up.kind == kind)
+    }
+
+    /// Whether game time will stand still on the next tick.
+    pub fn is_next_tick_skipped(&self) -> bool {
+        self.is_active(PickupKind::SlowMotion) && !self.is_tick_skipped
+    }
+}
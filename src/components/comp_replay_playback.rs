@@ -0,0 +1,10 @@
+use thomas::Component;
+
+use crate::Replay;
+
+/// Marks the controls as being driven by a replay rather than the keyboard.
+#[derive(Component)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub ticks_played: u64,
+}
@@ -0,0 +1,11 @@
+use thomas::{Component, Timer};
+
+use crate::RunStats;
+
+/// Keeps the stats of the run being played.
+#[derive(Component)]
+pub struct RunStatsTracker {
+    pub stats: RunStats,
+    /// Runs while the game is paused.
+    pub pause_timer: Timer,
+}
@@ -0,0 +1,12 @@
+use thomas::Component;
+
+use crate::components::GameState;
+
+/// Where the player is in the shop, and which screen they came to it from.
+#[derive(Component)]
+pub struct Shop {
+    /// Which of the upgrades is picked, out of all of them in order.
+    pub selected_index: usize,
+    /// The state the game goes back to once the shop is closed.
+    pub return_state: GameState,
+}
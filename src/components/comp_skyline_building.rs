@@ -1,6 +1,6 @@
-use thomas::{Component};
+use thomas::Component;
 
 #[derive(Component)]
 pub struct SkylineBuilding {
-  pub last_distance_scrolled: u64,
+    pub last_distance_scrolled: u64,
 }
@@ -0,0 +1,57 @@
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use thomas::Component;
+
+/// The single source of randomness in the world. Everything random in a run is rolled from here so that a run can be
+/// reproduced from its seed.
+#[derive(Component)]
+pub struct WorldRng {
+    seed: u64,
+    /// Whether every run is generated from the same seed, rather than each run after the first getting a new one.
+    is_seed_fixed: bool,
+    generator: StdRng,
+}
+impl WorldRng {
+    pub fn new(seed: u64, is_seed_fixed: bool) -> Self {
+        Self {
+            seed,
+            is_seed_fixed,
+            generator: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Rewinds the generator to the start of its seed, so the rolls that follow are the same ones as the first time.
+    pub fn reseed(&mut self) {
+        self.generator = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Gets the generator ready for another run. With a fixed seed it's rewound just like `reseed`. Otherwise the next
+    /// seed is rolled from the current one, so a run after the first still plays back the same from the first seed.
+    pub fn reseed_for_next_run(&mut self) {
+        if !self.is_seed_fixed {
+            self.seed = self.generator.next_u64();
+        }
+
+        self.reseed();
+    }
+}
+impl RngCore for WorldRng {
+    fn next_u32(&mut self) -> u32 {
+        self.generator.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.generator.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generator.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.generator.try_fill_bytes(dest)
+    }
+}
@@ -2,9 +2,13 @@ use std::collections::HashMap;
 
 use thomas::{Component, Timer};
 
+use crate::TickTimer;
+
 pub const NOON_TIME: u8 = 12;
 pub const SUNRISE_TIME: u8 = 5;
 pub const SUNSET_TIME: u8 = 19;
+/// The hour every run starts at, so the windows lighting up and going dark roll the same way each time.
+pub const RUN_START_TIME: u8 = 9;
 
 #[derive(PartialEq, Eq, Hash)]
 pub enum TimeOfDay {
@@ -15,11 +19,25 @@ pub enum TimeOfDay {
     Afternoon,
     Dusk,
 }
+impl TimeOfDay {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeOfDay::Night => "night",
+            TimeOfDay::Dawn => "dawn",
+            TimeOfDay::Morning => "morning",
+            TimeOfDay::Noon => "noon",
+            TimeOfDay::Afternoon => "afternoon",
+            TimeOfDay::Dusk => "dusk",
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct WorldTime {
     pub current_time: u8,
-    pub advance_time_timer: Timer,
+    pub advance_time_timer: TickTimer,
+    /// How long it's been since windows last went dark.
+    pub window_timer: TickTimer,
     pub color_transition_timers: HashMap<&'static str, Timer>,
 }
 impl WorldTime {
@@ -39,11 +57,9 @@ impl WorldTime {
     }
 
     pub fn is_light(&self) -> bool {
-        match self.time_of_day() {
-            TimeOfDay::Morning | TimeOfDay::Noon | TimeOfDay::Afternoon => {
-                true
-            }
-            _ => false,
-        }
+        matches!(
+            self.time_of_day(),
+            TimeOfDay::Morning | TimeOfDay::Noon | TimeOfDay::Afternoon
+        )
     }
 }
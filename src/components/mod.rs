@@ -21,3 +21,57 @@ pub use comp_cleanup_on_screen_exit::*;
 
 mod comp_moveable;
 pub use comp_moveable::*;
+
+mod comp_flight;
+pub use comp_flight::*;
+
+mod comp_controls;
+pub use comp_controls::*;
+
+mod comp_world_rng;
+pub use comp_world_rng::*;
+
+mod comp_replay_playback;
+pub use comp_replay_playback::*;
+
+mod comp_high_score_table;
+pub use comp_high_score_table::*;
+
+mod comp_obstacle;
+pub use comp_obstacle::*;
+
+mod comp_pit;
+pub use comp_pit::*;
+
+mod comp_run_stats_tracker;
+pub use comp_run_stats_tracker::*;
+
+mod comp_pickup;
+pub use comp_pickup::*;
+
+mod comp_power_ups;
+pub use comp_power_ups::*;
+
+mod comp_coin;
+pub use comp_coin::*;
+
+mod comp_bank;
+pub use comp_bank::*;
+
+mod comp_shop;
+pub use comp_shop::*;
+
+mod comp_combo_tracker;
+pub use comp_combo_tracker::*;
+
+mod comp_bonus_popup;
+pub use comp_bonus_popup::*;
+
+mod comp_enemy;
+pub use comp_enemy::*;
+
+mod comp_collision;
+pub use comp_collision::*;
+
+mod comp_game_clock;
+pub use comp_game_clock::*;
@@ -0,0 +1,536 @@
+use std::{fmt, fs, io, ops::RangeInclusive, path::Path, str::FromStr};
+
+use thomas::Component;
+
+use crate::{scale_wait_ticks, DifficultyCurve, DifficultyStage};
+
+/// The most air jumps a config can give the player. Each one is another way the fairness check has to try getting past
+/// every obstacle, and past a handful the player can stay up in the air for good anyway.
+///
+/// This only limits the config. The extra air jump upgrade and power-up can take the player past it, but the fairness
+/// check only plans with the config's own air jumps, so it's never slowed down by them.
+const MAX_AIR_JUMPS: u8 = 5;
+
+/// The values that tune how the game plays. They can be loaded from a config file so the game can be tuned without
+/// recompiling it.
+///
+/// Config files have one `key = value` setting per line. Blank lines and lines starting with `#` are ignored, and
+/// anything that isn't set keeps the value it had before the file was applied:
+///
+/// ```text
+/// # Floatier jumps.
+/// gravity = 10
+/// jump_force = 40
+/// ```
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct GameConfig {
+    /// How quickly the player's fall speeds up, in cells per second every second.
+    pub gravity: u8,
+    /// How fast the player starts rising when they jump, in cells per second.
+    pub jump_force: u8,
+    /// The fastest the player keeps rising once they let go of jump, in cells per second. Letting go early makes for a
+    /// shorter jump.
+    pub short_jump_force: u8,
+    /// How many more times the player can jump before they land again, up to `MAX_AIR_JUMPS`.
+    pub max_air_jumps: u8,
+    /// How many ticks a slide lasts.
+    pub slide_ticks: u64,
+    pub max_lives: u8,
+    /// How many ticks the player can't lose another life for after losing one.
+    pub invulnerability_ticks: u64,
+    /// How many ticks pass between each step the camera scrolls.
+    pub camera_scroll_wait_ticks: u64,
+    pub obstacle_wait_ticks_min: u64,
+    pub obstacle_wait_ticks_max: u64,
+    /// The fewest ticks that pass between each step an air obstacle moves towards the player.
+    pub air_obstacle_move_interval_ticks_min: u64,
+    pub air_obstacle_move_interval_ticks_max: u64,
+    /// How many ticks pass between each chance of a pickup appearing.
+    pub pickup_wait_ticks: u64,
+    /// The percent chance of a pickup appearing each time there's a chance for one.
+    pub pickup_chance: u8,
+    /// How many ticks a power-up lasts once it's picked up.
+    pub power_up_ticks: u64,
+    /// How many ticks pass between each chance of coins appearing.
+    pub coin_wait_ticks: u64,
+    /// The percent chance of coins appearing each time there's a chance for them.
+    pub coin_chance: u8,
+    /// How many ticks pass between each chance of an enemy appearing.
+    pub enemy_wait_ticks: u64,
+    /// The percent chance of an enemy appearing each time there's a chance for one, as long as there isn't one out
+    /// already.
+    pub enemy_chance: u8,
+    /// The score that wins the run. In endless mode, a milestone is reached each time the score goes up by this much
+    /// instead.
+    pub victory_score: u64,
+    /// How the settings above get harder as the score grows.
+    pub difficulty_curve: DifficultyCurve,
+    pub mode: GameMode,
+}
+impl GameConfig {
+    /// Reads a config file and applies its settings on top of this config.
+    pub fn apply_file(&mut self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+
+        self.apply(&contents)
+    }
+
+    /// Applies the settings in the text of a config file on top of this config. The config is left as it was if
+    /// there's anything wrong with the settings.
+    pub fn apply(&mut self, contents: &str) -> Result<(), ConfigError> {
+        let mut config = self.clone();
+
+        for (line_index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parse_error = |message: String| ConfigError::Parse {
+                line_number: line_index + 1,
+                message,
+            };
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| parse_error(format!("expected 'key = value', got '{}'", line)))?;
+
+            config.set(key.trim(), value.trim()).map_err(parse_error)?;
+        }
+
+        config.validate()?;
+
+        *self = config;
+
+        Ok(())
+    }
+
+    /// Sets the value of the setting called `key` from its text form.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "gravity" => self.gravity = parse_value(key, value)?,
+            "jump_force" => self.jump_force = parse_value(key, value)?,
+            "short_jump_force" => self.short_jump_force = parse_value(key, value)?,
+            "max_air_jumps" => self.max_air_jumps = parse_value(key, value)?,
+            "slide_ticks" => self.slide_ticks = parse_value(key, value)?,
+            "max_lives" => self.max_lives = parse_value(key, value)?,
+            "invulnerability_ticks" => self.invulnerability_ticks = parse_value(key, value)?,
+            "camera_scroll_wait_ticks" => self.camera_scroll_wait_ticks = parse_value(key, value)?,
+            "obstacle_wait_ticks_min" => self.obstacle_wait_ticks_min = parse_value(key, value)?,
+            "obstacle_wait_ticks_max" => self.obstacle_wait_ticks_max = parse_value(key, value)?,
+            "air_obstacle_move_interval_ticks_min" => {
+                self.air_obstacle_move_interval_ticks_min = parse_value(key, value)?
+            }
+            "air_obstacle_move_interval_ticks_max" => {
+                self.air_obstacle_move_interval_ticks_max = parse_value(key, value)?
+            }
+            "pickup_wait_ticks" => self.pickup_wait_ticks = parse_value(key, value)?,
+            "pickup_chance" => self.pickup_chance = parse_value(key, value)?,
+            "power_up_ticks" => self.power_up_ticks = parse_value(key, value)?,
+            "coin_wait_ticks" => self.coin_wait_ticks = parse_value(key, value)?,
+            "coin_chance" => self.coin_chance = parse_value(key, value)?,
+            "enemy_wait_ticks" => self.enemy_wait_ticks = parse_value(key, value)?,
+            "enemy_chance" => self.enemy_chance = parse_value(key, value)?,
+            "victory_score" => self.victory_score = parse_value(key, value)?,
+            "difficulty_curve" => {
+                self.difficulty_curve = value
+                    .parse()
+                    .map_err(|e| format!("invalid difficulty_curve: {}", e))?
+            }
+            "mode" => self.mode = value.parse()?,
+            _ => return Err(format!("'{}' isn't a setting", key)),
+        }
+
+        Ok(())
+    }
+
+    /// Every setting's key and value in text form, in the same form `set` takes them.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("gravity", self.gravity.to_string()),
+            ("jump_force", self.jump_force.to_string()),
+            ("short_jump_force", self.short_jump_force.to_string()),
+            ("max_air_jumps", self.max_air_jumps.to_string()),
+            ("slide_ticks", self.slide_ticks.to_string()),
+            ("max_lives", self.max_lives.to_string()),
+            (
+                "invulnerability_ticks",
+                self.invulnerability_ticks.to_string(),
+            ),
+            (
+                "camera_scroll_wait_ticks",
+                self.camera_scroll_wait_ticks.to_string(),
+            ),
+            (
+                "obstacle_wait_ticks_min",
+                self.obstacle_wait_ticks_min.to_string(),
+            ),
+            (
+                "obstacle_wait_ticks_max",
+                self.obstacle_wait_ticks_max.to_string(),
+            ),
+            (
+                "air_obstacle_move_interval_ticks_min",
+                self.air_obstacle_move_interval_ticks_min.to_string(),
+            ),
+            (
+                "air_obstacle_move_interval_ticks_max",
+                self.air_obstacle_move_interval_ticks_max.to_string(),
+            ),
+            ("pickup_wait_ticks", self.pickup_wait_ticks.to_string()),
+            ("pickup_chance", self.pickup_chance.to_string()),
+            ("power_up_ticks", self.power_up_ticks.to_string()),
+            ("coin_wait_ticks", self.coin_wait_ticks.to_string()),
+            ("coin_chance", self.coin_chance.to_string()),
+            ("enemy_wait_ticks", self.enemy_wait_ticks.to_string()),
+            ("enemy_chance", self.enemy_chance.to_string()),
+            ("victory_score", self.victory_score.to_string()),
+            ("difficulty_curve", self.difficulty_curve.to_string()),
+            ("mode", self.mode.to_string()),
+        ]
+    }
+
+    /// Checks that the settings make for a playable game.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = vec![];
+
+        if self.gravity == 0 {
+            problems.push(String::from("gravity must be greater than 0"));
+        }
+        if self.jump_force == 0 {
+            problems.push(String::from("jump_force must be greater than 0"));
+        }
+        if self.short_jump_force > self.jump_force {
+            problems.push(String::from(
+                "short_jump_force can't be greater than jump_force",
+            ));
+        }
+        if self.max_air_jumps > MAX_AIR_JUMPS {
+            problems.push(format!(
+                "max_air_jumps can't be greater than {}",
+                MAX_AIR_JUMPS
+            ));
+        }
+        if self.slide_ticks == 0 {
+            problems.push(String::from("slide_ticks must be greater than 0"));
+        }
+        if self.max_lives == 0 {
+            problems.push(String::from("max_lives must be greater than 0"));
+        }
+        if self.camera_scroll_wait_ticks == 0 {
+            problems.push(String::from(
+                "camera_scroll_wait_ticks must be greater than 0",
+            ));
+        }
+        if self.obstacle_wait_ticks_min == 0 {
+            problems.push(String::from(
+                "obstacle_wait_ticks_min must be greater than 0",
+            ));
+        }
+        if self.obstacle_wait_ticks_min > self.obstacle_wait_ticks_max {
+            problems.push(String::from(
+                "obstacle_wait_ticks_min can't be greater than obstacle_wait_ticks_max",
+            ));
+        }
+        if self.air_obstacle_move_interval_ticks_min == 0 {
+            problems.push(String::from(
+                "air_obstacle_move_interval_ticks_min must be greater than 0",
+            ));
+        }
+        if self.air_obstacle_move_interval_ticks_min > self.air_obstacle_move_interval_ticks_max {
+            problems.push(String::from(
+                "air_obstacle_move_interval_ticks_min can't be greater than air_obstacle_move_interval_ticks_max",
+            ));
+        }
+        if self.pickup_wait_ticks == 0 {
+            problems.push(String::from("pickup_wait_ticks must be greater than 0"));
+        }
+        if self.pickup_chance > 100 {
+            problems.push(String::from(
+                "pickup_chance is a percentage, so it can't be greater than 100",
+            ));
+        }
+        if self.power_up_ticks == 0 {
+            problems.push(String::from("power_up_ticks must be greater than 0"));
+        }
+        if self.coin_wait_ticks == 0 {
+            problems.push(String::from("coin_wait_ticks must be greater than 0"));
+        }
+        if self.coin_chance > 100 {
+            problems.push(String::from(
+                "coin_chance is a percentage, so it can't be greater than 100",
+            ));
+        }
+        if self.enemy_wait_ticks == 0 {
+            problems.push(String::from("enemy_wait_ticks must be greater than 0"));
+        }
+        if self.enemy_chance > 100 {
+            problems.push(String::from(
+                "enemy_chance is a percentage, so it can't be greater than 100",
+            ));
+        }
+        if self.victory_score == 0 {
+            problems.push(String::from("victory_score must be greater than 0"));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+
+    /// How hard the game is at `score`. Endless runs keep getting harder past the end of the curve.
+    fn difficulty_at(&self, score: u64) -> DifficultyStage {
+        match self.mode {
+            GameMode::Classic => self.difficulty_curve.at(score),
+            GameMode::Endless => self.difficulty_curve.extended_at(score),
+        }
+    }
+
+    /// How many ticks pass between each step the camera scrolls once the score reaches `score`.
+    pub fn camera_scroll_wait_ticks(&self, score: u64) -> u64 {
+        scale_wait_ticks(
+            self.camera_scroll_wait_ticks,
+            self.difficulty_at(score).scroll_speed_percent,
+        )
+    }
+
+    pub fn obstacle_wait_ticks_range(&self, score: u64) -> RangeInclusive<u64> {
+        let spawn_rate_percent = self.difficulty_at(score).spawn_rate_percent;
+
+        scale_wait_ticks(self.obstacle_wait_ticks_min, spawn_rate_percent)
+            ..=scale_wait_ticks(self.obstacle_wait_ticks_max, spawn_rate_percent)
+    }
+
+    pub fn air_obstacle_move_interval_range(&self, score: u64) -> RangeInclusive<u64> {
+        let air_obstacle_speed_percent = self.difficulty_at(score).air_obstacle_speed_percent;
+
+        scale_wait_ticks(
+            self.air_obstacle_move_interval_ticks_min,
+            air_obstacle_speed_percent,
+        )
+            ..=scale_wait_ticks(
+                self.air_obstacle_move_interval_ticks_max,
+                air_obstacle_speed_percent,
+            )
+    }
+
+    /// How far the game is towards the end of its difficulty curve once the score reaches `score`, as a percent.
+    pub fn difficulty_progress_percent(&self, score: u64) -> u8 {
+        self.difficulty_curve.progress_percent(score)
+    }
+
+    /// The percent chance of each obstacle being in the air once the score reaches `score`.
+    pub fn air_obstacle_chance(&self, score: u64) -> u8 {
+        self.difficulty_at(score).air_obstacle_chance
+    }
+}
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 15,
+            jump_force: 50,
+            short_jump_force: 20,
+            max_air_jumps: 1,
+            slide_ticks: 15,
+            max_lives: 3,
+            invulnerability_ticks: 45,
+            camera_scroll_wait_ticks: 3,
+            obstacle_wait_ticks_min: 8,
+            obstacle_wait_ticks_max: 90,
+            air_obstacle_move_interval_ticks_min: 9,
+            air_obstacle_move_interval_ticks_max: 24,
+            pickup_wait_ticks: 150,
+            pickup_chance: 25,
+            power_up_ticks: 300,
+            coin_wait_ticks: 90,
+            coin_chance: 50,
+            enemy_wait_ticks: 450,
+            enemy_chance: 40,
+            victory_score: 10000,
+            difficulty_curve: DifficultyCurve::default(),
+            mode: GameMode::Classic,
+        }
+    }
+}
+
+/// A preset that bundles how many lives there are, how fast the game scrolls, how often obstacles and pickups appear,
+/// and the score needed to win. Config files are applied on top of the difficulty's config.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Insane,
+}
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Insane,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+        }
+    }
+
+    /// The name as it's shown in the game.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Insane => "Insane",
+        }
+    }
+
+    /// Whether `config` is the difficulty's own, played in either mode, rather than one tuned by a config file.
+    pub fn is_own_config(&self, config: &GameConfig) -> bool {
+        *config
+            == GameConfig {
+                mode: config.mode,
+                ..self.config()
+            }
+    }
+
+    pub fn config(&self) -> GameConfig {
+        match self {
+            Difficulty::Easy => GameConfig {
+                max_lives: 5,
+                camera_scroll_wait_ticks: 4,
+                obstacle_wait_ticks_min: 15,
+                obstacle_wait_ticks_max: 100,
+                pickup_chance: 50,
+                victory_score: 5000,
+                ..GameConfig::default()
+            },
+            Difficulty::Normal => GameConfig::default(),
+            Difficulty::Hard => GameConfig {
+                max_lives: 2,
+                camera_scroll_wait_ticks: 2,
+                obstacle_wait_ticks_min: 6,
+                obstacle_wait_ticks_max: 60,
+                pickup_chance: 15,
+                victory_score: 15000,
+                ..GameConfig::default()
+            },
+            Difficulty::Insane => GameConfig {
+                max_lives: 1,
+                camera_scroll_wait_ticks: 1,
+                obstacle_wait_ticks_min: 5,
+                obstacle_wait_ticks_max: 40,
+                air_obstacle_move_interval_ticks_min: 6,
+                air_obstacle_move_interval_ticks_max: 16,
+                pickup_chance: 10,
+                victory_score: 20000,
+                ..GameConfig::default()
+            },
+        }
+    }
+}
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "'{}' isn't a difficulty, which must be one of: {}",
+                    s,
+                    Difficulty::ALL
+                        .map(|difficulty| difficulty.name())
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// How a run ends. Classic runs are won by reaching the victory score, while endless runs carry on past it, getting
+/// harder and harder until the player runs out of lives.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GameMode {
+    Classic,
+    Endless,
+}
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Classic, GameMode::Endless];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Endless => "endless",
+        }
+    }
+
+    /// How a run at `difficulty` in this mode is shown in the game.
+    pub fn title_with(&self, difficulty: Difficulty) -> String {
+        match self {
+            GameMode::Classic => String::from(difficulty.title()),
+            GameMode::Endless => format!("{} (Endless)", difficulty.title()),
+        }
+    }
+}
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+impl FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GameMode::ALL
+            .into_iter()
+            .find(|mode| mode.name() == s)
+            .ok_or_else(|| format!("'{}' isn't a mode, which must be classic or endless", s))
+    }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse::<T>().map_err(|_| {
+        format!(
+            "'{}' isn't a valid value for {}, which must be a whole number",
+            value, key
+        )
+    })
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse {
+        line_number: usize,
+        message: String,
+    },
+    /// The config was read fine, but some of its values don't make sense.
+    Invalid(Vec<String>),
+}
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "couldn't read the config: {}", error),
+            ConfigError::Parse {
+                line_number,
+                message,
+            } => write!(f, "invalid config on line {}: {}", line_number, message),
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid config: {}", problems.join("; "))
+            }
+        }
+    }
+}
+impl std::error::Error for ConfigError {}
@@ -1,36 +1,109 @@
 use thomas::{Layer, Rgb};
 
-use crate::components::TimeOfDay;
+use crate::components::{PickupKind, TimeOfDay};
 
 pub type TimeOfDayColors = [(TimeOfDay, Option<Rgb>); 6];
 
-pub const SCREEN_HEIGHT: u16 = 12;
-pub const SCREEN_WIDTH: u16 = 80;
+/// How many times the game's clock ticks in a second. The clock keeps to this however many frames are drawn each
+/// second.
+pub const TICKS_PER_SECOND: u16 = 30;
+/// The most ticks the clock runs in a single frame. After a stall, the clock skips what it's missed past this rather
+/// than racing through it.
+pub const MAX_TICKS_PER_FRAME: u64 = TICKS_PER_SECOND as u64;
+
+/// Triggered on every tick of the game's clock, in this order. Everything that plays out in game time runs on these
+/// rather than on the per-frame update events, so a run plays out the same however many frames are drawn.
+pub const EVENT_BEFORE_TICK: &str = "event-before-tick";
+pub const EVENT_TICK: &str = "event-tick";
+pub const EVENT_AFTER_TICK: &str = "event-after-tick";
 
 pub const EVENT_TIME_OF_DAY_CHANGE: &str = "event-time-change";
 pub const EVENT_GAME_PAUSE_STATE_CHANGE: &str = "event-game-pause-change";
 pub const EVENT_VICTORY: &str = "event-victory";
 pub const EVENT_DEFEAT: &str = "event-defeat";
 pub const EVENT_RESTART: &str = "event-restart";
+pub const EVENT_START: &str = "event-start";
+pub const EVENT_DIFFICULTY_CHANGE: &str = "event-difficulty-change";
+pub const EVENT_MILESTONE: &str = "event-milestone";
+pub const EVENT_SHOP_STATE_CHANGE: &str = "event-shop-state-change";
 
 pub const SKYLINE_LAYER: Layer = Layer(-2);
 pub const STAR_LAYER: Layer = Layer(-4);
 pub const SUN_LAYER: Layer = Layer(-3);
 
 pub const PLAYER_DISPLAY: char = '|';
+pub const PLAYER_HEAD_DISPLAY: char = 'o';
+pub const PLAYER_SLIDE_DISPLAY: char = '_';
 pub const PLAYER_LIFE_DISPLAY: char = '♥';
+/// How many ticks the player spends shown, and then hidden, each time they blink.
+pub const PLAYER_BLINK_INTERVAL_TICKS: u64 = 3;
+pub const PLAYER_HIT_COLOR: Rgb = Rgb(255, 80, 80);
 pub const PLAYER_COLLISION_LAYER: Layer = Layer(1);
 pub const PLAYER_X_OFFSET: i64 = 10;
 pub const PLAYER_Y_OFFSET: i64 = 2;
 
 pub const GROUND_COLLISION_LAYER: Layer = Layer(2);
+pub const GROUND_COLOR: Rgb = Rgb(94, 153, 84);
+pub const GROUND_PIECE_NAME: &str = "ground-piece";
+
+pub const PIT_NAME: &str = "pit";
+pub const PIT_COLOR: Rgb = Rgb(28, 22, 18);
 
 pub const OBSTACLE_NAME: &str = "obstacle";
 pub const OBSTACLE_BACKGROUND_COLOR: Rgb = Rgb(255, 0, 0);
 pub const OBSTACLE_COLLISION_LAYER: Layer = Layer(3);
+/// How many cells above the ground air obstacles are. It's low enough to run under, but high enough to jump into.
+pub const AIR_OBSTACLE_HEIGHT: i64 = 2;
+/// How many patterns are rolled for each spawn before giving up on it, when the player couldn't get past the ones
+/// rolled.
+pub const MAX_OBSTACLE_PATTERN_ROLLS: u8 = 10;
+
+pub const ENEMY_DISPLAY: char = 'Ж';
+pub const ENEMY_COLOR: Rgb = Rgb(190, 120, 255);
+/// The color enemies flash while they wind up a shot.
+pub const ENEMY_WIND_UP_COLOR: Rgb = Rgb(255, 220, 0);
+/// How many cells above the ground enemies hover while they aren't lined up to fire.
+pub const ENEMY_HOVER_HEIGHT: i64 = 5;
+/// How many cells in from the right edge of the screen enemies hover.
+pub const ENEMY_HOVER_INSET: i64 = 6;
+/// How many ticks it takes an enemy to fly a cell on its way in or out.
+pub const ENEMY_FLY_TICKS: u64 = 2;
+/// How many ticks an enemy sticks around for before it leaves.
+pub const ENEMY_STAY_TICKS: u64 = 300;
+/// How many ticks pass between an enemy's shots, not counting the wind-up.
+pub const ENEMY_SHOT_WAIT_TICKS: u64 = 60;
+/// How many ticks an enemy spends lined up with the player's lane, flashing, before it fires.
+pub const ENEMY_WIND_UP_TICKS: u64 = 30;
+/// How many ticks an enemy spends shown, and then flashed, while it winds up a shot.
+pub const ENEMY_FLASH_INTERVAL_TICKS: u64 = 5;
+pub const PROJECTILE_DISPLAY: char = '•';
+pub const PROJECTILE_COLOR: Rgb = Rgb(255, 140, 0);
+/// How many ticks pass between each step a projectile moves towards the player. They're slower than air obstacles.
+pub const PROJECTILE_MOVE_INTERVAL_TICKS: u64 = 12;
 
 pub const DISTANCE_MARKER_COLLISION_LAYER: Layer = Layer(4);
 
+pub const PICKUP_COLLISION_LAYER: Layer = Layer(5);
+/// How many cells above the ground pickups appear.
+pub const PICKUP_HEIGHT: i64 = 4;
+/// How likely each kind of pickup is to be the one that appears, relative to the others.
+pub const PICKUP_SPAWN_WEIGHTS: [(PickupKind, u32); 6] = [
+    (PickupKind::ExtraLife, 4),
+    (PickupKind::Shield, 2),
+    (PickupKind::Magnet, 2),
+    (PickupKind::SlowMotion, 1),
+    (PickupKind::ScoreMultiplier, 2),
+    (PickupKind::ExtraAirJump, 2),
+];
+/// How many columns either side of the player a magnet reaches.
+pub const MAGNET_RANGE: i64 = 8;
+pub const SCORE_MULTIPLIER: u64 = 2;
+
+pub const COIN_DISPLAY: char = '$';
+pub const COIN_COLOR: Rgb = Rgb(255, 200, 40);
+/// The color of upgrades in the shop that there aren't enough coins to buy, or that can't be bought any more.
+pub const UNAFFORDABLE_UPGRADE_COLOR: Rgb = Rgb(128, 128, 128);
+
 pub const STAR_NAME: &str = "star";
 pub const STAR_DISPLAY: char = '•';
 
@@ -43,6 +116,7 @@ pub const SUN_PIECE_NAME: &str = "sun-piece";
 pub const BUILDING_PIECE_NAME: &str = "building-piece";
 
 pub const DISTANCE_MARKER_PIECE_NAME: &str = "distance-marker-piece";
+pub const DISTANCE_MARKER_SPACING: u64 = 500;
 
 pub const STAR_COLORS: TimeOfDayColors = [
     (TimeOfDay::Night, Some(Rgb(219, 219, 219))),
@@ -71,18 +145,27 @@ pub const SUN_COLORS: TimeOfDayColors = [
 pub const BUILDING_COLOR: Rgb = Rgb(143, 143, 143);
 pub const ALTERNATE_BUILDING_COLOR: Rgb = Rgb(135, 135, 135);
 pub const WINDOW_COLOR: Rgb = Rgb(245, 195, 32);
+pub const NEW_BEST_COLOR: Rgb = Rgb(255, 215, 0);
+pub const MILESTONE_COLOR: Rgb = Rgb(255, 215, 0);
+pub const BONUS_POPUP_COLOR: Rgb = Rgb(120, 230, 255);
 
 pub const SKY_COLOR_TRANSITION_TIMER_NAME: &str = "sky-color";
 pub const STAR_COLOR_TRANSITION_TIMER_NAME: &str = "star-color";
 pub const SUN_COLOR_TRANSITION_TIMER_NAME: &str = "sun-color";
-pub const WINDOW_COLOR_TRANSITION_TIMER_NAME: &str = "window-color";
 
 pub const START_PLAYING_TEXT_NAME: &str = "start-playing-text";
 pub const PAUSED_TEXT_NAME: &str = "paused-text";
 pub const VICTORY_TEXT_NAME: &str = "victory-text";
 pub const DEFEAT_TEXT_NAME: &str = "defeat-text";
-
-pub const GAME_VICTORY_SCORE: u64 = 10000;
+pub const MILESTONE_TEXT_NAME: &str = "milestone-text";
+pub const SHOP_TEXT_NAME: &str = "shop-text";
+
+/// How many points the banner for reaching a milestone stays up for.
+pub const MILESTONE_TEXT_SCORE_SPAN: u64 = 200;
+/// How many ticks the points for a bonus float above where it happened.
+pub const BONUS_POPUP_TICKS: u64 = 30;
+/// How many ticks it takes the points for a bonus to float up a cell.
+pub const BONUS_POPUP_RISE_TICKS: u64 = 10;
 
 pub fn get_color<'a>(colors: &'a TimeOfDayColors, time_of_day: &TimeOfDay) -> &'a Option<Rgb> {
     &colors
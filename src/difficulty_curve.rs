@@ -0,0 +1,339 @@
+use std::{fmt, str::FromStr};
+
+/// How hard the game is once the score reaches `score`. Speeds and rates are percents of what the config sets them to,
+/// so 200 means twice as fast or twice as often.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DifficultyStage {
+    pub score: u64,
+    pub scroll_speed_percent: u64,
+    pub spawn_rate_percent: u64,
+    pub air_obstacle_speed_percent: u64,
+    /// The percent chance of each obstacle being in the air rather than on the ground.
+    pub air_obstacle_chance: u8,
+}
+impl fmt::Display for DifficultyStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}",
+            self.score,
+            self.scroll_speed_percent,
+            self.spawn_rate_percent,
+            self.air_obstacle_speed_percent,
+            self.air_obstacle_chance
+        )
+    }
+}
+impl FromStr for DifficultyStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<u64> = s
+            .trim()
+            .split(':')
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| {
+                format!(
+                    "'{}' isn't a stage, which must be 5 whole numbers",
+                    s.trim()
+                )
+            })?;
+
+        match parts[..] {
+            [score, scroll_speed_percent, spawn_rate_percent, air_obstacle_speed_percent, air_obstacle_chance] => {
+                Ok(Self {
+                    score,
+                    scroll_speed_percent,
+                    spawn_rate_percent,
+                    air_obstacle_speed_percent,
+                    air_obstacle_chance: u8::try_from(air_obstacle_chance).map_err(|_| {
+                        format!("the air obstacle chance in '{}' is too big", s.trim())
+                    })?,
+                })
+            }
+            _ => Err(format!(
+                "'{}' isn't a stage, which must be 5 whole numbers",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// How the game gets harder as the score grows. Between two stages, the difficulty ramps evenly from one to the next,
+/// and it stays at the last stage once the score gets past it.
+///
+/// Curves are written as their stages separated by commas, each stage being its score, scroll speed, spawn rate, air
+/// obstacle speed, and air obstacle chance separated by colons:
+///
+/// ```text
+/// 0:100:100:100:50,5000:150:150:120:60
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DifficultyCurve {
+    stages: Vec<DifficultyStage>,
+}
+impl DifficultyCurve {
+    /// Makes a curve out of `stages`, which must start at a score of 0 and go up in score.
+    pub fn new(stages: Vec<DifficultyStage>) -> Result<Self, String> {
+        match stages.first() {
+            None => return Err(String::from("the curve needs at least one stage")),
+            Some(first_stage) if first_stage.score != 0 => {
+                return Err(String::from(
+                    "the curve's first stage must be at a score of 0",
+                ))
+            }
+            _ => {}
+        }
+
+        if stages.windows(2).any(|pair| pair[0].score >= pair[1].score) {
+            return Err(String::from("the curve's stages must go up in score"));
+        }
+
+        for stage in &stages {
+            if stage.scroll_speed_percent == 0
+                || stage.spawn_rate_percent == 0
+                || stage.air_obstacle_speed_percent == 0
+            {
+                return Err(format!(
+                    "the speeds and rates in stage '{}' must be greater than 0",
+                    stage
+                ));
+            }
+            if stage.air_obstacle_chance > 100 {
+                return Err(format!(
+                    "the air obstacle chance in stage '{}' is a percentage, so it can't be greater than 100",
+                    stage
+                ));
+            }
+        }
+
+        Ok(Self { stages })
+    }
+
+    pub fn stages(&self) -> &[DifficultyStage] {
+        &self.stages
+    }
+
+    /// How hard the game is at `score`.
+    pub fn at(&self, score: u64) -> DifficultyStage {
+        let next_stage_index = self
+            .stages
+            .iter()
+            .position(|stage| stage.score > score)
+            .unwrap_or(self.stages.len());
+
+        let stage = self.stages[next_stage_index - 1];
+
+        match self.stages.get(next_stage_index) {
+            None => stage,
+            Some(next_stage) => {
+                let progress = score - stage.score;
+                let span = next_stage.score - stage.score;
+                let ramp = |from: u64, to: u64| {
+                    (from as i64 + (to as i64 - from as i64) * progress as i64 / span as i64) as u64
+                };
+
+                DifficultyStage {
+                    score,
+                    scroll_speed_percent: ramp(
+                        stage.scroll_speed_percent,
+                        next_stage.scroll_speed_percent,
+                    ),
+                    spawn_rate_percent: ramp(
+                        stage.spawn_rate_percent,
+                        next_stage.spawn_rate_percent,
+                    ),
+                    air_obstacle_speed_percent: ramp(
+                        stage.air_obstacle_speed_percent,
+                        next_stage.air_obstacle_speed_percent,
+                    ),
+                    air_obstacle_chance: ramp(
+                        stage.air_obstacle_chance as u64,
+                        next_stage.air_obstacle_chance as u64,
+                    ) as u8,
+                }
+            }
+        }
+    }
+
+    /// How far along the curve `score` is, as a percent of the way to its last stage. A curve with a single stage
+    /// never gets harder, so it's always at 0.
+    pub fn progress_percent(&self, score: u64) -> u8 {
+        let last_stage_score = self.stages[self.stages.len() - 1].score;
+
+        (score.min(last_stage_score) * 100)
+            .checked_div(last_stage_score)
+            .unwrap_or(0) as u8
+    }
+
+    /// How hard the game is at `score` when it never stops getting harder. Past the last stage, the game keeps
+    /// ramping at the rate it ramped between the last two stages instead of holding.
+    pub fn extended_at(&self, score: u64) -> DifficultyStage {
+        let last_stage = self.stages[self.stages.len() - 1];
+
+        let second_last_stage = match self.stages.len().checked_sub(2) {
+            Some(index) if score > last_stage.score => self.stages[index],
+            _ => return self.at(score),
+        };
+
+        let progress = score - last_stage.score;
+        let span = last_stage.score - second_last_stage.score;
+        let extend = |from: u64, to: u64| {
+            to as i64 + (to as i64 - from as i64) * progress as i64 / span as i64
+        };
+
+        DifficultyStage {
+            score,
+            scroll_speed_percent: extend(
+                second_last_stage.scroll_speed_percent,
+                last_stage.scroll_speed_percent,
+            )
+            .max(1) as u64,
+            spawn_rate_percent: extend(
+                second_last_stage.spawn_rate_percent,
+                last_stage.spawn_rate_percent,
+            )
+            .max(1) as u64,
+            air_obstacle_speed_percent: extend(
+                second_last_stage.air_obstacle_speed_percent,
+                last_stage.air_obstacle_speed_percent,
+            )
+            .max(1) as u64,
+            air_obstacle_chance: extend(
+                second_last_stage.air_obstacle_chance as u64,
+                last_stage.air_obstacle_chance as u64,
+            )
+            .clamp(0, 100) as u8,
+        }
+    }
+}
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        "0:100:100:100:50,2500:120:125:110:55,5000:140:150:120:60,7500:160:175:130:65,10000:180:200:140:70"
+            .parse()
+            .unwrap()
+    }
+}
+impl fmt::Display for DifficultyCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages: Vec<String> = self.stages.iter().map(|stage| stage.to_string()).collect();
+
+        write!(f, "{}", stages.join(","))
+    }
+}
+impl FromStr for DifficultyCurve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let stages = s
+            .split(',')
+            .map(|stage| stage.parse::<DifficultyStage>())
+            .collect::<Result<Vec<DifficultyStage>, String>>()?;
+
+        Self::new(stages)
+    }
+}
+
+/// Scales a wait between things happening by how much faster they should happen. The wait never drops below a tick, and
+/// waits too long to scale stay as long as they can be.
+pub fn scale_wait_ticks(wait_ticks: u64, speed_percent: u64) -> u64 {
+    (wait_ticks
+        .saturating_mul(100)
+        .saturating_add(speed_percent / 2)
+        / speed_percent)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve() -> DifficultyCurve {
+        "0:100:100:100:50,1000:200:150:120:60".parse().unwrap()
+    }
+
+    #[test]
+    fn curves_parse_from_their_stages() {
+        let curve = curve();
+
+        assert_eq!(
+            curve.stages(),
+            [
+                DifficultyStage {
+                    score: 0,
+                    scroll_speed_percent: 100,
+                    spawn_rate_percent: 100,
+                    air_obstacle_speed_percent: 100,
+                    air_obstacle_chance: 50,
+                },
+                DifficultyStage {
+                    score: 1000,
+                    scroll_speed_percent: 200,
+                    spawn_rate_percent: 150,
+                    air_obstacle_speed_percent: 120,
+                    air_obstacle_chance: 60,
+                },
+            ]
+        );
+        assert_eq!(curve.to_string().parse::<DifficultyCurve>(), Ok(curve));
+    }
+
+    #[test]
+    fn curves_must_start_at_0_and_go_up_in_score() {
+        assert!("100:100:100:100:50".parse::<DifficultyCurve>().is_err());
+        assert!("0:100:100:100:50,2000:120:120:120:50,1000:140:140:140:50"
+            .parse::<DifficultyCurve>()
+            .is_err());
+        assert!("0:100:100:100:50,0:120:120:120:50"
+            .parse::<DifficultyCurve>()
+            .is_err());
+        assert!("0:100:100:100".parse::<DifficultyCurve>().is_err());
+        assert!("0:100:0:100:50".parse::<DifficultyCurve>().is_err());
+        assert!("0:100:100:100:101".parse::<DifficultyCurve>().is_err());
+    }
+
+    #[test]
+    fn the_difficulty_ramps_evenly_between_stages() {
+        assert_eq!(
+            curve().at(500),
+            DifficultyStage {
+                score: 500,
+                scroll_speed_percent: 150,
+                spawn_rate_percent: 125,
+                air_obstacle_speed_percent: 110,
+                air_obstacle_chance: 55,
+            }
+        );
+        assert_eq!(curve().at(0), curve().stages()[0]);
+        assert_eq!(curve().at(1000), curve().stages()[1]);
+    }
+
+    #[test]
+    fn classic_holds_at_the_last_stage_while_endless_keeps_ramping() {
+        let held_stage = curve().at(2000);
+        assert_eq!(held_stage.scroll_speed_percent, 200);
+        assert_eq!(held_stage.air_obstacle_chance, 60);
+
+        assert_eq!(
+            curve().extended_at(2000),
+            DifficultyStage {
+                score: 2000,
+                scroll_speed_percent: 300,
+                spawn_rate_percent: 200,
+                air_obstacle_speed_percent: 140,
+                air_obstacle_chance: 70,
+            }
+        );
+        assert_eq!(curve().extended_at(500), curve().at(500));
+        assert_eq!(curve().extended_at(100_000).air_obstacle_chance, 100);
+    }
+
+    #[test]
+    fn waits_scale_down_as_things_speed_up_but_never_below_a_tick() {
+        assert_eq!(scale_wait_ticks(10, 100), 10);
+        assert_eq!(scale_wait_ticks(10, 200), 5);
+        assert_eq!(scale_wait_ticks(1, 1000), 1);
+        assert_eq!(scale_wait_ticks(u64::MAX, 100), u64::MAX / 100);
+    }
+}
@@ -0,0 +1,56 @@
+use thomas::Component;
+
+use crate::{Difficulty, GameConfig};
+
+/// The difficulties that can be picked from on the start screen, each with the config it's played with.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct DifficultyMenu {
+    choices: Vec<(Difficulty, GameConfig)>,
+    selected_index: usize,
+}
+impl DifficultyMenu {
+    /// Creates a menu of `choices` with `selected` picked. The first choice is picked if `selected` isn't one of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there aren't any choices.
+    pub fn new(choices: Vec<(Difficulty, GameConfig)>, selected: Difficulty) -> Self {
+        assert!(!choices.is_empty(), "the menu needs at least one choice");
+
+        let selected_index = choices
+            .iter()
+            .position(|(difficulty, _)| *difficulty == selected)
+            .unwrap_or(0);
+
+        Self {
+            choices,
+            selected_index,
+        }
+    }
+
+    /// A menu with nothing to pick from but `difficulty` played with `config`.
+    pub fn single(difficulty: Difficulty, config: GameConfig) -> Self {
+        Self::new(vec![(difficulty, config)], difficulty)
+    }
+
+    pub fn selected_difficulty(&self) -> Difficulty {
+        self.choices[self.selected_index].0
+    }
+
+    pub fn selected_config(&self) -> &GameConfig {
+        &self.choices[self.selected_index].1
+    }
+
+    /// Whether there's more than one difficulty to pick from.
+    pub fn has_choices(&self) -> bool {
+        self.choices.len() > 1
+    }
+
+    /// Moves the selection `steps` choices along, wrapping around at either end.
+    pub fn select_by(&mut self, steps: isize) {
+        let num_choices = self.choices.len() as isize;
+
+        self.selected_index =
+            (self.selected_index as isize + steps).rem_euclid(num_choices) as usize;
+    }
+}
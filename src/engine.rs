@@ -0,0 +1,381 @@
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    ops::Deref,
+    rc::Rc,
+};
+
+use thomas::{Component, Priority, StoredComponentList};
+
+/// The function that's given to a `System` to run against its queries' matches.
+pub type OperatorFn = dyn Fn(Vec<QueryResultList>, GameCommandsArg);
+
+pub type WherePredicate = dyn Fn(&dyn Component) -> bool;
+
+/// The game's own take on a Thomas `Query`. It builds the equivalent Thomas `Query` as it goes, but also remembers
+/// what it's asking for so it can be run against worlds other than a Thomas `Game`'s.
+pub struct Query {
+    allowed_components: Vec<(&'static str, Option<Box<WherePredicate>>)>,
+    forbidden_components: Vec<&'static str>,
+    thomas_query: thomas::Query,
+}
+impl Query {
+    pub fn new() -> Self {
+        Self {
+            allowed_components: vec![],
+            forbidden_components: vec![],
+            thomas_query: thomas::Query::new(),
+        }
+    }
+
+    /// Specifies that a matching entity must have the provided component to be a match for the query.
+    pub fn has<T: Component + 'static>(mut self) -> Self {
+        self.allowed_components.push((T::name(), None));
+        self.thomas_query = self.thomas_query.has::<T>();
+
+        self
+    }
+
+    /// Specifies that a matching entity may _not_ have the provided component to be a match for the query.
+    pub fn has_no<T: Component + 'static>(mut self) -> Self {
+        self.forbidden_components.push(T::name());
+        self.thomas_query = self.thomas_query.has_no::<T>();
+
+        self
+    }
+
+    /// Specifies that a matching entity must have the provided component _and_ the component must pass the provided
+    /// predicate to be a match for the query.
+    pub fn has_where<T>(mut self, predicate: impl Fn(&T) -> bool + 'static) -> Self
+    where
+        T: Component + 'static,
+    {
+        let predicate = Rc::new(predicate);
+        let thomas_predicate = Rc::clone(&predicate);
+
+        self.allowed_components.push((
+            T::name(),
+            Some(Box::new(move |comp| {
+                T::cast(comp).is_some_and(|comp| predicate(comp))
+            })),
+        ));
+        self.thomas_query = self
+            .thomas_query
+            .has_where::<T>(move |comp| thomas_predicate(comp));
+
+        self
+    }
+
+    pub fn allowed_component_names(&self) -> Vec<&'static str> {
+        self.allowed_components
+            .iter()
+            .map(|(component_name, _)| *component_name)
+            .collect()
+    }
+
+    pub fn forbidden_component_names(&self) -> &Vec<&'static str> {
+        &self.forbidden_components
+    }
+
+    /// Whether the component passes any predicate the query has for components of its type.
+    pub fn passes_predicates(&self, component: &dyn Component) -> bool {
+        self.allowed_components
+            .iter()
+            .filter(|(component_name, _)| *component_name == component.component_name())
+            .all(|(_, predicate_option)| {
+                predicate_option
+                    .as_ref()
+                    .is_none_or(|predicate| predicate(component))
+            })
+    }
+}
+impl Default for Query {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl From<Query> for thomas::Query {
+    fn from(query: Query) -> Self {
+        query.thomas_query
+    }
+}
+
+/// Identifies an entity in whichever world the game's systems are running against. Thomas only lets its own `Game` make
+/// `Entity`s, so the entities in a `Simulation`'s world are identified by the simulation instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(EntityKey);
+impl EntityId {
+    pub(crate) fn simulated(id: u64) -> Self {
+        Self(EntityKey::Simulated(id))
+    }
+
+    /// The simulation's ID for the entity, if it's in a simulation's world.
+    pub(crate) fn simulated_id(&self) -> Option<u64> {
+        match self.0 {
+            EntityKey::Simulated(id) => Some(id),
+            EntityKey::Thomas(_) => None,
+        }
+    }
+
+    fn thomas_entity(&self) -> thomas::Entity {
+        match self.0 {
+            EntityKey::Thomas(entity) => entity,
+            EntityKey::Simulated(id) => {
+                panic!("Entity {} of a simulation was handed to a Thomas game.", id)
+            }
+        }
+    }
+}
+impl From<thomas::Entity> for EntityId {
+    fn from(entity: thomas::Entity) -> Self {
+        Self(EntityKey::Thomas(entity))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum EntityKey {
+    Thomas(thomas::Entity),
+    Simulated(u64),
+}
+
+/// A single match from a query, just like a Thomas `QueryResult`, but for an entity in either kind of world.
+pub struct QueryResult {
+    entity: EntityId,
+    components: MatchedComponents,
+}
+impl QueryResult {
+    pub fn new(entity: EntityId, components: StoredComponentList) -> Self {
+        Self {
+            entity,
+            components: MatchedComponents::Stored(components),
+        }
+    }
+
+    pub fn entity(&self) -> &EntityId {
+        &self.entity
+    }
+
+    /// The components the query asked for. Like with Thomas, these aren't all the components on the entity.
+    pub fn components(&self) -> &StoredComponentList {
+        match &self.components {
+            MatchedComponents::Thomas(result) => result.components(),
+            MatchedComponents::Stored(components) => components,
+        }
+    }
+}
+impl From<thomas::QueryResult> for QueryResult {
+    fn from(result: thomas::QueryResult) -> Self {
+        Self {
+            entity: (*result.entity()).into(),
+            components: MatchedComponents::Thomas(result),
+        }
+    }
+}
+
+/// Thomas doesn't give up the components of a match it's made, so matches from a Thomas `Game` hold onto the whole match.
+enum MatchedComponents {
+    Thomas(thomas::QueryResult),
+    Stored(StoredComponentList),
+}
+
+/// Every match from a query, with the same conveniences as a Thomas `QueryResultList`.
+pub struct QueryResultList {
+    matches: Vec<QueryResult>,
+}
+impl QueryResultList {
+    pub fn new(matches: Vec<QueryResult>) -> Self {
+        Self { matches }
+    }
+
+    /// Gets the component from the first match, for queries that only ever match one entity.
+    ///
+    /// # Panics
+    /// If there are no matches, or the first doesn't have the component.
+    pub fn get_only<T: Component + 'static>(&self) -> Ref<'_, T> {
+        self.matches[0].components().get::<T>()
+    }
+
+    /// Like `get_only`, but provides a mutable reference.
+    ///
+    /// # Panics
+    /// If there are no matches, or the first doesn't have the component.
+    pub fn get_only_mut<T: Component + 'static>(&self) -> RefMut<'_, T> {
+        self.matches[0].components().get_mut::<T>()
+    }
+
+    /// Like `get_only`, but doesn't panic.
+    pub fn try_get_only<T: Component + 'static>(&self) -> Option<Ref<'_, T>> {
+        self.matches
+            .first()
+            .and_then(|query_match| query_match.components().try_get::<T>())
+    }
+
+    /// Like `try_get_only`, but provides a mutable reference.
+    pub fn try_get_only_mut<T: Component + 'static>(&self) -> Option<RefMut<'_, T>> {
+        self.matches
+            .first()
+            .and_then(|query_match| query_match.components().try_get_mut::<T>())
+    }
+}
+impl From<thomas::QueryResultList> for QueryResultList {
+    fn from(results: thomas::QueryResultList) -> Self {
+        Self::new(results.into_iter().map(QueryResult::from).collect())
+    }
+}
+impl Deref for QueryResultList {
+    type Target = Vec<QueryResult>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.matches
+    }
+}
+impl<'a> IntoIterator for &'a QueryResultList {
+    type Item = &'a QueryResult;
+    type IntoIter = std::slice::Iter<'a, QueryResult>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.matches.iter()
+    }
+}
+
+/// The game's own take on a Thomas `GameCommand`, naming entities by their `EntityId`.
+pub enum GameCommand {
+    Quit,
+    AddEntity(Vec<Box<dyn Component>>),
+    AddComponentsToEntity(EntityId, Vec<Box<dyn Component>>),
+    RemoveComponentFromEntity(EntityId, &'static str),
+    DestroyEntity(EntityId),
+    TriggerEvent(&'static str),
+}
+impl From<GameCommand> for thomas::GameCommand {
+    fn from(command: GameCommand) -> Self {
+        match command {
+            GameCommand::Quit => thomas::GameCommand::Quit,
+            GameCommand::AddEntity(components) => thomas::GameCommand::AddEntity(components),
+            GameCommand::AddComponentsToEntity(entity, components) => {
+                thomas::GameCommand::AddComponentsToEntity(entity.thomas_entity(), components)
+            }
+            GameCommand::RemoveComponentFromEntity(entity, component_name) => {
+                thomas::GameCommand::RemoveComponentFromEntity(
+                    entity.thomas_entity(),
+                    component_name,
+                )
+            }
+            GameCommand::DestroyEntity(entity) => {
+                thomas::GameCommand::DestroyEntity(entity.thomas_entity())
+            }
+            GameCommand::TriggerEvent(event_name) => thomas::GameCommand::TriggerEvent(event_name),
+        }
+    }
+}
+
+/// The commands systems have issued. Nothing changes in the world until they're processed after the systems have run.
+#[derive(Default)]
+pub struct GameCommandQueue {
+    queue: Vec<GameCommand>,
+}
+impl GameCommandQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn issue(&mut self, command: GameCommand) {
+        self.queue.push(command);
+    }
+}
+impl IntoIterator for GameCommandQueue {
+    type Item = GameCommand;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.queue.into_iter()
+    }
+}
+
+pub type GameCommandsArg = Rc<RefCell<GameCommandQueue>>;
+
+/// The game's own take on a Thomas `System`. Thomas doesn't expose a system's queries or operator once it's been made,
+/// so the game holds onto them itself. That lets the same systems be handed to a Thomas `Game` when playing in the
+/// terminal, or run directly by a `Simulation` when there's no terminal to play in.
+pub struct System {
+    queries: Vec<Query>,
+    operator: Box<OperatorFn>,
+    priority: Priority,
+}
+impl System {
+    pub fn new(
+        queries: Vec<Query>,
+        operator: impl Fn(Vec<QueryResultList>, GameCommandsArg) + 'static,
+    ) -> Self {
+        Self::new_with_priority(Priority::default(), queries, operator)
+    }
+
+    pub fn new_with_priority(
+        priority: Priority,
+        queries: Vec<Query>,
+        operator: impl Fn(Vec<QueryResultList>, GameCommandsArg) + 'static,
+    ) -> Self {
+        Self {
+            queries,
+            operator: Box::new(operator),
+            priority,
+        }
+    }
+
+    pub fn queries(&self) -> &Vec<Query> {
+        &self.queries
+    }
+
+    pub fn operator(&self) -> &OperatorFn {
+        &self.operator
+    }
+
+    pub fn priority(&self) -> &Priority {
+        &self.priority
+    }
+}
+impl From<System> for thomas::System {
+    fn from(system: System) -> Self {
+        let operator = system.operator;
+
+        thomas::System::new_with_priority(
+            system.priority,
+            system
+                .queries
+                .into_iter()
+                .map(thomas::Query::from)
+                .collect(),
+            move |thomas_results, thomas_commands| {
+                let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+
+                operator(
+                    thomas_results
+                        .into_iter()
+                        .map(QueryResultList::from)
+                        .collect(),
+                    Rc::clone(&commands),
+                );
+
+                for command in commands.take() {
+                    thomas_commands.borrow_mut().issue(command.into());
+                }
+            },
+        )
+    }
+}
+
+/// Organizes related systems into a unit, just like a Thomas `SystemsGenerator`.
+pub trait SystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)>;
+}
+
+/// Adapts one of the game's `SystemsGenerator`s so it can be added to a Thomas `Game`.
+pub struct ThomasSystemsGenerator(pub Box<dyn SystemsGenerator>);
+impl thomas::SystemsGenerator for ThomasSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, thomas::System)> {
+        self.0
+            .generate()
+            .into_iter()
+            .map(|(event_name, system)| (event_name, system.into()))
+            .collect()
+    }
+}
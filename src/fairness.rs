@@ -0,0 +1,384 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use crate::{
+    components::Action, FlightPath, GameConfig, MotionInput, PlayerMotion, HOMING_DRIFT_TICKS,
+    HOMING_HEIGHTS, PLAYER_X_OFFSET, TICKS_PER_SECOND,
+};
+
+/// The most frames ahead a course is checked. It's far more than it takes the slowest scrolling screen to bring an
+/// obstacle from the right edge of the widest screen to the player.
+const MAX_COURSE_FRAMES: u64 = 20_000;
+
+/// What the player presses on a frame, as far as getting past obstacles goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RunnerInput {
+    #[default]
+    Nothing,
+    Jump,
+    Slide,
+}
+impl RunnerInput {
+    /// The actions the player performs to press this.
+    pub fn actions(&self) -> Vec<Action> {
+        match self {
+            RunnerInput::Nothing => vec![],
+            RunnerInput::Jump => vec![Action::Jump],
+            RunnerInput::Slide => vec![Action::Slide],
+        }
+    }
+}
+
+/// Where the player is in their jump or slide, as far as getting past obstacles goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RunnerState {
+    /// How many cells above the ground the player is.
+    pub height: i64,
+    pub motion: PlayerMotion,
+    /// What the player pressed on the frame before. Holding something down doesn't do it again.
+    pub last_input: RunnerInput,
+}
+impl RunnerState {
+    /// A player standing on the ground.
+    pub fn on_ground() -> Self {
+        Self {
+            height: 0,
+            motion: PlayerMotion {
+                gravity_ticks: TICKS_PER_SECOND as u64,
+                velocity_ticks: TICKS_PER_SECOND as u64,
+                ..PlayerMotion::default()
+            },
+            last_input: RunnerInput::Nothing,
+        }
+    }
+
+    /// Moves the player on by a frame the same way the player's systems do, with `input` pressed. `is_over_pit` is
+    /// whether there was a pit under the player at the end of the last frame.
+    pub fn step(&self, config: &GameConfig, input: RunnerInput, is_over_pit: bool) -> Self {
+        let mut next = *self;
+        let is_pressed_down = input != RunnerInput::Nothing && input != self.last_input;
+
+        next.motion.tick();
+
+        // Whether the player is on the ground is worked out from where they were at the end of the last frame.
+        let motion_step = next.motion.step(
+            config,
+            MotionInput {
+                is_jump_down: is_pressed_down && input == RunnerInput::Jump,
+                is_slide_down: is_pressed_down && input == RunnerInput::Slide,
+                was_jump_held: self.last_input == RunnerInput::Jump,
+            },
+            self.is_on_ground(is_over_pit),
+            config.max_air_jumps,
+        );
+
+        next.height += motion_step.climb;
+        next.last_input = input;
+
+        // Timers that are already due stay due until they're restarted, so there's no telling apart how long past due
+        // they are. Capping them keeps states that play out the same from looking different. Standing still, the next
+        // speed the player moves at is either a jump's or a first step of falling. The slide timer's only looked at
+        // during a slide, so it doesn't tell states apart at all otherwise.
+        next.motion.gravity_ticks = next
+            .motion
+            .gravity_ticks
+            .min(ticks_until_due(config.gravity as u64));
+        next.motion.velocity_ticks =
+            next.motion
+                .velocity_ticks
+                .min(if next.motion.vertical_velocity == 0 {
+                    ticks_until_due(config.jump_force as u64)
+                        .max(ticks_until_due(config.gravity as u64))
+                } else {
+                    TICKS_PER_SECOND as u64
+                });
+        next.motion.slide_ticks = if next.motion.is_sliding {
+            next.motion.slide_ticks.min(config.slide_ticks)
+        } else {
+            config.slide_ticks
+        };
+
+        next
+    }
+
+    /// Whether the player takes up the cell `height` cells above the ground.
+    fn is_at_height(&self, height: i64) -> bool {
+        height == self.height || (!self.motion.is_sliding && height == self.height + 1)
+    }
+
+    /// Whether the player is standing on solid ground.
+    fn is_on_ground(&self, is_over_pit: bool) -> bool {
+        self.height == 0 && !is_over_pit
+    }
+
+    /// The inputs worth trying this frame. Ones that wouldn't do anything, like holding down slide or holding jump once
+    /// the player's slowed down anyway, play out the same as pressing nothing.
+    fn inputs(&self, config: &GameConfig, is_over_pit: bool) -> Vec<RunnerInput> {
+        let is_on_ground = self.is_on_ground(is_over_pit);
+        let mut inputs = vec![RunnerInput::Nothing];
+
+        if self.last_input == RunnerInput::Jump {
+            if self.motion.vertical_velocity < -(config.short_jump_force as i64) {
+                inputs.push(RunnerInput::Jump);
+            }
+        } else if is_on_ground || self.motion.air_jumps < config.max_air_jumps {
+            inputs.push(RunnerInput::Jump);
+        }
+        if is_on_ground && self.last_input != RunnerInput::Slide {
+            inputs.push(RunnerInput::Slide);
+        }
+
+        inputs
+    }
+}
+
+/// An obstacle on the course, at `x` in the world and `height` cells above the ground.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CourseObstacle {
+    pub x: i64,
+    pub height: i64,
+    /// How many ticks pass between each step the obstacle moves towards the player, if it moves at all.
+    pub move_interval_option: Option<u64>,
+    pub move_ticks: u64,
+    pub flight_path: FlightPath,
+    /// How many ticks the obstacle's been flying for.
+    pub flight_ticks: u64,
+    /// How many cells a homing obstacle could have drifted from `height` either way by now. Which way it drifts depends
+    /// on where the player goes, so it's taken to be anywhere it could have drifted to.
+    pub drift: i64,
+}
+impl CourseObstacle {
+    /// Every height the obstacle could be at.
+    fn heights(&self) -> RangeInclusive<i64> {
+        if self.flight_path == FlightPath::Homing {
+            (self.height - self.drift).max(*HOMING_HEIGHTS.start())
+                ..=(self.height + self.drift).min(*HOMING_HEIGHTS.end())
+        } else {
+            self.height..=self.height
+        }
+    }
+
+    /// Moves the obstacle up or down along its flight path for a tick, with the player at `player_x`.
+    fn fly(&mut self, player_x: i64) {
+        if self.flight_path == FlightPath::Homing {
+            if self.flight_ticks.is_multiple_of(HOMING_DRIFT_TICKS) {
+                self.drift += 1;
+            }
+        } else {
+            // Only homing obstacles care where the player is, so the player's height is left out.
+            self.height =
+                self.flight_path
+                    .next_height(self.height, self.flight_ticks, self.x - player_x, 0);
+        }
+    }
+}
+
+/// A pit in the ground, starting at `x` in the world and running `width` cells to the right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoursePit {
+    pub x: i64,
+    pub width: u64,
+}
+impl CoursePit {
+    fn is_under(&self, x: i64) -> bool {
+        x >= self.x && x < self.x + self.width as i64
+    }
+}
+
+/// A snapshot of the player and the obstacles and pits coming up, for checking whether the player can get past all of
+/// them.
+///
+/// The check plays the course out frame by frame the same way the game does, trying every way the player could jump or
+/// slide. It's stricter than the game in one way: an obstacle that moves far enough in a frame to skip over the
+/// player's cells counts as hitting them if it was level with them before or after, even though the game would let it
+/// through, and homing obstacles count as being everywhere they could have drifted to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Course {
+    pub runner: RunnerState,
+    /// Where the main camera is. The player is always `PLAYER_X_OFFSET` cells to the right of it.
+    pub camera_x: i64,
+    pub camera_move_ticks: u64,
+    /// The score the camera's scroll speed is worked out from, which is how far the player's made it without any bonus
+    /// points.
+    pub score: u64,
+    pub obstacles: Vec<CourseObstacle>,
+    pub pits: Vec<CoursePit>,
+}
+impl Course {
+    /// Whether the player can get past every obstacle without being hit.
+    pub fn is_clearable(&self, config: &GameConfig) -> bool {
+        self.plan_inputs(config).is_some()
+    }
+
+    /// Finds a way for the player to get past every obstacle without being hit, as what's pressed on each frame from
+    /// the next one on. There isn't one if the player can't avoid being hit.
+    pub fn plan_inputs(&self, config: &GameConfig) -> Option<Vec<RunnerInput>> {
+        let frames = self.play_out(config)?;
+
+        if frames
+            .first()
+            .is_some_and(|hazards| hazards.hits(None, &self.runner))
+        {
+            return None;
+        }
+
+        // Each frame's states are the ones the player can be in on that frame without having been hit, each with the
+        // index of the state it came from on the frame before and what was pressed to get there.
+        let mut states_by_frame: Vec<Vec<(RunnerState, usize, RunnerInput)>> =
+            vec![vec![(self.runner, 0, RunnerInput::Nothing)]];
+
+        for (hazards_before, hazards) in frames.iter().zip(&frames[1..]) {
+            let mut seen = HashSet::new();
+            let mut next_states = vec![];
+
+            for (index, (runner, _, _)) in states_by_frame[states_by_frame.len() - 1]
+                .iter()
+                .enumerate()
+            {
+                for input in runner.inputs(config, hazards_before.is_over_pit) {
+                    let next_runner = runner.step(config, input, hazards_before.is_over_pit);
+
+                    if !hazards.hits(Some(runner), &next_runner) && seen.insert(next_runner) {
+                        next_states.push((next_runner, index, input));
+                    }
+                }
+            }
+
+            if next_states.is_empty() {
+                return None;
+            }
+
+            states_by_frame.push(next_states);
+        }
+
+        let mut plan = vec![];
+        let mut index = 0;
+        for states in states_by_frame[1..].iter().rev() {
+            let (_, previous_index, input) = states[index];
+
+            plan.push(input);
+            index = previous_index;
+        }
+        plan.reverse();
+
+        Some(plan)
+    }
+
+    /// Works out where the obstacles and pits are relative to the player on every frame until the last of them has
+    /// passed the player. They don't depend on what the player does, so they're worked out once up front. The course
+    /// can't be played out if the obstacles never pass the player.
+    fn play_out(&self, config: &GameConfig) -> Option<Vec<FrameHazards>> {
+        let mut camera_x = self.camera_x;
+        let mut camera_move_ticks = self.camera_move_ticks;
+        let mut score = self.score;
+        let mut obstacles = self.obstacles.clone();
+
+        let mut frames = vec![FrameHazards::at_rest(&obstacles, &self.pits, camera_x)];
+
+        while obstacles
+            .iter()
+            .any(|obstacle| obstacle.x >= camera_x + PLAYER_X_OFFSET)
+            || self
+                .pits
+                .iter()
+                .any(|pit| pit.x + pit.width as i64 > camera_x + PLAYER_X_OFFSET)
+        {
+            if frames.len() as u64 > MAX_COURSE_FRAMES {
+                return None;
+            }
+
+            let player_x_before = camera_x + PLAYER_X_OFFSET;
+            let obstacles_before = obstacles.clone();
+
+            camera_move_ticks += 1;
+            for obstacle in &mut obstacles {
+                obstacle.move_ticks += 1;
+                obstacle.flight_ticks += 1;
+            }
+
+            // Obstacles go up and down before anything moves across, like they do in the game.
+            for obstacle in &mut obstacles {
+                obstacle.fly(player_x_before);
+            }
+
+            let distance_traveled = camera_x;
+            if camera_move_ticks >= config.camera_scroll_wait_ticks(score) {
+                camera_x += 1;
+                camera_move_ticks = 0;
+            }
+            score = distance_traveled as u64;
+
+            for obstacle in &mut obstacles {
+                if let Some(move_interval) = obstacle.move_interval_option {
+                    if obstacle.move_ticks >= move_interval {
+                        obstacle.x -= 1;
+                        obstacle.move_ticks = 0;
+                    }
+                }
+            }
+
+            let player_x_after = camera_x + PLAYER_X_OFFSET;
+            let mut hazards = FrameHazards::at_rest(&obstacles, &self.pits, camera_x);
+
+            for (obstacle, obstacle_before) in obstacles.iter().zip(obstacles_before) {
+                let was_ahead = obstacle_before.x > player_x_before;
+                let is_behind = obstacle.x < player_x_after;
+
+                if was_ahead && is_behind {
+                    hazards
+                        .skipped_heights
+                        .extend(obstacle_before.heights().chain(obstacle.heights()));
+                }
+            }
+
+            frames.push(hazards);
+        }
+
+        Some(frames)
+    }
+}
+
+/// The heights the player can't be at on a frame, and whether there's ground under them.
+#[derive(Clone, Debug, Default)]
+struct FrameHazards {
+    /// The heights of the obstacles in the player's column at the end of the frame.
+    heights: Vec<i64>,
+    /// The heights of the obstacles that skipped over the player's column during the frame.
+    skipped_heights: Vec<i64>,
+    /// Whether there's a pit under the player's column at the end of the frame.
+    is_over_pit: bool,
+}
+impl FrameHazards {
+    fn at_rest(obstacles: &[CourseObstacle], pits: &[CoursePit], camera_x: i64) -> Self {
+        let player_x = camera_x + PLAYER_X_OFFSET;
+
+        Self {
+            heights: obstacles
+                .iter()
+                .filter(|obstacle| obstacle.x == player_x)
+                .flat_map(|obstacle| obstacle.heights())
+                .collect(),
+            skipped_heights: vec![],
+            is_over_pit: pits.iter().any(|pit| pit.is_under(player_x)),
+        }
+    }
+
+    /// Whether the player gets hit by an obstacle, or falls into a pit, going from `runner_before_option` to
+    /// `runner_after` this frame.
+    fn hits(&self, runner_before_option: Option<&RunnerState>, runner_after: &RunnerState) -> bool {
+        runner_after.height < 0
+            || self
+                .heights
+                .iter()
+                .any(|height| runner_after.is_at_height(*height))
+            || self.skipped_heights.iter().any(|height| {
+                runner_after.is_at_height(*height)
+                    || runner_before_option
+                        .is_some_and(|runner_before| runner_before.is_at_height(*height))
+            })
+    }
+}
+
+/// How many ticks have to pass for something happening `rate_per_second` times a second to happen again.
+fn ticks_until_due(rate_per_second: u64) -> u64 {
+    (TICKS_PER_SECOND as u64).div_ceil(rate_per_second)
+}
@@ -0,0 +1,135 @@
+use std::{f64::consts::TAU, ops::RangeInclusive};
+
+use rand::Rng;
+
+use crate::AIR_OBSTACLE_HEIGHT;
+
+/// How many cells above and below air obstacle height bobbing obstacles go.
+pub const BOB_AMPLITUDE: i64 = 1;
+/// How many ticks a bob up and back down takes.
+pub const BOB_PERIOD_TICKS: u64 = 24;
+/// How many cells above the ground swooping obstacles fly in at.
+pub const SWOOP_START_HEIGHT: i64 = 5;
+/// Swooping obstacles come down to the player's head and no further.
+pub const SWOOP_END_HEIGHT: i64 = 1;
+/// How close to the player, in cells, a swooping obstacle gets before it starts coming down.
+pub const SWOOP_DISTANCE: i64 = 24;
+/// How many ticks a swooping obstacle takes to come down a cell.
+pub const SWOOP_FALL_TICKS: u64 = 3;
+/// The heights homing obstacles drift between. They never come down far enough to stop the player sliding under them.
+pub const HOMING_HEIGHTS: RangeInclusive<i64> = 1..=3;
+/// How many ticks a homing obstacle takes to drift a cell towards the player.
+pub const HOMING_DRIFT_TICKS: u64 = 8;
+/// How many cells above the ground dropping obstacles hang before they drop. It's the top of the smallest screen.
+pub const DROP_START_HEIGHT: i64 = 7;
+/// How close to the player, in cells, a dropping obstacle gets before it drops.
+pub const DROP_DISTANCE: i64 = 16;
+/// How many ticks a dropping obstacle takes to fall a cell.
+pub const DROP_FALL_TICKS: u64 = 2;
+
+/// How a moving air obstacle goes up and down as it flies in towards the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FlightPath {
+    /// Flies straight along at air obstacle height.
+    Level,
+    /// Bobs up and down around air obstacle height in a sine wave.
+    Bob,
+    /// Flies in high, then swoops down at the player's head once it's close.
+    Swoop,
+    /// Drifts up or down towards the player's height, within `HOMING_HEIGHTS`.
+    Homing,
+    /// Hangs at the top of the screen, then drops to the ground once it's close.
+    Drop,
+}
+impl FlightPath {
+    pub const ALL: [FlightPath; 5] = [
+        FlightPath::Level,
+        FlightPath::Bob,
+        FlightPath::Swoop,
+        FlightPath::Homing,
+        FlightPath::Drop,
+    ];
+
+    /// How many cells above the ground obstacles on this path spawn.
+    pub fn spawn_height(&self) -> i64 {
+        match self {
+            FlightPath::Level | FlightPath::Bob | FlightPath::Homing => AIR_OBSTACLE_HEIGHT,
+            FlightPath::Swoop => SWOOP_START_HEIGHT,
+            FlightPath::Drop => DROP_START_HEIGHT,
+        }
+    }
+
+    /// How likely the path is to be picked when the game is `progress_percent` of the way to its hardest, relative to
+    /// the other paths.
+    pub fn weight(&self, progress_percent: u8) -> u32 {
+        let (weight_at_start, weight_at_hardest): (i64, i64) = match self {
+            FlightPath::Level => (100, 40),
+            FlightPath::Bob => (20, 30),
+            FlightPath::Swoop => (10, 30),
+            FlightPath::Homing => (5, 25),
+            FlightPath::Drop => (10, 25),
+        };
+        let progress_percent = progress_percent.min(100) as i64;
+
+        (weight_at_start + (weight_at_hardest - weight_at_start) * progress_percent / 100) as u32
+    }
+
+    /// The height an obstacle on this path flies to on a tick, from `height`. `flight_ticks` is how many ticks it's
+    /// been flying, counting this one, and `distance` is how many cells ahead of the player it is.
+    pub fn next_height(
+        &self,
+        height: i64,
+        flight_ticks: u64,
+        distance: i64,
+        player_height: i64,
+    ) -> i64 {
+        match self {
+            FlightPath::Level => height,
+            FlightPath::Bob => {
+                let phase = (flight_ticks % BOB_PERIOD_TICKS) as f64 / BOB_PERIOD_TICKS as f64;
+
+                AIR_OBSTACLE_HEIGHT + (BOB_AMPLITUDE as f64 * (TAU * phase).sin()).round() as i64
+            }
+            FlightPath::Swoop
+                if distance <= SWOOP_DISTANCE && flight_ticks.is_multiple_of(SWOOP_FALL_TICKS) =>
+            {
+                (height - 1).max(SWOOP_END_HEIGHT)
+            }
+            FlightPath::Homing if flight_ticks.is_multiple_of(HOMING_DRIFT_TICKS) => {
+                let next_height = height + (player_height - height).signum();
+
+                next_height.clamp(*HOMING_HEIGHTS.start(), *HOMING_HEIGHTS.end())
+            }
+            FlightPath::Drop
+                if distance <= DROP_DISTANCE && flight_ticks.is_multiple_of(DROP_FALL_TICKS) =>
+            {
+                (height - 1).max(0)
+            }
+            _ => height,
+        }
+    }
+}
+
+/// Randomly picks the path a moving air obstacle flies, weighted by how far along the difficulty curve the game is.
+pub fn pick_flight_path(progress_percent: u8, rng: &mut impl Rng) -> FlightPath {
+    let total_weight: u32 = FlightPath::ALL
+        .iter()
+        .map(|path| path.weight(progress_percent))
+        .sum();
+
+    let mut roll = rng.gen_range(0..total_weight);
+
+    FlightPath::ALL
+        .into_iter()
+        .find(|path| {
+            let weight = path.weight(progress_percent);
+
+            if roll < weight {
+                true
+            } else {
+                roll -= weight;
+                false
+            }
+        })
+        .expect("The roll is less than the total weight of the paths.")
+}
@@ -0,0 +1,441 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{Difficulty, GameMode, Upgrade, UpgradeLevels};
+
+/// How many runs the high-score table keeps for each difficulty in each mode.
+pub const MAX_HIGH_SCORES: usize = 10;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Outcome {
+    Victory,
+    Defeat,
+}
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Victory => "victory",
+            Outcome::Defeat => "defeat",
+        }
+    }
+}
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Outcome::Victory, Outcome::Defeat]
+            .into_iter()
+            .find(|outcome| outcome.name() == s)
+            .ok_or_else(|| format!("'{}' isn't an outcome", s))
+    }
+}
+
+/// A finished run that made it onto the high-score table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighScore {
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    pub score: u64,
+    pub seed: u64,
+    pub lives: u8,
+    pub outcome: Outcome,
+    /// When the run finished, in seconds since the Unix epoch.
+    pub finished_at: u64,
+    /// The upgrades from the shop the run was played with.
+    pub upgrades: UpgradeLevels,
+}
+impl HighScore {
+    /// Reads a high score from its line in the high-score file. Settings that aren't recognized are skipped so
+    /// files written by newer versions of the game can still be read.
+    fn parse(line: &str) -> Option<Self> {
+        let mut difficulty_option = None;
+        let mut mode_option = None;
+        let mut score_option = None;
+        let mut seed_option = None;
+        let mut lives_option = None;
+        let mut outcome_option = None;
+        let mut finished_at_option = None;
+        let mut upgrades = UpgradeLevels::default();
+        let mut upgrades_read = vec![];
+
+        for (key, value) in line
+            .split_whitespace()
+            .filter_map(|word| word.split_once('='))
+        {
+            match key {
+                "difficulty" => difficulty_option = value.parse().ok(),
+                "mode" => mode_option = value.parse().ok(),
+                "score" => score_option = value.parse().ok(),
+                "seed" => seed_option = value.parse().ok(),
+                "lives" => lives_option = value.parse().ok(),
+                "outcome" => outcome_option = value.parse().ok(),
+                "finished_at" => finished_at_option = value.parse().ok(),
+                _ => {
+                    if let Ok(upgrade) = key.parse::<Upgrade>() {
+                        upgrades.set_level(upgrade, value.parse().ok()?);
+                        upgrades_read.push(upgrade);
+                    }
+                }
+            }
+        }
+
+        if !Upgrade::ALL
+            .iter()
+            .all(|upgrade| upgrades_read.contains(upgrade))
+        {
+            return None;
+        }
+
+        Some(Self {
+            difficulty: difficulty_option?,
+            mode: mode_option?,
+            score: score_option?,
+            seed: seed_option?,
+            lives: lives_option?,
+            outcome: outcome_option?,
+            finished_at: finished_at_option?,
+            upgrades,
+        })
+    }
+
+    /// The day the run finished, as `YYYY-MM-DD` in UTC.
+    pub fn finished_on(&self) -> String {
+        let (year, month, day) = civil_from_days((self.finished_at / 86_400) as i64);
+
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+impl fmt::Display for HighScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "difficulty={} mode={} score={} seed={} lives={} outcome={} finished_at={}",
+            self.difficulty,
+            self.mode,
+            self.score,
+            self.seed,
+            self.lives,
+            self.outcome,
+            self.finished_at
+        )?;
+
+        for upgrade in Upgrade::ALL {
+            write!(f, " {}={}", upgrade, self.upgrades.level(upgrade))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The best runs at each difficulty, highest score first. Each mode has its own table of runs at every difficulty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+impl HighScores {
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Where the high scores are kept when none is given.
+    pub fn default_path() -> Option<PathBuf> {
+        data_file_path("high-scores.txt")
+    }
+
+    /// Reads the high scores kept at `path`. A missing file means there aren't any high scores yet, and any line that
+    /// can't be read is skipped rather than losing the whole table to it.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(Self::parse(&contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let mut high_scores = Self::new();
+
+        for high_score in contents
+            .lines()
+            .filter(|line| !line.trim().starts_with('#'))
+            .filter_map(HighScore::parse)
+        {
+            high_scores.add(high_score);
+        }
+
+        high_scores
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, self.to_string())
+    }
+
+    /// Adds the run to the table if it's good enough to make it, and gives where it placed among the runs at its
+    /// difficulty in its mode. Places start at 0.
+    pub fn add(&mut self, high_score: HighScore) -> Option<usize> {
+        let difficulty = high_score.difficulty;
+        let mode = high_score.mode;
+        let place = self
+            .entries_for(difficulty, mode)
+            .iter()
+            .position(|entry| high_score.score > entry.score)
+            .unwrap_or(self.entries_for(difficulty, mode).len());
+
+        if place >= MAX_HIGH_SCORES {
+            return None;
+        }
+
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| high_score.score > entry.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, high_score);
+
+        let dropped_index_option = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.difficulty == difficulty && entry.mode == mode)
+            .map(|(index, _)| index)
+            .nth(MAX_HIGH_SCORES);
+        if let Some(dropped_index) = dropped_index_option {
+            self.entries.remove(dropped_index);
+        }
+
+        Some(place)
+    }
+
+    /// The runs at `difficulty` in `mode`, highest score first.
+    pub fn entries_for(&self, difficulty: Difficulty, mode: GameMode) -> Vec<&HighScore> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.difficulty == difficulty && entry.mode == mode)
+            .collect()
+    }
+
+    pub fn best(&self, difficulty: Difficulty, mode: GameMode) -> Option<&HighScore> {
+        self.entries
+            .iter()
+            .find(|entry| entry.difficulty == difficulty && entry.mode == mode)
+    }
+}
+impl fmt::Display for HighScores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# runner high scores")?;
+
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Where the game keeps a file between sessions: a `runner` folder in the user's data directory.
+pub fn data_file_path(file_name: &str) -> Option<PathBuf> {
+    let data_dir_option = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|data_home| !data_home.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+
+    data_dir_option.map(|data_dir| data_dir.join("runner").join(file_name))
+}
+
+/// The number of seconds since the Unix epoch.
+pub fn now_unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Turns a count of days since the Unix epoch into a `(year, month, day)` date.
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days.
+fn civil_from_days(days_since_epoch: i64) -> (i64, u32, u32) {
+    let z = days_since_epoch + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(difficulty: Difficulty, mode: GameMode, score: u64) -> HighScore {
+        HighScore {
+            difficulty,
+            mode,
+            score,
+            seed: 9,
+            lives: 2,
+            outcome: Outcome::Defeat,
+            finished_at: 1_700_000_000,
+            upgrades: UpgradeLevels::default(),
+        }
+    }
+
+    /// The rest of a line that gives every upgrade, none of them bought.
+    const NO_UPGRADES: &str =
+        "extra_life=0 extra_air_jump=0 longer_power_ups=0 likelier_extra_lives=0";
+
+    #[test]
+    fn high_scores_parse_from_their_lines() {
+        assert_eq!(
+            HighScore::parse(&format!(
+                "difficulty=hard mode=endless score=420 seed=9 lives=2 outcome=defeat finished_at=1700000000 {}",
+                NO_UPGRADES
+            )),
+            Some(high_score(Difficulty::Hard, GameMode::Endless, 420))
+        );
+        // With a setting from a newer version of the game.
+        assert_eq!(
+            HighScore::parse(&format!(
+                "difficulty=hard mode=endless score=420 seed=9 lives=2 outcome=defeat finished_at=1700000000 {} \
+                 combo=4",
+                NO_UPGRADES
+            )),
+            Some(high_score(Difficulty::Hard, GameMode::Endless, 420))
+        );
+
+        let mut high_score = high_score(Difficulty::Easy, GameMode::Classic, 7);
+        high_score.upgrades.set_level(Upgrade::ExtraLife, 2);
+        high_score.upgrades.set_level(Upgrade::LongerPowerUps, 1);
+        assert_eq!(high_score.finished_on(), "2023-11-14");
+        assert!(high_score
+            .to_string()
+            .ends_with("extra_life=2 extra_air_jump=0 longer_power_ups=1 likelier_extra_lives=0"));
+        assert_eq!(HighScore::parse(&high_score.to_string()), Some(high_score));
+    }
+
+    #[test]
+    fn lines_that_cant_be_read_are_skipped() {
+        let high_scores = HighScores::parse(&format!(
+            "# runner high scores\n\
+             difficulty=normal mode=classic score=30 seed=9 lives=2 outcome=defeat finished_at=1700000000 {0}\n\
+             difficulty=normal mode=classic score=lots seed=9 lives=2 outcome=defeat finished_at=1700000000 {0}\n\
+             difficulty=normal mode=classic score=20 seed=9 outcome=defeat finished_at=1700000000 {0}\n\
+             difficulty=impossible mode=classic score=20 seed=9 lives=2 outcome=defeat finished_at=1700000000 {0}\n\
+             difficulty=normal mode=classic score=10 seed=9 lives=2 outcome=retired finished_at=1700000000 {0}\n\
+             difficulty=normal mode=classic score=10 seed=9 lives=2 outcome=defeat finished_at=1700000000 \
+             extra_life=lots\n\
+             score=10 seed=9 lives=2 outcome=defeat finished_at=1700000000\n\
+             garbage\n",
+            NO_UPGRADES
+        ));
+
+        assert_eq!(
+            high_scores.entries_for(Difficulty::Normal, GameMode::Classic),
+            [&high_score(Difficulty::Normal, GameMode::Classic, 30)]
+        );
+    }
+
+    #[test]
+    fn only_the_best_runs_are_kept() {
+        let mut high_scores = HighScores::new();
+
+        for score in 1..=MAX_HIGH_SCORES as u64 {
+            assert!(high_scores
+                .add(high_score(
+                    Difficulty::Normal,
+                    GameMode::Classic,
+                    score * 10
+                ))
+                .is_some());
+        }
+
+        assert_eq!(
+            high_scores.add(high_score(Difficulty::Normal, GameMode::Classic, 5)),
+            None
+        );
+        assert_eq!(
+            high_scores.add(high_score(Difficulty::Normal, GameMode::Classic, 55)),
+            Some(5)
+        );
+
+        let scores: Vec<u64> = high_scores
+            .entries_for(Difficulty::Normal, GameMode::Classic)
+            .iter()
+            .map(|entry| entry.score)
+            .collect();
+        assert_eq!(scores, [100, 90, 80, 70, 60, 55, 50, 40, 30, 20]);
+    }
+
+    #[test]
+    fn each_difficulty_in_each_mode_has_its_own_table() {
+        let mut high_scores = HighScores::new();
+
+        for score in 1..=MAX_HIGH_SCORES as u64 {
+            high_scores.add(high_score(Difficulty::Hard, GameMode::Classic, score * 100));
+        }
+
+        assert_eq!(
+            high_scores.add(high_score(Difficulty::Easy, GameMode::Classic, 1)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores.add(high_score(Difficulty::Hard, GameMode::Endless, 1)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores
+                .entries_for(Difficulty::Hard, GameMode::Classic)
+                .len(),
+            MAX_HIGH_SCORES
+        );
+        assert_eq!(
+            high_scores
+                .best(Difficulty::Hard, GameMode::Endless)
+                .map(|entry| entry.score),
+            Some(1)
+        );
+        assert_eq!(
+            high_scores.best(Difficulty::Normal, GameMode::Classic),
+            None
+        );
+
+        assert_eq!(HighScores::parse(&high_scores.to_string()), high_scores);
+    }
+
+    #[test]
+    fn files_that_cant_be_read_are_an_error_rather_than_an_empty_table() {
+        assert_eq!(
+            HighScores::load(env::temp_dir().join("runner-test-high-scores-that-arent-there.txt"))
+                .ok(),
+            Some(HighScores::new())
+        );
+        assert!(HighScores::load(env::temp_dir()).is_err());
+    }
+}
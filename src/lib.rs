@@ -1,32 +1,257 @@
+mod coin_formations;
+pub use coin_formations::*;
+
+mod combo;
+pub use combo::*;
+
+mod config;
+pub use config::*;
+
 mod constants;
 pub use constants::*;
 
+mod difficulty_curve;
+pub use difficulty_curve::*;
+
+mod difficulty_menu;
+pub use difficulty_menu::*;
+
+mod engine;
+pub use engine::*;
+
+mod fairness;
+pub use fairness::*;
+
+mod flight_paths;
+pub use flight_paths::*;
+
+mod high_scores;
+pub use high_scores::*;
+
 mod systems;
 use systems::*;
 
-mod components;
+pub mod components;
+
+mod obstacle_patterns;
+pub use obstacle_patterns::*;
+
+mod physics;
+pub use physics::*;
 
 mod prefabs;
 pub use prefabs::*;
 
-use thomas::{Dimensions2d, Game, GameOptions, Renderer, TerminalRendererOptions};
+mod replay;
+pub use replay::*;
+
+mod resolution;
+pub use resolution::*;
+
+mod run_stats;
+pub use run_stats::*;
+
+mod simulation;
+pub use simulation::*;
+
+mod tick_timer;
+pub use tick_timer::*;
+
+mod upgrades;
+pub use upgrades::*;
+
+mod wallet;
+pub use wallet::*;
+
+use std::{cell::RefCell, io, path::PathBuf, rc::Rc};
 
-pub fn run() {
-    Game::new(GameOptions {
-        max_frame_rate: 30,
+use thomas::{Game, GameOptions, Renderer, TerminalRendererOptions};
+
+pub struct PlayOptions {
+    /// The seed to generate every run from. When there isn't one, the first run is generated from a random seed, and
+    /// each run after it from a new seed.
+    pub seed_option: Option<u64>,
+    /// The difficulties that can be picked on the start screen, and which one is picked to begin with.
+    pub difficulty_menu: DifficultyMenu,
+    pub resolution: Resolution,
+    /// How many frames are drawn each second. The game's clock keeps to `TICKS_PER_SECOND` whatever this is, so it only
+    /// changes how smoothly the game is drawn.
+    pub max_frame_rate: u16,
+    /// Where to save a `Replay` of the run once the game is quit. Nothing is recorded when there isn't a path.
+    pub record_path_option: Option<PathBuf>,
+    /// The high scores runs are added to.
+    pub high_scores: HighScores,
+    /// Where the high scores are saved after each run. They're only kept for this session when there isn't a path.
+    pub high_scores_path_option: Option<PathBuf>,
+    /// Where to export the stats of each run once it's over. See `RunStats::append_to` for the format.
+    pub stats_path_option: Option<PathBuf>,
+    /// The wallet coins collected are put into and upgrades are bought from.
+    pub wallet: Wallet,
+    /// Where the wallet is saved after each run and purchase. It's only kept for this session when there isn't a path.
+    pub wallet_path_option: Option<PathBuf>,
+}
+
+/// Plays the game in the terminal.
+pub fn run(options: PlayOptions) -> io::Result<()> {
+    let seed = options.seed_option.unwrap_or_else(rand::random);
+    let is_seed_fixed = options.seed_option.is_some();
+    let wallet = options.wallet;
+    let save_paths = SavePaths {
+        high_scores_path_option: options.high_scores_path_option,
+        stats_path_option: options.stats_path_option,
+        wallet_path_option: options.wallet_path_option,
+    };
+    let systems_generators = game_systems_generators(
+        seed,
+        is_seed_fixed,
+        options.difficulty_menu.clone(),
+        options.resolution,
+        wallet,
+        options.high_scores,
+        save_paths,
+    );
+
+    match options.record_path_option {
+        Some(record_path) => {
+            let replay = Rc::new(RefCell::new(Replay::new(
+                seed,
+                is_seed_fixed,
+                options.difficulty_menu.selected_difficulty(),
+                options.difficulty_menu.selected_config().clone(),
+                wallet,
+                options.resolution,
+            )));
+
+            start_game(
+                options.resolution,
+                options.max_frame_rate,
+                systems_generators
+                    .into_iter()
+                    .chain([Box::new(ReplaySystemsGenerator {
+                        mode: ReplayMode::Record(Rc::clone(&replay)),
+                    }) as Box<dyn SystemsGenerator>])
+                    .collect(),
+            );
+
+            let replay = replay.borrow();
+            replay.save(record_path)
+        }
+        None => {
+            start_game(
+                options.resolution,
+                options.max_frame_rate,
+                systems_generators,
+            );
+
+            Ok(())
+        }
+    }
+}
+
+/// Plays a recorded run back in the terminal, drawing `max_frame_rate` frames per second. Once the replay runs out, nothing
+/// more is pressed until the game is quit. Replayed runs don't count towards the high scores or the wallet, but their
+/// stats can be exported to `stats_path_option`.
+pub fn play_replay(replay: Replay, max_frame_rate: u16, stats_path_option: Option<PathBuf>) {
+    let resolution = replay.resolution();
+    let systems_generators = game_systems_generators(
+        replay.seed(),
+        replay.is_seed_fixed(),
+        DifficultyMenu::single(replay.difficulty(), replay.config().clone()),
+        resolution,
+        replay.wallet(),
+        HighScores::new(),
+        SavePaths {
+            high_scores_path_option: None,
+            stats_path_option,
+            wallet_path_option: None,
+        },
+    );
+
+    start_game(
+        resolution,
+        max_frame_rate,
+        systems_generators
+            .into_iter()
+            .chain([Box::new(ReplaySystemsGenerator {
+                mode: ReplayMode::Play(replay),
+            }) as Box<dyn SystemsGenerator>])
+            .collect(),
+    );
+}
+
+fn start_game(
+    resolution: Resolution,
+    max_frame_rate: u16,
+    systems_generators: Vec<Box<dyn SystemsGenerator>>,
+) {
+    let mut game = Game::new(GameOptions {
+        max_frame_rate,
         press_escape_to_quit: false,
-    })
-    .add_systems_from_generator(PlayerSystemsGenerator {})
-    .add_systems_from_generator(WorldSetupSystemsGenerator {})
-    .add_systems_from_generator(WorldUpdateSystemsGenerator {})
-    .add_systems_from_generator(GameManagerSystemsGenerator {})
-    .add_systems_from_generator(FollowCameraSystemsGenerator {})
-    .add_systems_from_generator(GameObjectsSystemsGenerator {})
-    .add_systems_from_generator(HudSystemsGenerator {})
-    .start(Renderer::Terminal(TerminalRendererOptions {
-        screen_resolution: Dimensions2d::new(SCREEN_HEIGHT as u64, SCREEN_WIDTH as u64),
+    });
+
+    for systems_generator in systems_generators
+        .into_iter()
+        .chain([Box::new(ClockSystemsGenerator {}) as Box<dyn SystemsGenerator>])
+    {
+        game = game.add_systems_from_generator(ThomasSystemsGenerator(systems_generator));
+    }
+
+    game.start(Renderer::Terminal(TerminalRendererOptions {
+        screen_resolution: resolution.dimensions(),
         include_default_camera: true,
         default_foreground_color: None,
         default_background_color: None,
     }));
 }
+
+/// Where the game keeps what it saves, each left out when there isn't a path. See `PlayOptions` for what each is.
+#[derive(Default)]
+struct SavePaths {
+    high_scores_path_option: Option<PathBuf>,
+    stats_path_option: Option<PathBuf>,
+    wallet_path_option: Option<PathBuf>,
+}
+
+/// Every generator that makes up the game, in the order they're added.
+fn game_systems_generators(
+    seed: u64,
+    is_seed_fixed: bool,
+    difficulty_menu: DifficultyMenu,
+    resolution: Resolution,
+    wallet: Wallet,
+    high_scores: HighScores,
+    save_paths: SavePaths,
+) -> Vec<Box<dyn SystemsGenerator>> {
+    vec![
+        Box::new(CollisionsSystemsGenerator {}),
+        Box::new(ControlsSystemsGenerator {}),
+        Box::new(TicksSystemsGenerator {}),
+        Box::new(PlayerSystemsGenerator {}),
+        Box::new(WorldSetupSystemsGenerator {
+            seed,
+            is_seed_fixed,
+            resolution,
+        }),
+        Box::new(WorldUpdateSystemsGenerator {}),
+        Box::new(GameManagerSystemsGenerator { difficulty_menu }),
+        Box::new(FollowCameraSystemsGenerator {}),
+        Box::new(GameObjectsSystemsGenerator {}),
+        Box::new(EnemiesSystemsGenerator {}),
+        Box::new(PickupsSystemsGenerator {}),
+        Box::new(CoinsSystemsGenerator {}),
+        Box::new(ComboSystemsGenerator {}),
+        Box::new(HudSystemsGenerator {}),
+        Box::new(HighScoresSystemsGenerator {
+            high_scores,
+            path_option: save_paths.high_scores_path_option,
+        }),
+        Box::new(WalletSystemsGenerator {
+            wallet,
+            path_option: save_paths.wallet_path_option,
+        }),
+        Box::new(ShopSystemsGenerator {}),
+        Box::new(RunStatsSystemsGenerator {
+            export_path_option: save_paths.stats_path_option,
+        }),
+    ]
+}
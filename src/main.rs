@@ -1,3 +1,227 @@
+mod cli;
+
+use std::{env, path::PathBuf, process};
+
+use cli::{Command, HELP};
+use runner::{
+    Difficulty, DifficultyMenu, GameMode, HighScores, PlayOptions, Replay, Resolution, RunStats,
+    Upgrade, Wallet,
+};
+
 fn main() {
-    runner::run();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = cli::parse_args(&args).unwrap_or_else(|e| {
+        exit_with_error(&format!("{}\nRun 'runner help' to see how to use it.", e))
+    });
+
+    match command {
+        Command::Play(play_args) => {
+            // The config file is applied on top of every difficulty. Any it doesn't work with are left out of the
+            // menu, unless it's the one that was asked for.
+            let mut difficulty_choices = vec![];
+            for difficulty in Difficulty::ALL {
+                let mut config = difficulty.config();
+
+                if let Some(config_path) = &play_args.config_path_option {
+                    match config.apply_file(config_path) {
+                        Ok(()) => {}
+                        Err(e) if difficulty == play_args.difficulty => {
+                            exit_with_error(&e.to_string())
+                        }
+                        Err(_) => continue,
+                    }
+                }
+
+                if let Some(mode) = play_args.mode_option {
+                    config.mode = mode;
+                }
+
+                difficulty_choices.push((difficulty, config));
+            }
+
+            let terminal_resolution = Resolution::from_terminal().unwrap_or_default();
+            let resolution = Resolution::new(
+                play_args
+                    .width_option
+                    .unwrap_or(terminal_resolution.width()),
+                play_args
+                    .height_option
+                    .unwrap_or(terminal_resolution.height()),
+            )
+            .unwrap_or_else(|e| exit_with_error(&e));
+
+            check_stats_path(&play_args.stats_path_option);
+            let (high_scores, high_scores_path_option) = load_high_scores();
+            let (wallet, wallet_path_option) = load_wallet(Wallet::default_path());
+
+            if let Err(e) = runner::run(PlayOptions {
+                seed_option: play_args.seed_option,
+                difficulty_menu: DifficultyMenu::new(difficulty_choices, play_args.difficulty),
+                resolution,
+                max_frame_rate: play_args.max_frame_rate,
+                record_path_option: play_args.record_path_option,
+                high_scores,
+                high_scores_path_option,
+                stats_path_option: play_args.stats_path_option,
+                wallet,
+                wallet_path_option,
+            }) {
+                exit_with_error(&format!("Couldn't save the replay: {}", e));
+            }
+        }
+        Command::Replay {
+            replay_path,
+            max_frame_rate,
+            stats_path_option,
+        } => match Replay::load(replay_path) {
+            Ok(replay) => {
+                check_stats_path(&stats_path_option);
+                runner::play_replay(replay, max_frame_rate, stats_path_option)
+            }
+            Err(e) => exit_with_error(&e.to_string()),
+        },
+        Command::Scores => print_high_scores(),
+        Command::Help => println!("{}", HELP),
+    }
+}
+
+/// Makes sure the stats of each run can be exported to `stats_path_option` before any runs are played, since there's
+/// nowhere to report a failed export once the game's going.
+fn check_stats_path(stats_path_option: &Option<PathBuf>) {
+    if let Some(stats_path) = stats_path_option {
+        if let Err(e) = RunStats::check_export_path(stats_path) {
+            exit_with_error(&format!(
+                "Can't export stats to {}: {}",
+                stats_path.display(),
+                e
+            ));
+        }
+    }
+}
+
+/// Reads the high scores runs are added to, and gives where to save them. When they can't be read, the game's played
+/// without keeping high scores rather than saving over the ones that are there.
+fn load_high_scores() -> (HighScores, Option<PathBuf>) {
+    match HighScores::default_path() {
+        Some(path) => match HighScores::load(&path) {
+            Ok(high_scores) => (high_scores, Some(path)),
+            Err(e) => {
+                eprintln!(
+                    "Couldn't read the high scores in {}, so they won't be kept this time: {}",
+                    path.display(),
+                    e
+                );
+
+                (HighScores::new(), None)
+            }
+        },
+        None => (HighScores::new(), None),
+    }
+}
+
+/// Reads the wallet the game starts with from `path_option`, and gives where to save it. When it can't be read, the
+/// game's played with an empty wallet that isn't kept rather than saving over the coins and upgrades that are there.
+fn load_wallet(path_option: Option<PathBuf>) -> (Wallet, Option<PathBuf>) {
+    match path_option {
+        Some(path) => match Wallet::load(&path) {
+            Ok(wallet) => (wallet, Some(path)),
+            Err(e) => {
+                eprintln!(
+                    "Couldn't read the wallet in {}, so coins and upgrades won't be kept this time: {}",
+                    path.display(),
+                    e
+                );
+
+                (Wallet::default(), None)
+            }
+        },
+        None => (Wallet::default(), None),
+    }
+}
+
+fn print_high_scores() {
+    let high_scores = match HighScores::default_path() {
+        Some(path) => HighScores::load(&path).unwrap_or_else(|e| {
+            exit_with_error(&format!(
+                "Couldn't read the high scores in {}: {}",
+                path.display(),
+                e
+            ))
+        }),
+        None => exit_with_error("Couldn't find where the high scores are kept."),
+    };
+
+    let mut has_high_scores = false;
+
+    for mode in GameMode::ALL {
+        for difficulty in Difficulty::ALL {
+            let entries = high_scores.entries_for(difficulty, mode);
+            if entries.is_empty() {
+                continue;
+            }
+
+            if has_high_scores {
+                println!();
+            }
+            has_high_scores = true;
+
+            println!("{}", mode.title_with(difficulty));
+            println!(
+                "{:>3}  {:>8}  {:<8}  {:>5}  {:<10}  {:<20}  Upgrades",
+                "#", "Score", "Outcome", "Lives", "Date", "Seed"
+            );
+            for (place, high_score) in entries.iter().enumerate() {
+                let upgrades: Vec<String> = Upgrade::ALL
+                    .iter()
+                    .filter(|upgrade| high_score.upgrades.level(**upgrade) > 0)
+                    .map(|upgrade| format!("{} {}", upgrade, high_score.upgrades.level(*upgrade)))
+                    .collect();
+
+                println!(
+                    "{:>3}  {:>8}  {:<8}  {:>5}  {:<10}  {:<20}  {}",
+                    place + 1,
+                    high_score.score,
+                    high_score.outcome,
+                    high_score.lives,
+                    high_score.finished_on(),
+                    high_score.seed,
+                    if upgrades.is_empty() {
+                        String::from("none")
+                    } else {
+                        upgrades.join(", ")
+                    }
+                );
+            }
+        }
+    }
+
+    if !has_high_scores {
+        println!("No high scores yet. Go play!");
+    }
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    #[test]
+    fn wallets_that_cant_be_read_are_played_without_and_left_alone() {
+        let path = env::temp_dir().join("runner-test-wallet-corrupt.txt");
+        let contents = "# runner wallet\ncoins=12x\nextra_life=2\n";
+        fs::write(&path, contents).unwrap();
+
+        let (wallet, path_option) = load_wallet(Some(path.clone()));
+
+        assert_eq!(wallet, Wallet::default());
+        assert_eq!(path_option, None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
 }
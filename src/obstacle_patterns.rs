@@ -0,0 +1,331 @@
+use rand::Rng;
+use thomas::{Dimensions2d, Matrix};
+
+use crate::AIR_OBSTACLE_HEIGHT;
+
+/// Marks the cells of an obstacle's shape that are part of the obstacle. Any other character leaves a gap.
+pub const OBSTACLE_SHAPE_CELL: char = '#';
+/// The shape of an obstacle that's a single cell.
+pub const SINGLE_CELL_SHAPE: &[&str] = &["#"];
+
+/// One of the obstacles that make up a pattern. Offsets are in cells from the start of the pattern, and heights are in
+/// cells above the ground.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternObstacle {
+    /// An obstacle sitting on the ground, or stacked `height` cells up on top of others.
+    Ground { x_offset: i64, height: i64 },
+    /// An obstacle hanging still in the air, just high enough to hit the player if they jump into it.
+    Air { x_offset: i64 },
+    /// An obstacle that flies along at the height of a still air obstacle, towards the player.
+    MovingAir { x_offset: i64 },
+    /// Either a ground obstacle or a moving air obstacle, in the air as often as the difficulty curve says.
+    Random { x_offset: i64 },
+    /// An obstacle drawn from `rows`, top row first, with its bottom row `height` cells above the ground. It's a
+    /// ground obstacle if it sits on the ground and an air obstacle if it doesn't.
+    Shape {
+        x_offset: i64,
+        height: i64,
+        rows: &'static [&'static str],
+    },
+    /// A gap in the ground `width` cells wide that the player has to jump over.
+    Pit { x_offset: i64, width: u64 },
+}
+impl PatternObstacle {
+    pub fn x_offset(&self) -> i64 {
+        match *self {
+            PatternObstacle::Ground { x_offset, .. }
+            | PatternObstacle::Air { x_offset }
+            | PatternObstacle::MovingAir { x_offset }
+            | PatternObstacle::Random { x_offset }
+            | PatternObstacle::Shape { x_offset, .. }
+            | PatternObstacle::Pit { x_offset, .. } => x_offset,
+        }
+    }
+
+    /// How many cells across the obstacle is.
+    pub fn width(&self) -> u64 {
+        match *self {
+            PatternObstacle::Shape { rows, .. } => shape_width(rows),
+            PatternObstacle::Pit { width, .. } => width,
+            _ => 1,
+        }
+    }
+}
+
+/// Turns the rows of an obstacle's shape into a matrix of which cells are part of the obstacle. Rows shorter than the
+/// widest one are padded with gaps.
+pub fn make_obstacle_shape(rows: &[&str]) -> Matrix<bool> {
+    let mut shape = Matrix::new(
+        Dimensions2d::new(rows.len() as u64, shape_width(rows)),
+        || false,
+    );
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, cell) in row.chars().enumerate() {
+            shape.update_cell_at(x as u64, y as u64, cell == OBSTACLE_SHAPE_CELL);
+        }
+    }
+
+    shape
+}
+
+fn shape_width(rows: &[&str]) -> u64 {
+    rows.iter()
+        .map(|row| row.chars().count() as u64)
+        .max()
+        .unwrap_or(0)
+}
+
+/// A hand-designed group of obstacles that's spawned all at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObstaclePattern {
+    pub name: &'static str,
+    pub obstacles: &'static [PatternObstacle],
+    /// How likely the pattern is to be picked at the start of a run, relative to the other patterns.
+    pub weight_at_start: u32,
+    /// How likely the pattern is to be picked once the game is as hard as its difficulty curve gets.
+    pub weight_at_hardest: u32,
+}
+impl ObstaclePattern {
+    /// How likely the pattern is to be picked when the game is `progress_percent` of the way to its hardest.
+    pub fn weight(&self, progress_percent: u8) -> u32 {
+        let progress_percent = progress_percent.min(100) as i64;
+        let weight_at_start = self.weight_at_start as i64;
+        let weight_at_hardest = self.weight_at_hardest as i64;
+
+        (weight_at_start + (weight_at_hardest - weight_at_start) * progress_percent / 100) as u32
+    }
+
+    /// How many cells across the pattern is.
+    pub fn width(&self) -> u64 {
+        self.obstacles
+            .iter()
+            .map(|obstacle| obstacle.x_offset() as u64 + obstacle.width())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Every pattern obstacles are spawned in. A single jump stays in the air for about 14 ticks and tops out 4 cells up,
+/// and the screen can scroll as fast as a cell a tick, so the gaps the player has to land in are at least 16 cells
+/// wide.
+pub const OBSTACLE_PATTERNS: &[ObstaclePattern] = &[
+    ObstaclePattern {
+        name: "lone",
+        obstacles: &[PatternObstacle::Random { x_offset: 0 }],
+        weight_at_start: 100,
+        weight_at_hardest: 40,
+    },
+    ObstaclePattern {
+        name: "hurdle",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: 0,
+            rows: &["##"],
+        }],
+        weight_at_start: 15,
+        weight_at_hardest: 20,
+    },
+    ObstaclePattern {
+        name: "tall-hurdle",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: 0,
+            rows: &["#", "#"],
+        }],
+        weight_at_start: 10,
+        weight_at_hardest: 20,
+    },
+    ObstaclePattern {
+        name: "ground-pair",
+        obstacles: &[
+            PatternObstacle::Ground {
+                x_offset: 0,
+                height: 0,
+            },
+            PatternObstacle::Ground {
+                x_offset: 18,
+                height: 0,
+            },
+        ],
+        weight_at_start: 20,
+        weight_at_hardest: 30,
+    },
+    ObstaclePattern {
+        name: "staircase",
+        obstacles: &[
+            PatternObstacle::Ground {
+                x_offset: 0,
+                height: 0,
+            },
+            PatternObstacle::Shape {
+                x_offset: 16,
+                height: 0,
+                rows: &["#", "#"],
+            },
+            PatternObstacle::Shape {
+                x_offset: 32,
+                height: 0,
+                rows: &["#", "#", "#"],
+            },
+        ],
+        weight_at_start: 0,
+        weight_at_hardest: 15,
+    },
+    ObstaclePattern {
+        name: "ground-air-ground",
+        obstacles: &[
+            PatternObstacle::Ground {
+                x_offset: 0,
+                height: 0,
+            },
+            PatternObstacle::Air { x_offset: 16 },
+            PatternObstacle::Ground {
+                x_offset: 21,
+                height: 0,
+            },
+        ],
+        weight_at_start: 5,
+        weight_at_hardest: 20,
+    },
+    ObstaclePattern {
+        name: "double-jump-wall",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: 0,
+            rows: &["######"],
+        }],
+        weight_at_start: 0,
+        weight_at_hardest: 10,
+    },
+    ObstaclePattern {
+        name: "tall-wall",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: 0,
+            rows: &["#", "#", "#", "#", "#", "#"],
+        }],
+        weight_at_start: 0,
+        weight_at_hardest: 10,
+    },
+    ObstaclePattern {
+        name: "wide-block",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: 0,
+            rows: &["####", "####", "####"],
+        }],
+        weight_at_start: 0,
+        weight_at_hardest: 10,
+    },
+    ObstaclePattern {
+        name: "overhang",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: AIR_OBSTACLE_HEIGHT,
+            rows: &["########", "########", "########", "########", "########"],
+        }],
+        weight_at_start: 0,
+        weight_at_hardest: 15,
+    },
+    ObstaclePattern {
+        name: "flyer-and-hurdle",
+        obstacles: &[
+            PatternObstacle::MovingAir { x_offset: 0 },
+            PatternObstacle::Ground {
+                x_offset: 10,
+                height: 0,
+            },
+        ],
+        weight_at_start: 0,
+        weight_at_hardest: 15,
+    },
+    ObstaclePattern {
+        name: "low-bar",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: 1,
+            rows: &["###"],
+        }],
+        weight_at_start: 5,
+        weight_at_hardest: 15,
+    },
+    ObstaclePattern {
+        name: "low-ceiling",
+        obstacles: &[PatternObstacle::Shape {
+            x_offset: 0,
+            height: 1,
+            rows: &["############", "############"],
+        }],
+        weight_at_start: 0,
+        weight_at_hardest: 10,
+    },
+    ObstaclePattern {
+        name: "pit",
+        obstacles: &[PatternObstacle::Pit {
+            x_offset: 0,
+            width: 3,
+        }],
+        weight_at_start: 10,
+        weight_at_hardest: 15,
+    },
+    ObstaclePattern {
+        name: "wide-pit",
+        obstacles: &[PatternObstacle::Pit {
+            x_offset: 0,
+            width: 6,
+        }],
+        weight_at_start: 0,
+        weight_at_hardest: 10,
+    },
+    ObstaclePattern {
+        name: "pit-and-hurdle",
+        obstacles: &[
+            PatternObstacle::Pit {
+                x_offset: 0,
+                width: 3,
+            },
+            PatternObstacle::Ground {
+                x_offset: 19,
+                height: 0,
+            },
+        ],
+        weight_at_start: 0,
+        weight_at_hardest: 15,
+    },
+];
+
+/// Picks one of `patterns`, weighted by how likely each is when the game is `progress_percent` of the way to its
+/// hardest.
+///
+/// # Panics
+///
+/// Panics if none of the patterns can be picked at `progress_percent`.
+pub fn pick_obstacle_pattern<'a>(
+    patterns: &'a [ObstaclePattern],
+    progress_percent: u8,
+    rng: &mut impl Rng,
+) -> &'a ObstaclePattern {
+    let total_weight: u32 = patterns
+        .iter()
+        .map(|pattern| pattern.weight(progress_percent))
+        .sum();
+
+    assert!(total_weight > 0, "at least one pattern must have a weight");
+
+    let mut roll = rng.gen_range(0..total_weight);
+
+    patterns
+        .iter()
+        .find(|pattern| {
+            let weight = pattern.weight(progress_percent);
+
+            if roll < weight {
+                true
+            } else {
+                roll -= weight;
+                false
+            }
+        })
+        .expect("The roll is less than the total weight of the patterns.")
+}
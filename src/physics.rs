@@ -0,0 +1,118 @@
+use crate::{GameConfig, TICKS_PER_SECOND};
+
+/// How the player's moving up and down, and whether they're sliding. The player's systems and the course checks in
+/// `fairness` both move it on with `PlayerMotion::step`, so the checks play out the same way the game does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PlayerMotion {
+    /// How fast the player is falling, in cells per second. Rising is negative.
+    pub vertical_velocity: i64,
+    /// How many ticks it's been since gravity last sped up the player's fall.
+    pub gravity_ticks: u64,
+    /// How many ticks it's been since the player last moved up or down a cell.
+    pub velocity_ticks: u64,
+    /// How many times the player has jumped since they were last on the ground.
+    pub air_jumps: u8,
+    /// Whether the player is sliding along the ground, low enough to pass under obstacles their head would hit.
+    pub is_sliding: bool,
+    /// How many ticks it's been since the player started sliding.
+    pub slide_ticks: u64,
+}
+impl PlayerMotion {
+    /// Advances the motion's timers by a tick of game time.
+    pub fn tick(&mut self) {
+        self.gravity_ticks += 1;
+        self.velocity_ticks += 1;
+        self.slide_ticks += 1;
+    }
+
+    /// Moves the player on by a tick with `input` pressed. `is_on_ground` is whether they were standing on solid ground
+    /// at the end of the last tick, and `max_air_jumps` is how many times they can jump before they land again.
+    pub fn step(
+        &mut self,
+        config: &GameConfig,
+        input: MotionInput,
+        is_on_ground: bool,
+        max_air_jumps: u8,
+    ) -> MotionStep {
+        if is_on_ground {
+            self.vertical_velocity = 0;
+            self.air_jumps = 0;
+        } else {
+            if is_rate_due(self.gravity_ticks, config.gravity as u64) {
+                self.vertical_velocity += config.gravity as i64;
+                self.gravity_ticks = 0;
+            }
+
+            if !input.was_jump_held {
+                self.vertical_velocity = self
+                    .vertical_velocity
+                    .max(-(config.short_jump_force as i64));
+            }
+        }
+
+        // Slides run out after a while, and as soon as the player leaves the ground.
+        if self.is_sliding && (!is_on_ground || self.slide_ticks >= config.slide_ticks) {
+            self.is_sliding = false;
+        }
+
+        let mut jump_option = None;
+
+        if input.is_jump_down && (is_on_ground || self.air_jumps < max_air_jumps) {
+            jump_option = Some(if is_on_ground {
+                Jump::FromGround
+            } else {
+                self.air_jumps += 1;
+                Jump::InAir
+            });
+
+            self.vertical_velocity = -(config.jump_force as i64);
+            self.is_sliding = false;
+        } else if input.is_slide_down && is_on_ground {
+            self.is_sliding = true;
+            self.slide_ticks = 0;
+        }
+
+        let mut climb = 0;
+
+        if self.vertical_velocity != 0
+            && is_rate_due(self.velocity_ticks, self.vertical_velocity.unsigned_abs())
+        {
+            climb = if self.vertical_velocity > 0 { -1 } else { 1 };
+            self.velocity_ticks = 0;
+        }
+
+        MotionStep { jump_option, climb }
+    }
+}
+
+/// What the player's pressing on a tick, as far as moving goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MotionInput {
+    /// Whether jump was pressed down this tick.
+    pub is_jump_down: bool,
+    /// Whether slide was pressed down this tick.
+    pub is_slide_down: bool,
+    /// Whether jump was held down on the tick before. Letting go of it cuts a jump short.
+    pub was_jump_held: bool,
+}
+
+/// Where the player jumped from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Jump {
+    FromGround,
+    InAir,
+}
+
+/// What came of moving the player on by a tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MotionStep {
+    /// The jump the player made, if they made one.
+    pub jump_option: Option<Jump>,
+    /// How many cells the player went up, or down when it's negative.
+    pub climb: i64,
+}
+
+/// Whether enough ticks have passed for something happening `rate_per_second` times a second to happen again.
+pub fn is_rate_due(ticks: u64, rate_per_second: u64) -> bool {
+    ticks * rate_per_second >= TICKS_PER_SECOND as u64
+}
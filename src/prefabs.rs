@@ -1,37 +1,46 @@
-use std::ops::RangeInclusive;
+use std::rc::Rc;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use thomas::{
-    Alignment, Component, Dimensions2d, GameCommand, GameCommandsArg, Identity, IntCoords2d,
-    IntVector2, Layer, Matrix, Rgb, TerminalCollider, TerminalRenderer, TerminalTransform, Text,
-    Timer, UiAnchor, WorldText,
+    Alignment, Component, Dimensions2d, Identity, IntCoords2d, IntVector2, Layer, Matrix, Rgb,
+    TerminalCollider, TerminalRenderer, TerminalTransform, Text, UiAnchor, WorldText,
 };
 
 use crate::{
-    components::{CleanupOnScreenExit, FollowCamera, Moveable, SkylineBuilding},
-    ALTERNATE_BUILDING_COLOR, BUILDING_COLOR, BUILDING_PIECE_NAME, DEFEAT_TEXT_NAME,
-    DISTANCE_MARKER_PIECE_NAME, OBSTACLE_BACKGROUND_COLOR, OBSTACLE_COLLISION_LAYER, OBSTACLE_NAME,
-    PAUSED_TEXT_NAME, PLAYER_COLLISION_LAYER, PLAYER_LIFE_DISPLAY, PLAYER_Y_OFFSET, SCREEN_HEIGHT,
-    SCREEN_WIDTH, SKYLINE_LAYER, START_PLAYING_TEXT_NAME, VICTORY_TEXT_NAME, WINDOW_COLOR,
+    components::{
+        BonusPopup, CleanupOnScreenExit, Coin, Enemy, Flight, FollowCamera, Moveable, Obstacle,
+        Pickup, PickupKind, Pit, SkylineBuilding,
+    },
+    Bonus, FlightPath, GameCommand, GameCommandsArg, GameMode, Resolution, TickTimer, Upgrade,
+    Wallet, ALTERNATE_BUILDING_COLOR, BONUS_POPUP_COLOR, BUILDING_COLOR, BUILDING_PIECE_NAME,
+    COIN_COLOR, COIN_DISPLAY, DEFEAT_TEXT_NAME, DISTANCE_MARKER_PIECE_NAME, ENEMY_COLOR,
+    ENEMY_DISPLAY, ENEMY_HOVER_HEIGHT, MILESTONE_COLOR, MILESTONE_TEXT_NAME,
+    OBSTACLE_BACKGROUND_COLOR, OBSTACLE_COLLISION_LAYER, OBSTACLE_NAME, PAUSED_TEXT_NAME,
+    PICKUP_COLLISION_LAYER, PICKUP_HEIGHT, PIT_NAME, PROJECTILE_COLOR, PROJECTILE_DISPLAY,
+    SHOP_TEXT_NAME, SKYLINE_LAYER, START_PLAYING_TEXT_NAME, UNAFFORDABLE_UPGRADE_COLOR,
+    VICTORY_TEXT_NAME, WINDOW_COLOR,
 };
 
-const OBSTACLE_MOVE_INTERVAL_RANGE_MILLIS: RangeInclusive<u128> = 300..=800;
-
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum ObstacleType {
     Ground,
     Air,
+    /// Fired at the player by an enemy.
+    Projectile,
 }
 
-pub fn add_building(commands: GameCommandsArg, x_coord: i64, size: Dimensions2d) {
+pub fn add_building(
+    commands: GameCommandsArg,
+    rng: &mut impl Rng,
+    resolution: &Resolution,
+    x_coord: i64,
+    size: Dimensions2d,
+) {
     let building_shape_matrix = Matrix::new(size, || ());
 
-    let start_coords = IntCoords2d::new(
-        x_coord,
-        SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET - size.height() as i64 + 1,
-    );
+    let start_coords = IntCoords2d::new(x_coord, resolution.ground_y() - size.height() as i64 + 1);
 
-    let background_color = if thread_rng().gen_bool(0.5) {
+    let background_color = if rng.gen_bool(0.5) {
         BUILDING_COLOR
     } else {
         ALTERNATE_BUILDING_COLOR
@@ -47,7 +56,7 @@ pub fn add_building(commands: GameCommandsArg, x_coord: i64, size: Dimensions2d)
                 foreground_color: Some(WINDOW_COLOR),
                 background_color: Some(background_color),
             }),
-            Box::new(TerminalTransform { coords: coords }),
+            Box::new(TerminalTransform { coords }),
             Box::new(FollowCamera {
                 base_position: coords,
                 offset: IntCoords2d::zero(),
@@ -63,53 +72,124 @@ pub fn add_building(commands: GameCommandsArg, x_coord: i64, size: Dimensions2d)
     }
 }
 
-pub fn make_obstacle(
-    main_cam_transform: &TerminalTransform,
+/// Adds an obstacle in the shape of `shape`, with the bottom left corner of the shape at `bottom_left_coords`. Every
+/// cell of the shape collides, and all of them share `obstacle_id` so the obstacle is hit as a whole. The obstacle
+/// moves towards the player a cell every `move_interval_option` ticks if there's an interval.
+pub fn add_obstacle(
+    commands: GameCommandsArg,
+    obstacle_id: u64,
     typ: ObstacleType,
-) -> Vec<Box<dyn Component>> {
-    let mut comps: Vec<Box<dyn Component>> = vec![
+    shape: &Matrix<bool>,
+    bottom_left_coords: IntCoords2d,
+    move_interval_option: Option<u64>,
+    flight_path: FlightPath,
+) {
+    let start_coords =
+        bottom_left_coords + IntCoords2d::new(0, -(shape.dimensions().height() as i64 - 1));
+
+    for cell in shape.iter().filter(|cell| *cell.data()) {
+        let renderer = match typ {
+            ObstacleType::Projectile => TerminalRenderer {
+                background_color: None,
+                foreground_color: Some(PROJECTILE_COLOR),
+                display: PROJECTILE_DISPLAY,
+                layer: Layer::base(),
+            },
+            _ => TerminalRenderer {
+                background_color: Some(OBSTACLE_BACKGROUND_COLOR),
+                foreground_color: None,
+                display: ' ',
+                layer: Layer::base(),
+            },
+        };
+
+        let mut comps: Vec<Box<dyn Component>> = vec![
+            Box::new(renderer),
+            Box::new(TerminalTransform {
+                coords: start_coords + *cell.location(),
+            }),
+            Box::new(TerminalCollider {
+                is_active: true,
+                layer: OBSTACLE_COLLISION_LAYER,
+            }),
+            Box::new(Identity {
+                id: String::from(""),
+                name: String::from(OBSTACLE_NAME),
+            }),
+            Box::new(Obstacle {
+                obstacle_id,
+                obstacle_type: typ,
+                is_dodged: false,
+                is_near_missed: false,
+            }),
+            Box::new(CleanupOnScreenExit {}),
+        ];
+
+        if let Some(move_interval) = move_interval_option {
+            comps.push(Box::new(Moveable {
+                move_timer: TickTimer::start_new(),
+                move_interval,
+            }));
+            comps.push(Box::new(Flight {
+                path: flight_path,
+                timer: TickTimer::start_new(),
+            }));
+        }
+
+        commands.borrow_mut().issue(GameCommand::AddEntity(comps));
+    }
+}
+
+/// Adds an enemy just off the right edge of the screen, at the height it hovers at.
+pub fn add_enemy(commands: GameCommandsArg, resolution: &Resolution) {
+    let base_position = IntCoords2d::new(
+        resolution.width() as i64 + 1,
+        resolution.ground_y() - ENEMY_HOVER_HEIGHT,
+    );
+
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Enemy {
+            timer: TickTimer::start_new(),
+            shot_timer: TickTimer::start_new(),
+            lane_option: None,
+        }),
         Box::new(TerminalRenderer {
-            background_color: Some(OBSTACLE_BACKGROUND_COLOR),
-            foreground_color: None,
-            display: ' ',
+            display: ENEMY_DISPLAY,
             layer: Layer::base(),
+            background_color: None,
+            foreground_color: Some(ENEMY_COLOR),
         }),
         Box::new(TerminalTransform {
-            coords: IntCoords2d::new(
-                main_cam_transform.coords.x() + SCREEN_WIDTH as i64 + 1,
-                match typ {
-                    ObstacleType::Ground => SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET,
-                    ObstacleType::Air => SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET - 2,
-                },
-            ),
+            coords: base_position,
         }),
-        Box::new(TerminalCollider {
-            is_active: true,
-            layer: OBSTACLE_COLLISION_LAYER,
+        Box::new(FollowCamera {
+            base_position,
+            offset: IntCoords2d::zero(),
+        }),
+    ]));
+}
+
+/// Adds a pit `width` cells wide to the ground, with its left edge at `x`.
+pub fn add_pit(commands: GameCommandsArg, resolution: &Resolution, x: i64, width: u64) {
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(TerminalTransform {
+            coords: IntCoords2d::new(x, resolution.ground_y() + 1),
         }),
         Box::new(Identity {
             id: String::from(""),
-            name: String::from(OBSTACLE_NAME),
+            name: String::from(PIT_NAME),
         }),
+        Box::new(Pit { width }),
         Box::new(CleanupOnScreenExit {}),
-    ];
-
-    if typ == ObstacleType::Air {
-        comps.push(Box::new(Moveable {
-            move_timer: Timer::start_new(),
-            move_interval: thread_rng().gen_range(OBSTACLE_MOVE_INTERVAL_RANGE_MILLIS),
-        }));
-    }
-
-    comps
+    ]));
 }
 
-pub fn add_distance_marker(commands: GameCommandsArg, distance: u64) {
+pub fn add_distance_marker(commands: GameCommandsArg, resolution: &Resolution, distance: u64) {
     let board_matrix = Matrix::new(Dimensions2d::new(1, 5), || ());
 
     let base_x_pos = distance;
 
-    let board_y_pos: i64 = SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET as i64 - 1;
+    let board_y_pos: i64 = resolution.ground_y() - 1;
 
     for cell in &board_matrix {
         commands.borrow_mut().issue(GameCommand::AddEntity(vec![
@@ -136,7 +216,7 @@ pub fn add_distance_marker(commands: GameCommandsArg, distance: u64) {
         let support_x_pos = if i == 0 {
             base_x_pos as i64
         } else {
-            (base_x_pos as u64 + board_matrix.dimensions().width() - 1) as i64
+            (base_x_pos + board_matrix.dimensions().width() - 1) as i64
         };
 
         commands.borrow_mut().issue(GameCommand::AddEntity(vec![
@@ -176,34 +256,115 @@ pub fn add_distance_marker(commands: GameCommandsArg, distance: u64) {
     ]))
 }
 
-pub fn make_extra_life(main_cam_transform: &TerminalTransform) -> Vec<Box<dyn Component>> {
+pub fn make_pickup(
+    kind: PickupKind,
+    main_cam_transform: &TerminalTransform,
+    resolution: &Resolution,
+) -> Vec<Box<dyn Component>> {
     vec![
+        Box::new(Pickup { kind }),
         Box::new(TerminalCollider {
             is_active: true,
-            layer: PLAYER_COLLISION_LAYER,
+            layer: PICKUP_COLLISION_LAYER,
         }),
         Box::new(TerminalRenderer {
-            display: PLAYER_LIFE_DISPLAY,
+            display: kind.display(),
             layer: Layer::base(),
             background_color: None,
-            foreground_color: Some(Rgb(232, 23, 255)),
+            foreground_color: Some(kind.color()),
         }),
         Box::new(TerminalTransform {
             coords: IntCoords2d::new(
-                main_cam_transform.coords.x() + SCREEN_WIDTH as i64 + 1,
-                SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET - 4,
+                main_cam_transform.coords.x() + resolution.width() as i64 + 1,
+                resolution.ground_y() - PICKUP_HEIGHT,
             ),
         }),
+        Box::new(CleanupOnScreenExit {}),
     ]
 }
 
-pub fn add_start_playing_text(commands: GameCommandsArg) {
+pub fn add_coin(commands: GameCommandsArg, coords: IntCoords2d) {
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Coin {}),
+        Box::new(TerminalCollider {
+            is_active: true,
+            layer: PICKUP_COLLISION_LAYER,
+        }),
+        Box::new(TerminalRenderer {
+            display: COIN_DISPLAY,
+            layer: Layer::base(),
+            background_color: None,
+            foreground_color: Some(COIN_COLOR),
+        }),
+        Box::new(TerminalTransform { coords }),
+        Box::new(CleanupOnScreenExit {}),
+    ]));
+}
+
+/// Floats the points for a bonus up from `coords`, written like "+50 NEAR MISS".
+pub fn add_bonus_popup(commands: GameCommandsArg, coords: IntCoords2d, points: u64, bonus: Bonus) {
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(BonusPopup {
+            origin: coords,
+            timer: TickTimer::start_new(),
+        }),
+        Box::new(WorldText {
+            value: format!("+{} {}", points, bonus.title()),
+            justification: Alignment::Middle,
+            offset: IntCoords2d::zero(),
+            foreground_color: Some(BONUS_POPUP_COLOR),
+            background_color: None,
+        }),
+        Box::new(TerminalTransform { coords }),
+    ]));
+}
+
+/// Shows the start screen. `difficulty_choice` is the difficulty that's picked, written how it should be shown.
+pub fn add_start_playing_text(
+    commands: GameCommandsArg,
+    difficulty_choice: &str,
+    mode: GameMode,
+    victory_score: u64,
+    best_score_option: Option<u64>,
+) {
+    if let Some(best_score) = best_score_option {
+        add_high_score_text(
+            Rc::clone(&commands),
+            START_PLAYING_TEXT_NAME,
+            &format!("Best: {}", best_score),
+            Rgb::white(),
+        );
+    }
+
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Text {
+            anchor: UiAnchor::Middle,
+            justification: Alignment::Middle,
+            offset: IntVector2::new(0, -3),
+            value: String::from(difficulty_choice),
+            background_color: None,
+            foreground_color: Some(Rgb::white()),
+        }),
+        Box::new(Identity {
+            id: String::from(""),
+            name: String::from(START_PLAYING_TEXT_NAME),
+        }),
+    ]));
+
+    let goal = match mode {
+        GameMode::Classic => format!(
+            "Get {} points to win!",
+            with_thousands_separators(victory_score)
+        ),
+        GameMode::Endless => String::from("Endless: how far can you go?"),
+    };
+
     add_fullscreen_text(
         commands,
         "RUNNER",
-        "Get 10,000 points to win!",
+        &goal,
         START_PLAYING_TEXT_NAME,
-        Some("Press any key to start"),
+        Some("Press any key to start, or U for the shop"),
     );
 }
 
@@ -217,26 +378,130 @@ pub fn add_paused_text(commands: GameCommandsArg) {
     );
 }
 
-pub fn add_victory_text(commands: GameCommandsArg) {
+pub fn add_victory_text(commands: GameCommandsArg, seed: u64) {
     add_fullscreen_text(
         commands,
         "VICTORY!",
-        "Press R to play again",
+        "Press R to play again, or U for the shop",
         VICTORY_TEXT_NAME,
-        None,
+        Some(&format!("Seed: {}", seed)),
     );
 }
 
-pub fn add_defeat_text(commands: GameCommandsArg) {
+pub fn add_defeat_text(commands: GameCommandsArg, seed: u64) {
     add_fullscreen_text(
         commands,
         "DEFEAT",
-        "Press R to play again",
+        "Press R to play again, or U for the shop",
         DEFEAT_TEXT_NAME,
-        None,
+        Some(&format!("Seed: {}", seed)),
     );
 }
 
+/// Shows the shop, with the upgrade at `selected_index` picked. Upgrades that can't be bought with what's in the wallet
+/// are greyed out.
+pub fn add_shop_text(commands: GameCommandsArg, wallet: &Wallet, selected_index: usize) {
+    let mut lines = vec![(String::from("UPGRADES"), Rgb::white())];
+
+    for (index, upgrade) in Upgrade::ALL.into_iter().enumerate() {
+        let cost_option = wallet.upgrades.next_cost(upgrade);
+        let price = match cost_option {
+            Some(cost) => format!("{} coins", cost),
+            None => String::from("maxed"),
+        };
+        let color = match cost_option {
+            Some(cost) if cost <= wallet.coins => Rgb::white(),
+            _ => UNAFFORDABLE_UPGRADE_COLOR,
+        };
+
+        lines.push((
+            format!(
+                "{} {:<16} {}/{} {:>10}",
+                if index == selected_index { '▸' } else { ' ' },
+                upgrade.title(),
+                wallet.upgrades.level(upgrade),
+                upgrade.max_level(),
+                price
+            ),
+            color,
+        ));
+    }
+
+    for (line_index, (value, color)) in lines.into_iter().enumerate() {
+        commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+            Box::new(Text {
+                anchor: UiAnchor::Middle,
+                justification: Alignment::Middle,
+                // The title gets a blank line under it.
+                offset: IntVector2::new(0, line_index as i64 + i64::from(line_index > 0) - 3),
+                value,
+                background_color: None,
+                foreground_color: Some(color),
+            }),
+            Box::new(Identity {
+                id: String::from(""),
+                name: String::from(SHOP_TEXT_NAME),
+            }),
+        ]));
+    }
+
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Text {
+            anchor: UiAnchor::MiddleBottom,
+            justification: Alignment::Middle,
+            offset: IntVector2::new(0, -2),
+            value: String::from("← → to pick, Space to buy, U to go back"),
+            background_color: None,
+            foreground_color: Some(Rgb::white()),
+        }),
+        Box::new(Identity {
+            id: String::from(""),
+            name: String::from(SHOP_TEXT_NAME),
+        }),
+    ]));
+}
+
+/// Celebrates an endless run passing a milestone, near the top of the screen.
+pub fn add_milestone_text(commands: GameCommandsArg, milestone_score: u64) {
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Text {
+            anchor: UiAnchor::MiddleTop,
+            justification: Alignment::Middle,
+            offset: IntVector2::new(0, 2),
+            value: format!("★ {} POINTS! ★", with_thousands_separators(milestone_score)),
+            background_color: None,
+            foreground_color: Some(MILESTONE_COLOR),
+        }),
+        Box::new(Identity {
+            id: String::from(""),
+            name: String::from(MILESTONE_TEXT_NAME),
+        }),
+    ]));
+}
+
+/// Shows how a run stands against the high scores, along the top of the screen.
+pub fn add_high_score_text(
+    commands: GameCommandsArg,
+    text_name: &str,
+    value: &str,
+    foreground_color: Rgb,
+) {
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Text {
+            anchor: UiAnchor::MiddleTop,
+            justification: Alignment::Middle,
+            offset: IntVector2::new(0, 1),
+            value: String::from(value),
+            background_color: None,
+            foreground_color: Some(foreground_color),
+        }),
+        Box::new(Identity {
+            id: String::from(""),
+            name: String::from(text_name),
+        }),
+    ]));
+}
+
 fn add_fullscreen_text(
     commands: GameCommandsArg,
     top_text: &str,
@@ -291,3 +556,18 @@ fn add_fullscreen_text(
         ]));
     }
 }
+
+fn with_thousands_separators(number: u64) -> String {
+    let digits = number.to_string();
+    let mut separated = String::new();
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            separated.push(',');
+        }
+
+        separated.push(digit);
+    }
+
+    separated
+}
@@ -0,0 +1,321 @@
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use crate::{components::Action, Difficulty, GameConfig, Resolution, Wallet};
+
+const REPLAY_HEADER: &str = "# runner replay";
+
+/// Everything needed to play a run back exactly as it happened: the seed the run was generated from, the difficulty,
+/// config and resolution it was played with, the wallet it started with, and the actions that were performed on each
+/// tick of the game's clock.
+///
+/// Replays are saved as plain text. After the header, there's a `seed` line, ending in `fixed` if every run was
+/// generated from that seed rather than just the first, a `difficulty` line, a `config` line for each setting, a
+/// `wallet` line for each value in the wallet, a `resolution` line, and a `ticks` line, followed by one line for each
+/// tick on which something was pressed, giving the tick and what was pressed on it:
+///
+/// ```text
+/// # runner replay
+/// seed 42
+/// difficulty normal
+/// config gravity 15
+/// config jump_force 50
+/// wallet coins 120
+/// wallet extra_life 1
+/// resolution 80 12
+/// ticks 912
+/// 0 any-key
+/// 57 jump any-key
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    seed: u64,
+    is_seed_fixed: bool,
+    difficulty: Difficulty,
+    config: GameConfig,
+    /// The wallet as it was when recording started. What's bought in the shop is played back along with everything
+    /// else, so it's bought again from this.
+    wallet: Wallet,
+    resolution: Resolution,
+    num_ticks: u64,
+    ticks_to_actions: BTreeMap<u64, Vec<Action>>,
+}
+impl Replay {
+    /// Creates an empty replay of a run generated from `seed` and played at `difficulty` with `config` at `resolution`,
+    /// starting out with `wallet`. See `WorldRng::reseed_for_next_run` for `is_seed_fixed`.
+    pub fn new(
+        seed: u64,
+        is_seed_fixed: bool,
+        difficulty: Difficulty,
+        config: GameConfig,
+        wallet: Wallet,
+        resolution: Resolution,
+    ) -> Self {
+        Self {
+            seed,
+            is_seed_fixed,
+            difficulty,
+            config,
+            wallet,
+            resolution,
+            num_ticks: 0,
+            ticks_to_actions: BTreeMap::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn is_seed_fixed(&self) -> bool {
+        self.is_seed_fixed
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Changes the difficulty and config the run is played with, for when they're picked after recording starts.
+    pub fn set_difficulty(&mut self, difficulty: Difficulty, config: GameConfig) {
+        self.difficulty = difficulty;
+        self.config = config;
+    }
+
+    pub fn wallet(&self) -> Wallet {
+        self.wallet
+    }
+
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    pub fn num_ticks(&self) -> u64 {
+        self.num_ticks
+    }
+
+    /// Adds a tick to the end of the replay on which exactly the provided actions were performed.
+    pub fn record(&mut self, actions: impl IntoIterator<Item = Action>) {
+        let mut actions: Vec<Action> = actions.into_iter().collect();
+
+        if !actions.is_empty() {
+            actions.sort();
+            actions.dedup();
+
+            self.ticks_to_actions.insert(self.num_ticks, actions);
+        }
+
+        self.num_ticks += 1;
+    }
+
+    /// The actions performed on the provided tick. Ticks past the end of the replay have no actions.
+    pub fn actions_at(&self, tick: u64) -> &[Action] {
+        self.ticks_to_actions
+            .get(&tick)
+            .map(|actions| &actions[..])
+            .unwrap_or(&[])
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let contents = fs::read_to_string(path).map_err(ReplayError::Io)?;
+
+        Self::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, ReplayError> {
+        let mut seed_option = None;
+        let mut is_seed_fixed = false;
+        let mut difficulty = Difficulty::Normal;
+        let mut config = GameConfig::default();
+        let mut wallet = Wallet::default();
+        let mut resolution = Resolution::default();
+        let mut num_ticks_option = None;
+        let mut ticks_to_actions = BTreeMap::new();
+
+        for (line_index, line) in contents.lines().enumerate() {
+            let line_number = line_index + 1;
+            let parse_error = |message: String| ReplayError::Parse {
+                line_number,
+                message,
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let first_word = words.next().unwrap_or_default();
+
+            let parse_number = |word_option: Option<&str>| {
+                word_option
+                    .and_then(|word| word.parse::<u64>().ok())
+                    .ok_or_else(|| parse_error(format!("expected a whole number in '{}'", line)))
+            };
+
+            match first_word {
+                "seed" => {
+                    seed_option = Some(parse_number(words.next())?);
+                    is_seed_fixed = match words.next() {
+                        None => false,
+                        Some("fixed") => true,
+                        Some(_) => {
+                            return Err(parse_error(format!(
+                                "expected 'seed <seed>' or 'seed <seed> fixed', got '{}'",
+                                line
+                            )))
+                        }
+                    };
+                }
+                "difficulty" => {
+                    difficulty = words
+                        .next()
+                        .unwrap_or_default()
+                        .parse::<Difficulty>()
+                        .map_err(parse_error)?
+                }
+                "config" => match (words.next(), words.next()) {
+                    (Some(key), Some(value)) => config.set(key, value).map_err(parse_error)?,
+                    _ => {
+                        return Err(parse_error(format!(
+                            "expected 'config <key> <value>', got '{}'",
+                            line
+                        )))
+                    }
+                },
+                "wallet" => match (words.next(), words.next()) {
+                    (Some(key), Some(value)) => wallet.set(key, value).map_err(parse_error)?,
+                    _ => {
+                        return Err(parse_error(format!(
+                            "expected 'wallet <key> <value>', got '{}'",
+                            line
+                        )))
+                    }
+                },
+                "resolution" => {
+                    let width = parse_number(words.next())?;
+                    let height = parse_number(words.next())?;
+
+                    resolution = u16::try_from(width)
+                        .ok()
+                        .zip(u16::try_from(height).ok())
+                        .ok_or_else(|| format!("{}x{} is too big a screen", width, height))
+                        .and_then(|(width, height)| Resolution::new(width, height))
+                        .map_err(parse_error)?;
+                }
+                "ticks" => num_ticks_option = Some(parse_number(words.next())?),
+                _ => {
+                    let tick = parse_number(Some(first_word))?;
+                    let actions = words
+                        .map(|word| word.parse::<Action>().map_err(parse_error))
+                        .collect::<Result<Vec<Action>, ReplayError>>()?;
+
+                    ticks_to_actions.insert(tick, actions);
+                }
+            }
+        }
+
+        let seed = seed_option.ok_or(ReplayError::Parse {
+            line_number: 0,
+            message: String::from("the replay has no seed"),
+        })?;
+        let num_ticks = num_ticks_option.ok_or(ReplayError::Parse {
+            line_number: 0,
+            message: String::from("the replay has no tick count"),
+        })?;
+
+        config.validate().map_err(|error| ReplayError::Parse {
+            line_number: 0,
+            message: error.to_string(),
+        })?;
+
+        if let Some((last_tick, _)) = ticks_to_actions.last_key_value() {
+            if *last_tick >= num_ticks {
+                return Err(ReplayError::Parse {
+                    line_number: 0,
+                    message: format!(
+                        "tick {} is past the end of the replay, which only has {} ticks",
+                        last_tick, num_ticks
+                    ),
+                });
+            }
+        }
+
+        Ok(Self {
+            seed,
+            is_seed_fixed,
+            difficulty,
+            config,
+            wallet,
+            resolution,
+            num_ticks,
+            ticks_to_actions,
+        })
+    }
+}
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", REPLAY_HEADER)?;
+        if self.is_seed_fixed {
+            writeln!(f, "seed {} fixed", self.seed)?;
+        } else {
+            writeln!(f, "seed {}", self.seed)?;
+        }
+        writeln!(f, "difficulty {}", self.difficulty)?;
+        for (key, value) in self.config.entries() {
+            writeln!(f, "config {} {}", key, value)?;
+        }
+        for (key, value) in self.wallet.entries() {
+            writeln!(f, "wallet {} {}", key, value)?;
+        }
+        writeln!(
+            f,
+            "resolution {} {}",
+            self.resolution.width(),
+            self.resolution.height()
+        )?;
+        writeln!(f, "ticks {}", self.num_ticks)?;
+
+        for (tick, actions) in &self.ticks_to_actions {
+            write!(f, "{}", tick)?;
+            for action in actions {
+                write!(f, " {}", action)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The replay isn't in the expected format. A `line_number` of 0 means the problem is with the replay as a whole.
+    Parse {
+        line_number: usize,
+        message: String,
+    },
+}
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "couldn't read the replay: {}", error),
+            ReplayError::Parse {
+                line_number: 0,
+                message,
+            } => write!(f, "invalid replay: {}", message),
+            ReplayError::Parse {
+                line_number,
+                message,
+            } => write!(f, "invalid replay on line {}: {}", line_number, message),
+        }
+    }
+}
+impl std::error::Error for ReplayError {}
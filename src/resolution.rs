@@ -0,0 +1,78 @@
+use crossterm::terminal;
+use thomas::{Component, Dimensions2d};
+
+use crate::PLAYER_Y_OFFSET;
+
+pub const MIN_SCREEN_WIDTH: u16 = 40;
+pub const MIN_SCREEN_HEIGHT: u16 = 10;
+
+/// The size of the screen the game is played on, in cells. Everything in the world is laid out from it.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Resolution {
+    width: u16,
+    height: u16,
+}
+impl Resolution {
+    pub fn new(width: u16, height: u16) -> Result<Self, String> {
+        if width < MIN_SCREEN_WIDTH || height < MIN_SCREEN_HEIGHT {
+            return Err(format!(
+                "the screen must be at least {}x{}, got {}x{}",
+                MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT, width, height
+            ));
+        }
+
+        Ok(Self { width, height })
+    }
+
+    /// The size of the terminal the game is running in, or `None` if it can't be found. Terminals smaller than the
+    /// smallest screen the game can be played on are treated as being that size.
+    pub fn from_terminal() -> Option<Self> {
+        terminal::size().ok().map(|(width, height)| Self {
+            width: width.max(MIN_SCREEN_WIDTH),
+            height: height.max(MIN_SCREEN_HEIGHT),
+        })
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The row the player runs along. Anything sitting on the ground is on this row.
+    pub fn ground_y(&self) -> i64 {
+        self.height as i64 - PLAYER_Y_OFFSET
+    }
+
+    /// How many rows there are above the ground.
+    pub fn background_height(&self) -> u64 {
+        self.height as u64 - PLAYER_Y_OFFSET as u64
+    }
+
+    /// Scales an amount of something meant for the default screen so there's as much of it per column on this screen.
+    pub fn scale_by_width(&self, amount_on_default_screen: u64) -> u64 {
+        amount_on_default_screen * self.width as u64 / Self::default().width as u64
+    }
+
+    /// Scales an amount of something meant for the default screen so there's as much of it per cell on this screen.
+    pub fn scale_by_area(&self, amount_on_default_screen: u64) -> u64 {
+        let default_resolution = Self::default();
+
+        amount_on_default_screen * self.width as u64 * self.height as u64
+            / (default_resolution.width as u64 * default_resolution.height as u64)
+    }
+
+    pub fn dimensions(&self) -> Dimensions2d {
+        Dimensions2d::new(self.height as u64, self.width as u64)
+    }
+}
+impl Default for Resolution {
+    fn default() -> Self {
+        Self {
+            width: 80,
+            height: 12,
+        }
+    }
+}
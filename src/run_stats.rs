@@ -0,0 +1,283 @@
+use std::{
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+use crate::Outcome;
+
+/// What happened over the course of a single run, for working out where and how runs end.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunStats {
+    pub seed: u64,
+    /// The name of the difficulty the run was played at.
+    pub difficulty: &'static str,
+    /// The name of the mode the run was played in.
+    pub mode: &'static str,
+    /// How the run ended. There isn't one until the run is over.
+    pub outcome_option: Option<Outcome>,
+    pub score: u64,
+    /// How many milestones an endless run passed.
+    pub milestones_reached: u64,
+    pub distance_traveled: u64,
+    pub jumps: u64,
+    /// How many of the jumps were made in the air.
+    pub double_jumps: u64,
+    pub ground_obstacles_dodged: u64,
+    pub air_obstacles_dodged: u64,
+    pub projectiles_dodged: u64,
+    pub lives_lost: u64,
+    /// Where and to what each life was lost, in the order they were lost.
+    pub lost_lives: Vec<LostLife>,
+    pub lives_gained: u64,
+    pub power_ups_collected: u64,
+    /// How many hits a shield took in place of a life.
+    pub hits_shielded: u64,
+    pub coins_collected: u64,
+    /// How many obstacles the player made it past with a cell or less to spare.
+    pub near_misses: u64,
+    /// The highest combo multiplier a bonus was scored at.
+    pub best_combo: u64,
+    pub distance_markers_passed: u64,
+    pub paused_millis: u64,
+    /// The hour of the in-game day the run ended in.
+    pub end_hour: u8,
+    pub end_time_of_day: &'static str,
+    /// When the run ended, in seconds since the Unix epoch.
+    pub finished_at: u64,
+}
+impl RunStats {
+    /// Every stat's name and value, in the order they're exported.
+    pub fn entries(&self) -> Vec<(&'static str, StatValue)> {
+        vec![
+            ("seed", StatValue::Number(self.seed)),
+            ("difficulty", StatValue::Text(self.difficulty.to_string())),
+            ("mode", StatValue::Text(self.mode.to_string())),
+            (
+                "outcome",
+                StatValue::Text(
+                    self.outcome_option
+                        .map_or("", |outcome| outcome.name())
+                        .to_string(),
+                ),
+            ),
+            ("score", StatValue::Number(self.score)),
+            (
+                "milestones_reached",
+                StatValue::Number(self.milestones_reached),
+            ),
+            (
+                "distance_traveled",
+                StatValue::Number(self.distance_traveled),
+            ),
+            ("jumps", StatValue::Number(self.jumps)),
+            ("double_jumps", StatValue::Number(self.double_jumps)),
+            (
+                "ground_obstacles_dodged",
+                StatValue::Number(self.ground_obstacles_dodged),
+            ),
+            (
+                "air_obstacles_dodged",
+                StatValue::Number(self.air_obstacles_dodged),
+            ),
+            (
+                "projectiles_dodged",
+                StatValue::Number(self.projectiles_dodged),
+            ),
+            ("lives_lost", StatValue::Number(self.lives_lost)),
+            (
+                "lost_lives",
+                StatValue::Text(
+                    self.lost_lives
+                        .iter()
+                        .map(|lost_life| lost_life.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                ),
+            ),
+            ("lives_gained", StatValue::Number(self.lives_gained)),
+            (
+                "power_ups_collected",
+                StatValue::Number(self.power_ups_collected),
+            ),
+            ("hits_shielded", StatValue::Number(self.hits_shielded)),
+            ("coins_collected", StatValue::Number(self.coins_collected)),
+            ("near_misses", StatValue::Number(self.near_misses)),
+            ("best_combo", StatValue::Number(self.best_combo)),
+            (
+                "distance_markers_passed",
+                StatValue::Number(self.distance_markers_passed),
+            ),
+            ("paused_millis", StatValue::Number(self.paused_millis)),
+            ("end_hour", StatValue::Number(self.end_hour as u64)),
+            (
+                "end_time_of_day",
+                StatValue::Text(self.end_time_of_day.to_string()),
+            ),
+            ("finished_at", StatValue::Number(self.finished_at)),
+        ]
+    }
+
+    /// The stats as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .entries()
+            .into_iter()
+            .map(|(name, value)| match value {
+                StatValue::Number(number) => format!("\"{}\":{}", name, number),
+                StatValue::Text(text) => format!("\"{}\":\"{}\"", name, escape_json(&text)),
+            })
+            .collect();
+
+        format!("{{{}}}", fields.join(","))
+    }
+
+    pub fn csv_header(&self) -> String {
+        self.entries()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<&str>>()
+            .join(",")
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        self.entries()
+            .into_iter()
+            .map(|(_, value)| match value {
+                StatValue::Number(number) => number.to_string(),
+                StatValue::Text(text) => escape_csv(&text),
+            })
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /// Adds the stats to the end of the file at `path`, which collects the stats of every run exported to it. Files
+    /// ending in `.csv` get a CSV row, with a header when the file is new, and anything else gets a line of JSON.
+    ///
+    /// CSV files whose header doesn't match the stats exported now are left alone rather than given rows that don't
+    /// line up with their columns. See `RunStats::check_export_path`.
+    pub fn append_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let is_new_file = Self::check_export_path(&path)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        if is_csv_path(&path) {
+            if is_new_file {
+                writeln!(file, "{}", self.csv_header())?;
+            }
+
+            writeln!(file, "{}", self.to_csv_row())
+        } else {
+            writeln!(file, "{}", self.to_json())
+        }
+    }
+
+    /// Checks that stats can be exported to the file at `path`, and gives whether there's anything in it yet. A CSV
+    /// file that's already been started must have the same header as the stats exported now.
+    pub fn check_export_path(path: impl AsRef<Path>) -> io::Result<bool> {
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(true),
+            Err(e) => return Err(e),
+        };
+
+        if file.metadata()?.len() == 0 {
+            return Ok(true);
+        }
+
+        if is_csv_path(&path) {
+            let mut header = String::new();
+            BufReader::new(file).read_line(&mut header)?;
+
+            if header.trim_end() != Self::default().csv_header() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "its columns aren't the ones exported now, so export to a new file instead",
+                ));
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// What cost the player a life.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifeLostTo {
+    GroundObstacle,
+    AirObstacle,
+    Projectile,
+    Pit,
+}
+impl LifeLostTo {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LifeLostTo::GroundObstacle => "ground_obstacle",
+            LifeLostTo::AirObstacle => "air_obstacle",
+            LifeLostTo::Projectile => "projectile",
+            LifeLostTo::Pit => "pit",
+        }
+    }
+}
+
+/// A life the player lost, to `cause` once they'd made it `distance_traveled` along the course. It's exported as
+/// `cause@distance_traveled`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LostLife {
+    pub cause: LifeLostTo,
+    pub distance_traveled: u64,
+}
+impl fmt::Display for LostLife {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.cause.name(), self.distance_traveled)
+    }
+}
+
+fn is_csv_path(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+}
+
+/// Escapes `text` to go between the quotes of a JSON string.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Quotes `text` for a CSV field if it has anything in it that'd otherwise be read as the end of the field.
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StatValue {
+    Number(u64),
+    /// Text is escaped as it's exported, for whichever format it's exported in.
+    Text(String),
+}
+impl fmt::Display for StatValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatValue::Number(number) => write!(f, "{}", number),
+            StatValue::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
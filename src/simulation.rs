@@ -0,0 +1,486 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    rc::Rc,
+    thread,
+    time::Duration,
+};
+
+use thomas::{
+    Component, IntCoords2d, StoredComponent, StoredComponentList, TerminalCamera,
+    TerminalTransform, Timer, EVENT_AFTER_INIT, EVENT_AFTER_UPDATE, EVENT_BEFORE_UPDATE,
+    EVENT_INIT, EVENT_UPDATE,
+};
+
+use crate::{
+    components::{
+        Action, Bank, ComboTracker, Controls, Flight, FollowCamera, GameManager, GameState,
+        HighScoreTable, Moveable, Obstacle, PickupKind, Pit, Player, PowerUps, RunStatsTracker,
+        WorldRng,
+    },
+    game_systems_generators, make_course, Course, Difficulty, DifficultyMenu, EntityId,
+    GameCommand, GameCommandQueue, GameCommandsArg, GameConfig, HighScores, Query, QueryResult,
+    QueryResultList, Replay, Resolution, RunStats, RunnerInput, SavePaths, System,
+    SystemsGenerator, Wallet, EVENT_AFTER_TICK, EVENT_BEFORE_TICK, EVENT_TICK,
+};
+
+pub struct SimulationOptions {
+    /// The maximum number of frames the simulation steps through in one second. The game's clock ticks once per frame
+    /// in a simulation, so this doesn't change how a run plays out, only how long it takes. A value of 0 steps through frames as quickly
+    /// as possible.
+    pub max_frame_rate: u16,
+    pub seed: u64,
+    /// Whether every run is generated from `seed`. See `WorldRng::reseed_for_next_run`.
+    pub is_seed_fixed: bool,
+    pub difficulty: Difficulty,
+    pub config: GameConfig,
+    pub resolution: Resolution,
+    /// The wallet the game starts with. It isn't saved anywhere.
+    pub wallet: Wallet,
+}
+
+/// A snapshot of where the run stands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulationReport {
+    /// The seed the current run was generated from.
+    pub seed: u64,
+    pub frames: u64,
+    pub game_state: GameState,
+    pub score: u64,
+    /// The points scored on top of the distance the player has made it.
+    pub bonus_score: u64,
+    /// What the next bonus is multiplied by.
+    pub combo_multiplier: u64,
+    pub lives: u8,
+    pub distance_traveled: u64,
+    /// How many cells above the ground the player is.
+    pub height: i64,
+    pub is_on_ground: bool,
+    pub is_sliding: bool,
+    pub is_invulnerable: bool,
+    /// The power-ups the player has going.
+    pub power_ups: Vec<PickupKind>,
+    /// Whether game time will stand still on the next frame, as it does every other frame in slow motion.
+    pub is_next_tick_skipped: bool,
+    /// How many coins the player has picked up this run.
+    pub coins: u64,
+    /// How many milestones an endless run has passed.
+    pub milestones_reached: u64,
+    /// Where the last run to finish placed on the high-score table, if it made it on.
+    pub high_score_place_option: Option<usize>,
+    /// How many coins are in the wallet. The run's coins only go in once it's over.
+    pub wallet_coins: u64,
+    /// The stats of the run so far. The ones describing how the run ended are only filled in once it's over.
+    pub stats: RunStats,
+}
+
+/// Runs the game without a terminal. The simulation registers the same systems the game does when it's played, and
+/// steps through frames on request using scripted actions in place of the keyboard.
+pub struct Simulation {
+    world: World,
+    events_to_systems: HashMap<&'static str, Vec<System>>,
+    options: SimulationOptions,
+    frame_timer: Timer,
+    frames: u64,
+    is_playing: bool,
+}
+impl Simulation {
+    pub fn new(options: SimulationOptions) -> Self {
+        let mut simulation = Self {
+            world: World::new(),
+            events_to_systems: HashMap::new(),
+            options,
+            frame_timer: Timer::new(),
+            frames: 0,
+            is_playing: true,
+        };
+
+        for systems_generator in game_systems_generators(
+            simulation.options.seed,
+            simulation.options.is_seed_fixed,
+            DifficultyMenu::single(
+                simulation.options.difficulty,
+                simulation.options.config.clone(),
+            ),
+            simulation.options.resolution,
+            simulation.options.wallet,
+            HighScores::new(),
+            SavePaths::default(),
+        ) {
+            simulation.add_systems_from_generator(systems_generator.as_ref());
+        }
+
+        for system_list in simulation.events_to_systems.values_mut() {
+            system_list.sort_by(|a, b| a.priority().cmp(b.priority()));
+        }
+
+        simulation.world.add_entity(vec![
+            Box::new(TerminalCamera {
+                field_of_view: simulation.options.resolution.dimensions(),
+                is_main: true,
+            }),
+            Box::new(TerminalTransform {
+                coords: IntCoords2d::zero(),
+            }),
+        ]);
+
+        let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+
+        simulation.trigger_event(EVENT_INIT, Rc::clone(&commands));
+        simulation.trigger_event(EVENT_AFTER_INIT, Rc::clone(&commands));
+
+        simulation
+    }
+
+    /// Runs a fresh simulation for the given number of frames. `script` is asked which actions are being performed
+    /// on each frame.
+    pub fn run(
+        options: SimulationOptions,
+        num_frames: u64,
+        script: impl Fn(u64) -> Vec<Action>,
+    ) -> SimulationReport {
+        let mut simulation = Simulation::new(options);
+
+        for frame in 0..num_frames {
+            simulation.step(script(frame));
+        }
+
+        simulation.report()
+    }
+
+    /// Plays a recorded run back from start to finish, and reports where it ended up.
+    pub fn replay(replay: &Replay, max_frame_rate: u16) -> SimulationReport {
+        Simulation::run(
+            SimulationOptions {
+                max_frame_rate,
+                seed: replay.seed(),
+                is_seed_fixed: replay.is_seed_fixed(),
+                difficulty: replay.difficulty(),
+                config: replay.config().clone(),
+                resolution: replay.resolution(),
+                wallet: replay.wallet(),
+            },
+            replay.num_ticks(),
+            |frame| replay.actions_at(frame).to_vec(),
+        )
+    }
+
+    /// Steps through a single frame in which exactly the provided actions are being performed. The game's clock ticks
+    /// exactly once every frame. Does nothing once the game has quit.
+    pub fn step(&mut self, actions: impl IntoIterator<Item = Action>) {
+        if !self.is_playing {
+            return;
+        }
+
+        self.frame_timer.restart();
+
+        if let Some(mut controls) = self
+            .world
+            .query(&Query::new().has::<Controls>())
+            .try_get_only_mut::<Controls>()
+        {
+            controls.hold(actions);
+        }
+
+        let commands = Rc::new(RefCell::new(GameCommandQueue::new()));
+
+        self.trigger_event(EVENT_BEFORE_UPDATE, Rc::clone(&commands));
+        self.trigger_event(EVENT_BEFORE_TICK, Rc::clone(&commands));
+        self.trigger_event(EVENT_TICK, Rc::clone(&commands));
+        self.trigger_event(EVENT_AFTER_TICK, Rc::clone(&commands));
+        self.trigger_event(EVENT_UPDATE, Rc::clone(&commands));
+        self.trigger_event(EVENT_AFTER_UPDATE, Rc::clone(&commands));
+
+        self.frames += 1;
+
+        self.wait_for_frame();
+    }
+
+    pub fn report(&self) -> SimulationReport {
+        let game_manager_results = self.world.query(&Query::new().has::<GameManager>());
+        let player_results = self.world.query(
+            &Query::new()
+                .has::<Player>()
+                .has::<PowerUps>()
+                .has::<TerminalTransform>(),
+        );
+
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let player = player_results.get_only::<Player>();
+        let power_ups = player_results.get_only::<PowerUps>();
+        let player_transform = player_results.get_only::<TerminalTransform>();
+        let run_stats_tracker_results = self.world.query(&Query::new().has::<RunStatsTracker>());
+        let run_stats_tracker = run_stats_tracker_results.get_only::<RunStatsTracker>();
+        let bank_results = self.world.query(&Query::new().has::<Bank>());
+        let bank = bank_results.get_only::<Bank>();
+        let combo_tracker_results = self.world.query(&Query::new().has::<ComboTracker>());
+        let combo_tracker = combo_tracker_results.get_only::<ComboTracker>();
+        let world_rng_results = self.world.query(&Query::new().has::<WorldRng>());
+        let world_rng = world_rng_results.get_only::<WorldRng>();
+        let high_score_table_results = self.world.query(&Query::new().has::<HighScoreTable>());
+        let high_score_table = high_score_table_results.get_only::<HighScoreTable>();
+
+        SimulationReport {
+            seed: world_rng.seed(),
+            frames: self.frames,
+            game_state: game_manager.game_state.clone(),
+            score: game_manager.score,
+            bonus_score: game_manager.bonus_score,
+            combo_multiplier: combo_tracker.combo.multiplier(),
+            lives: player.lives,
+            distance_traveled: player.distance_traveled,
+            height: self.options.resolution.ground_y() - player_transform.coords.y(),
+            is_on_ground: player.is_on_ground,
+            is_sliding: player.motion.is_sliding,
+            is_invulnerable: player.is_invulnerable,
+            power_ups: power_ups
+                .active
+                .iter()
+                .map(|power_up| power_up.kind)
+                .collect(),
+            is_next_tick_skipped: power_ups.is_next_tick_skipped(),
+            coins: game_manager.coins,
+            milestones_reached: game_manager.milestones_reached,
+            high_score_place_option: high_score_table.latest_place_option,
+            wallet_coins: bank.wallet.coins,
+            stats: run_stats_tracker.stats.clone(),
+        }
+    }
+
+    /// Where the player and the obstacles are, for working out whether and how the player can get past the obstacles
+    /// coming up.
+    pub fn course(&self) -> Course {
+        let game_manager_results = self.world.query(&Query::new().has::<GameManager>());
+        let main_cam_results = self.world.query(
+            &Query::new()
+                .has_where::<TerminalCamera>(|cam| cam.is_main)
+                .has::<TerminalTransform>()
+                .has::<Moveable>(),
+        );
+        let player_results = self
+            .world
+            .query(&Query::new().has::<Player>().has::<FollowCamera>());
+        let still_obstacle_results = self.world.query(
+            &Query::new()
+                .has::<Obstacle>()
+                .has::<TerminalTransform>()
+                .has_no::<Moveable>(),
+        );
+        let moving_obstacle_results = self.world.query(
+            &Query::new()
+                .has::<Obstacle>()
+                .has::<TerminalTransform>()
+                .has::<Moveable>()
+                .has::<Flight>(),
+        );
+        let pit_results = self
+            .world
+            .query(&Query::new().has::<Pit>().has::<TerminalTransform>());
+
+        let controls_results = self.world.query(&Query::new().has::<Controls>());
+
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let controls = controls_results.get_only::<Controls>();
+
+        let mut course = make_course(
+            &game_manager,
+            &self.options.resolution,
+            &main_cam_results,
+            &player_results,
+            &still_obstacle_results,
+            &moving_obstacle_results,
+            &pit_results,
+        );
+
+        // Keys that are still held from the last frame don't do anything until they're let go.
+        course.runner.last_input = if controls.is_action_pressed(&Action::Jump) {
+            RunnerInput::Jump
+        } else if controls.is_action_pressed(&Action::Slide) {
+            RunnerInput::Slide
+        } else {
+            RunnerInput::Nothing
+        };
+
+        course
+    }
+
+    /// Whether the game is still running. The game stops running once something issues `GameCommand::Quit`.
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    fn add_systems_from_generator(&mut self, systems_generator: &dyn SystemsGenerator) {
+        for (event_name, system) in systems_generator.generate() {
+            self.events_to_systems
+                .entry(event_name)
+                .or_default()
+                .push(system);
+        }
+    }
+
+    fn wait_for_frame(&self) {
+        let minimum_frame_time = 1000_u16
+            .checked_div(self.options.max_frame_rate)
+            .unwrap_or(0);
+
+        let elapsed_millis = self.frame_timer.elapsed_millis();
+
+        if elapsed_millis < minimum_frame_time as u128 {
+            thread::sleep(Duration::from_millis(
+                (minimum_frame_time as u128 - elapsed_millis) as u64,
+            ));
+        }
+    }
+
+    fn trigger_event(&mut self, event_name: &'static str, commands: GameCommandsArg) {
+        if let Some(system_list) = self.events_to_systems.get(event_name) {
+            for system in system_list {
+                let queries_results = system
+                    .queries()
+                    .iter()
+                    .map(|query| self.world.query(query))
+                    .collect();
+
+                system.operator()(queries_results, Rc::clone(&commands));
+            }
+
+            self.process_command_queue(commands);
+        }
+    }
+
+    fn process_command_queue(&mut self, commands: GameCommandsArg) {
+        let old_commands = commands.replace(GameCommandQueue::new());
+
+        for command in old_commands {
+            match command {
+                GameCommand::Quit => {
+                    self.is_playing = false;
+                }
+                GameCommand::AddEntity(components) => {
+                    self.world.add_entity(components);
+                }
+                GameCommand::AddComponentsToEntity(entity, components) => {
+                    self.world.add_components_to_entity(&entity, components);
+                }
+                GameCommand::DestroyEntity(entity) => {
+                    self.world.remove_entity(&entity);
+                }
+                GameCommand::RemoveComponentFromEntity(entity, component_name) => self
+                    .world
+                    .remove_component_from_entity(&entity, component_name),
+                GameCommand::TriggerEvent(event_name) => {
+                    self.trigger_event(event_name, Rc::clone(&commands))
+                }
+            }
+        }
+    }
+}
+
+/// The simulation's game world. Thomas keeps its own world to itself, so the simulation tracks entities and their
+/// components here and runs the game's queries against them.
+struct World {
+    entities_to_components: BTreeMap<u64, HashMap<&'static str, StoredComponent>>,
+    components_to_entities: HashMap<&'static str, BTreeSet<u64>>,
+    next_entity_id: u64,
+}
+impl World {
+    fn new() -> Self {
+        Self {
+            entities_to_components: BTreeMap::new(),
+            components_to_entities: HashMap::new(),
+            next_entity_id: 0,
+        }
+    }
+
+    fn add_entity(&mut self, components: Vec<Box<dyn Component>>) {
+        let entity_id = self.next_entity_id;
+        self.next_entity_id += 1;
+
+        self.entities_to_components
+            .insert(entity_id, HashMap::new());
+        self.add_components_to_entity(&EntityId::simulated(entity_id), components);
+    }
+
+    fn add_components_to_entity(&mut self, entity: &EntityId, components: Vec<Box<dyn Component>>) {
+        if let Some(entity_id) = entity.simulated_id() {
+            if let Some(entity_components) = self.entities_to_components.get_mut(&entity_id) {
+                for component in components {
+                    let component_name = component.component_name();
+
+                    entity_components.insert(component_name, Rc::new(RefCell::new(component)));
+                    self.components_to_entities
+                        .entry(component_name)
+                        .or_default()
+                        .insert(entity_id);
+                }
+            }
+        }
+    }
+
+    fn remove_entity(&mut self, entity: &EntityId) {
+        if let Some(entity_id) = entity.simulated_id() {
+            if let Some(entity_components) = self.entities_to_components.remove(&entity_id) {
+                for component_name in entity_components.keys() {
+                    if let Some(entity_ids) = self.components_to_entities.get_mut(component_name) {
+                        entity_ids.remove(&entity_id);
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove_component_from_entity(&mut self, entity: &EntityId, component_name: &'static str) {
+        if let Some(entity_id) = entity.simulated_id() {
+            if let Some(entity_components) = self.entities_to_components.get_mut(&entity_id) {
+                entity_components.remove(component_name);
+            }
+            if let Some(entity_ids) = self.components_to_entities.get_mut(component_name) {
+                entity_ids.remove(&entity_id);
+            }
+        }
+    }
+
+    fn query(&self, query: &Query) -> QueryResultList {
+        let allowed_component_names = query.allowed_component_names();
+
+        // Only the entities with the query's rarest component can match, so there's no need to look at any others.
+        let candidate_entity_ids: Vec<u64> = match allowed_component_names
+            .iter()
+            .map(|component_name| self.components_to_entities.get(component_name))
+            .min_by_key(|entity_ids_option| {
+                entity_ids_option.map_or(0, |entity_ids| entity_ids.len())
+            }) {
+            Some(Some(entity_ids)) => entity_ids.iter().copied().collect(),
+            Some(None) => vec![],
+            None => self.entities_to_components.keys().copied().collect(),
+        };
+
+        QueryResultList::new(
+            candidate_entity_ids
+                .into_iter()
+                .map(|entity_id| (entity_id, &self.entities_to_components[&entity_id]))
+                .filter(|(_, entity_components)| {
+                    allowed_component_names.iter().all(|component_name| {
+                        entity_components
+                            .get(component_name)
+                            .is_some_and(|component| query.passes_predicates(&**component.borrow()))
+                    }) && !query
+                        .forbidden_component_names()
+                        .iter()
+                        .any(|component_name| entity_components.contains_key(component_name))
+                })
+                .map(|(entity_id, entity_components)| {
+                    QueryResult::new(
+                        EntityId::simulated(entity_id),
+                        StoredComponentList::new(
+                            allowed_component_names
+                                .iter()
+                                .map(|component_name| Rc::clone(&entity_components[component_name]))
+                                .collect(),
+                        ),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
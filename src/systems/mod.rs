@@ -1,3 +1,15 @@
+mod sys_collisions;
+pub use sys_collisions::*;
+
+mod sys_controls;
+pub use sys_controls::*;
+
+mod sys_replay;
+pub use sys_replay::*;
+
+mod sys_ticks;
+pub use sys_ticks::*;
+
 mod sys_player;
 pub use sys_player::*;
 
@@ -10,11 +22,35 @@ pub use sys_world_setup::*;
 mod sys_game_objects;
 pub use sys_game_objects::*;
 
+mod sys_enemies;
+pub use sys_enemies::*;
+
+mod sys_pickups;
+pub use sys_pickups::*;
+
+mod sys_coins;
+pub use sys_coins::*;
+
+mod sys_combo;
+pub use sys_combo::*;
+
 mod sys_hud;
 pub use sys_hud::*;
 
+mod sys_high_scores;
+pub use sys_high_scores::*;
+
+mod sys_wallet;
+pub use sys_wallet::*;
+
+mod sys_shop;
+pub use sys_shop::*;
+
 mod sys_game_manager;
 pub use sys_game_manager::*;
 
 mod sys_follow_camera;
-pub use sys_follow_camera::*;
\ No newline at end of file
+pub use sys_follow_camera::*;
+
+mod sys_run_stats;
+pub use sys_run_stats::*;
@@ -0,0 +1,162 @@
+use std::rc::Rc;
+
+use rand::Rng;
+use thomas::{IntCoords2d, TerminalCamera, TerminalTransform};
+
+use crate::{
+    add_coin,
+    components::{
+        Coin, Collision, GameManager, GameObjectManager, Obstacle, Pit, RunStatsTracker, WorldRng,
+    },
+    GameCommand, GameCommandsArg, GameConfig, Query, QueryResultList, Resolution, System,
+    SystemsGenerator, COIN_FORMATIONS, EVENT_DIFFICULTY_CHANGE, EVENT_RESTART, EVENT_TICK,
+    PICKUP_COLLISION_LAYER, PLAYER_COLLISION_LAYER,
+};
+
+pub struct CoinsSystemsGenerator {}
+impl SystemsGenerator for CoinsSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<GameObjectManager>(),
+                        Query::new().has::<GameManager>(),
+                        Query::new()
+                            .has_where::<TerminalCamera>(|cam| cam.is_main)
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Resolution>(),
+                        Query::new().has::<Obstacle>().has::<TerminalTransform>(),
+                        Query::new().has::<Pit>().has::<TerminalTransform>(),
+                    ],
+                    generate_coins,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<GameManager>(),
+                        Query::new().has_where::<Collision>(|coll| {
+                            coll.is_collision_between(
+                                PLAYER_COLLISION_LAYER,
+                                PICKUP_COLLISION_LAYER,
+                            )
+                        }),
+                        Query::new().has::<RunStatsTracker>(),
+                        Query::new().has::<Coin>(),
+                    ],
+                    handle_coin_collision,
+                ),
+            ),
+            (
+                EVENT_RESTART,
+                System::new(vec![Query::new().has::<Coin>()], remove_coins),
+            ),
+            (
+                EVENT_DIFFICULTY_CHANGE,
+                System::new(vec![Query::new().has::<Coin>()], remove_coins),
+            ),
+        ]
+    }
+}
+
+/// Lays out a formation of coins just off the right edge of the screen. Coins are only laid out where they'll be
+/// clear of obstacles and pits, so there mustn't be any still to come on the screen, and the next obstacle mustn't be
+/// due before the whole formation has scrolled on.
+fn generate_coins(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_object_manager_results, game_manager_results, main_cam_results, world_rng_results, config_results, resolution_results, obstacle_results, pit_results, ..] =
+        &results[..]
+    {
+        let mut game_object_manager =
+            game_object_manager_results.get_only_mut::<GameObjectManager>();
+        let config = config_results.get_only::<GameConfig>();
+
+        if game_object_manager.coin_generation_timer.elapsed_ticks() >= config.coin_wait_ticks {
+            let game_manager = game_manager_results.get_only::<GameManager>();
+            let main_cam_transform = main_cam_results.get_only::<TerminalTransform>();
+            let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+            let resolution = resolution_results.get_only::<Resolution>();
+
+            let roll = world_rng.gen_range(0..100_u8);
+
+            if roll < config.coin_chance && game_manager.is_playing() {
+                let formation = COIN_FORMATIONS[world_rng.gen_range(0..COIN_FORMATIONS.len())];
+                let spawn_x = main_cam_transform.coords.x() + resolution.width() as i64 + 1;
+
+                let formation_scroll_ticks = formation.width(&config, game_manager.distance())
+                    * config.camera_scroll_wait_ticks(game_manager.distance());
+                let obstacle_wait_ticks_left =
+                    game_object_manager.next_obstacle_wait_ticks.saturating_sub(
+                        game_object_manager
+                            .obstacle_generation_timer
+                            .elapsed_ticks(),
+                    );
+
+                let is_hazard_ahead = obstacle_results.iter().any(|result| {
+                    result.components().get::<TerminalTransform>().coords.x() >= spawn_x
+                }) || pit_results.iter().any(|result| {
+                    result.components().get::<TerminalTransform>().coords.x()
+                        + result.components().get::<Pit>().width as i64
+                        > spawn_x
+                });
+
+                if !is_hazard_ahead && obstacle_wait_ticks_left >= formation_scroll_ticks {
+                    for (x_offset, height) in formation.coin_cells(&config, game_manager.distance())
+                    {
+                        add_coin(
+                            Rc::clone(&commands),
+                            IntCoords2d::new(spawn_x + x_offset, resolution.ground_y() - height),
+                        );
+                    }
+                }
+            }
+
+            game_object_manager.coin_generation_timer.restart();
+        }
+    }
+}
+
+fn handle_coin_collision(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_manager_results, collision_results, run_stats_tracker_results, coin_results, ..] =
+        &results[..]
+    {
+        if !collision_results.is_empty() {
+            let mut game_manager = game_manager_results.get_only_mut::<GameManager>();
+            let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+
+            for collision_result in collision_results {
+                let coin_entity = collision_result
+                    .components()
+                    .get::<Collision>()
+                    .get_entity_on_layer(PICKUP_COLLISION_LAYER)
+                    .unwrap();
+
+                if coin_results
+                    .iter()
+                    .any(|result| *result.entity() == coin_entity)
+                {
+                    game_manager.coins += 1;
+                    run_stats_tracker.stats.coins_collected += 1;
+
+                    commands
+                        .borrow_mut()
+                        .issue(GameCommand::DestroyEntity(coin_entity));
+                }
+            }
+        }
+    }
+}
+
+fn remove_coins(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [coin_results, ..] = &results[..] {
+        for coin_result in coin_results {
+            commands
+                .borrow_mut()
+                .issue(GameCommand::DestroyEntity(*coin_result.entity()));
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use thomas::{TerminalCollider, TerminalTransform};
+
+use crate::{
+    components::Collision, EntityId, GameCommand, GameCommandsArg, Query, QueryResultList, System,
+    SystemsGenerator, EVENT_AFTER_TICK, EVENT_BEFORE_TICK,
+};
+
+/// Thomas detects collisions in a `Game` on its own, but its `TerminalCollision`s can't name entities in a simulation's
+/// world. The game detects its own instead, so collisions are found the same way whether it's played or simulated.
+pub struct CollisionsSystemsGenerator {}
+impl SystemsGenerator for CollisionsSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (
+                EVENT_BEFORE_TICK,
+                System::new(
+                    vec![Query::new()
+                        .has_where::<TerminalCollider>(|collider| collider.is_active)
+                        .has::<TerminalTransform>()],
+                    detect_collisions,
+                ),
+            ),
+            (
+                EVENT_AFTER_TICK,
+                System::new(vec![Query::new().has::<Collision>()], cleanup_collisions),
+            ),
+        ]
+    }
+}
+
+/// Adds a `Collision` for every pair of bodies sharing a cell.
+fn detect_collisions(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [bodies_results, ..] = &results[..] {
+        let mut coords_to_bodies: HashMap<(i64, i64), Vec<(EntityId, TerminalCollider)>> =
+            HashMap::new();
+
+        for body_result in bodies_results {
+            let coords = body_result.components().get::<TerminalTransform>().coords;
+            let body = (
+                *body_result.entity(),
+                *body_result.components().get::<TerminalCollider>(),
+            );
+            let bodies_in_cell = coords_to_bodies
+                .entry((coords.x(), coords.y()))
+                .or_default();
+
+            for other_body in bodies_in_cell.iter() {
+                commands
+                    .borrow_mut()
+                    .issue(GameCommand::AddEntity(vec![Box::new(Collision {
+                        bodies: [*other_body, body],
+                    })]));
+            }
+
+            bodies_in_cell.push(body);
+        }
+    }
+}
+
+fn cleanup_collisions(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [collision_results, ..] = &results[..] {
+        for collision_result in collision_results {
+            commands
+                .borrow_mut()
+                .issue(GameCommand::DestroyEntity(*collision_result.entity()));
+        }
+    }
+}
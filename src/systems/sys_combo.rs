@@ -0,0 +1,337 @@
+use std::{collections::HashMap, rc::Rc};
+
+use thomas::{IntCoords2d, Priority, TerminalTransform, EVENT_INIT};
+
+use crate::{
+    add_bonus_popup,
+    components::{
+        BonusPopup, ComboTracker, GameManager, Obstacle, Player, PlayerHead, RunStatsTracker,
+    },
+    tick_timers_system, Bonus, Combo, GameCommand, GameCommandsArg, Query, QueryResultList, System,
+    SystemsGenerator, TickTimer, BONUS_POPUP_RISE_TICKS, BONUS_POPUP_TICKS, COMBO_DECAY_TICKS,
+    EVENT_RESTART, EVENT_TICK,
+};
+
+pub struct ComboSystemsGenerator {}
+impl SystemsGenerator for ComboSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (
+                EVENT_TICK,
+                tick_timers_system::<ComboTracker>(|combo_tracker| {
+                    combo_tracker.decay_timer.tick()
+                }),
+            ),
+            (
+                EVENT_TICK,
+                tick_timers_system::<BonusPopup>(|bonus_popup| bonus_popup.timer.tick()),
+            ),
+            (EVENT_INIT, System::new(vec![], make_combo_tracker)),
+            (
+                EVENT_TICK,
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<Player>().has::<TerminalTransform>(),
+                        Query::new().has::<PlayerHead>().has::<TerminalTransform>(),
+                        Query::new()
+                            .has_where::<Obstacle>(|obstacle| {
+                                !obstacle.is_dodged && !obstacle.is_near_missed
+                            })
+                            .has::<TerminalTransform>(),
+                    ],
+                    track_near_misses,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                // Has to see obstacles pass before the run stats mark them dodged.
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<Player>().has::<TerminalTransform>(),
+                        Query::new()
+                            .has_where::<Obstacle>(|obstacle| !obstacle.is_dodged)
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<ComboTracker>(),
+                        Query::new().has::<RunStatsTracker>(),
+                    ],
+                    score_passed_obstacles,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<Player>().has::<TerminalTransform>(),
+                        Query::new().has::<ComboTracker>(),
+                        Query::new().has::<RunStatsTracker>(),
+                    ],
+                    score_clean_double_jumps,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<ComboTracker>(),
+                    ],
+                    decay_combo,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![Query::new().has::<BonusPopup>().has::<TerminalTransform>()],
+                    float_bonus_popups,
+                ),
+            ),
+            (
+                EVENT_RESTART,
+                System::new(
+                    vec![
+                        Query::new().has::<ComboTracker>(),
+                        Query::new().has::<BonusPopup>(),
+                    ],
+                    reset_combo,
+                ),
+            ),
+        ]
+    }
+}
+
+fn make_combo_tracker(_: Vec<QueryResultList>, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(ComboTracker {
+            combo: Combo::new(),
+            decay_timer: TickTimer::start_new(),
+            is_double_jump_pending: false,
+        })]));
+}
+
+/// Marks every obstacle cell that comes within a cell of the player, diagonals included. Cells that pass while the
+/// player's invulnerable don't count, since they can't be hit anyway.
+fn track_near_misses(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [playing_game_manager_results, player_results, player_head_results, obstacle_results, ..] =
+        &results[..]
+    {
+        if playing_game_manager_results.is_empty() {
+            return;
+        }
+
+        let player = player_results.get_only::<Player>();
+
+        if player.is_invulnerable {
+            return;
+        }
+
+        let mut player_cells = vec![player_results.get_only::<TerminalTransform>().coords];
+        if !player.motion.is_sliding {
+            player_cells.push(player_head_results.get_only::<TerminalTransform>().coords);
+        }
+
+        for obstacle_result in obstacle_results {
+            let obstacle_coords = obstacle_result
+                .components()
+                .get::<TerminalTransform>()
+                .coords;
+
+            if player_cells.iter().any(|player_coords| {
+                let offset = obstacle_coords - *player_coords;
+
+                offset.x().abs() <= 1 && offset.y().abs() <= 1
+            }) {
+                obstacle_result
+                    .components()
+                    .get_mut::<Obstacle>()
+                    .is_near_missed = true;
+            }
+        }
+    }
+}
+
+/// Once every one of an obstacle's cells is behind the player, it's scored as a near miss if any of them came close.
+/// Otherwise it only builds the combo.
+fn score_passed_obstacles(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [playing_game_manager_results, player_results, obstacle_results, combo_tracker_results, run_stats_tracker_results, ..] =
+        &results[..]
+    {
+        if playing_game_manager_results.is_empty() {
+            return;
+        }
+
+        let player = player_results.get_only::<Player>();
+
+        // The run stats still mark these dodged, but they aren't worth anything.
+        if player.is_invulnerable {
+            return;
+        }
+
+        let player_x = player_results.get_only::<TerminalTransform>().coords.x();
+
+        // The highest cell of each obstacle the player's made it past, and whether any of its cells came close.
+        let mut passed_obstacles: HashMap<u64, (IntCoords2d, bool)> = HashMap::new();
+        let mut obstacle_ids_ahead = vec![];
+
+        for obstacle_result in obstacle_results {
+            let obstacle = obstacle_result.components().get::<Obstacle>();
+            let coords = obstacle_result
+                .components()
+                .get::<TerminalTransform>()
+                .coords;
+
+            if coords.x() >= player_x {
+                obstacle_ids_ahead.push(obstacle.obstacle_id);
+            } else {
+                let passed_obstacle = passed_obstacles
+                    .entry(obstacle.obstacle_id)
+                    .or_insert((coords, false));
+
+                if coords.y() < passed_obstacle.0.y() {
+                    passed_obstacle.0 = coords;
+                }
+                passed_obstacle.1 |= obstacle.is_near_missed;
+            }
+        }
+
+        let mut game_manager = playing_game_manager_results.get_only_mut::<GameManager>();
+        let mut combo_tracker = combo_tracker_results.get_only_mut::<ComboTracker>();
+        let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+
+        let mut passed_obstacles: Vec<(u64, (IntCoords2d, bool))> = passed_obstacles
+            .into_iter()
+            .filter(|(obstacle_id, _)| !obstacle_ids_ahead.contains(obstacle_id))
+            .collect();
+        // Obstacles passed on the same tick are scored in the same order every time, so replays score the same.
+        passed_obstacles.sort_by_key(|(obstacle_id, _)| *obstacle_id);
+
+        for (_, (top_coords, is_near_missed)) in passed_obstacles {
+            if is_near_missed {
+                run_stats_tracker.stats.near_misses += 1;
+
+                score_bonus(
+                    Bonus::NearMiss,
+                    top_coords + IntCoords2d::down(),
+                    &mut game_manager,
+                    &mut combo_tracker,
+                    &mut run_stats_tracker,
+                    Rc::clone(&commands),
+                );
+            } else {
+                combo_tracker.combo.extend();
+                combo_tracker.decay_timer.restart();
+            }
+        }
+    }
+}
+
+/// Scores a jump made in the air once the player lands without having been hit since.
+fn score_clean_double_jumps(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [playing_game_manager_results, player_results, combo_tracker_results, run_stats_tracker_results, ..] =
+        &results[..]
+    {
+        if playing_game_manager_results.is_empty() {
+            return;
+        }
+
+        let player = player_results.get_only::<Player>();
+        let mut combo_tracker = combo_tracker_results.get_only_mut::<ComboTracker>();
+
+        if player.is_on_ground && combo_tracker.is_double_jump_pending {
+            combo_tracker.is_double_jump_pending = false;
+
+            if !player.is_invulnerable {
+                let player_coords = player_results.get_only::<TerminalTransform>().coords;
+                let mut game_manager = playing_game_manager_results.get_only_mut::<GameManager>();
+                let mut run_stats_tracker =
+                    run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+
+                // Above the player's head.
+                score_bonus(
+                    Bonus::CleanDoubleJump,
+                    player_coords + IntCoords2d::new(0, -2),
+                    &mut game_manager,
+                    &mut combo_tracker,
+                    &mut run_stats_tracker,
+                    commands,
+                );
+            }
+        }
+    }
+}
+
+fn score_bonus(
+    bonus: Bonus,
+    popup_coords: IntCoords2d,
+    game_manager: &mut GameManager,
+    combo_tracker: &mut ComboTracker,
+    run_stats_tracker: &mut RunStatsTracker,
+    commands: GameCommandsArg,
+) {
+    run_stats_tracker.stats.best_combo = run_stats_tracker
+        .stats
+        .best_combo
+        .max(combo_tracker.combo.multiplier());
+
+    let points = combo_tracker.combo.score(bonus);
+
+    game_manager.add_bonus_score(points);
+    combo_tracker.decay_timer.restart();
+
+    add_bonus_popup(commands, popup_coords, points, bonus);
+}
+
+fn decay_combo(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [playing_game_manager_results, combo_tracker_results, ..] = &results[..] {
+        if !playing_game_manager_results.is_empty() {
+            let mut combo_tracker = combo_tracker_results.get_only_mut::<ComboTracker>();
+
+            if combo_tracker.decay_timer.elapsed_ticks() >= COMBO_DECAY_TICKS {
+                combo_tracker.combo.decay();
+                combo_tracker.decay_timer.restart();
+            }
+        }
+    }
+}
+
+fn float_bonus_popups(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [bonus_popup_results, ..] = &results[..] {
+        for bonus_popup_result in bonus_popup_results {
+            let bonus_popup = bonus_popup_result.components().get::<BonusPopup>();
+            let elapsed_ticks = bonus_popup.timer.elapsed_ticks();
+
+            if elapsed_ticks >= BONUS_POPUP_TICKS {
+                commands
+                    .borrow_mut()
+                    .issue(GameCommand::DestroyEntity(*bonus_popup_result.entity()));
+            } else {
+                bonus_popup_result
+                    .components()
+                    .get_mut::<TerminalTransform>()
+                    .coords = bonus_popup.origin
+                    + IntCoords2d::new(0, -((elapsed_ticks / BONUS_POPUP_RISE_TICKS) as i64));
+            }
+        }
+    }
+}
+
+fn reset_combo(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [combo_tracker_results, bonus_popup_results, ..] = &results[..] {
+        let mut combo_tracker = combo_tracker_results.get_only_mut::<ComboTracker>();
+
+        combo_tracker.combo = Combo::new();
+        combo_tracker.decay_timer.restart();
+        combo_tracker.is_double_jump_pending = false;
+
+        for bonus_popup_result in bonus_popup_results {
+            commands
+                .borrow_mut()
+                .issue(GameCommand::DestroyEntity(*bonus_popup_result.entity()));
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use thomas::{Input, Keycode, Priority, EVENT_BEFORE_UPDATE, EVENT_INIT};
+
+use crate::{
+    components::{Action, Controls, ReplayPlayback},
+    GameCommand, GameCommandsArg, Query, QueryResultList, System, SystemsGenerator,
+    EVENT_BEFORE_TICK,
+};
+
+const JUMP_BUTTONS: [Keycode; 1] = [Keycode::Space];
+const SLIDE_BUTTONS: [Keycode; 2] = [Keycode::Down, Keycode::S];
+const PAUSE_BUTTONS: [Keycode; 1] = [Keycode::Escape];
+const RESTART_BUTTONS: [Keycode; 1] = [Keycode::R];
+const LEFT_BUTTONS: [Keycode; 2] = [Keycode::Left, Keycode::A];
+const RIGHT_BUTTONS: [Keycode; 2] = [Keycode::Right, Keycode::D];
+const SHOP_BUTTONS: [Keycode; 1] = [Keycode::U];
+
+pub struct ControlsSystemsGenerator {}
+impl SystemsGenerator for ControlsSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (EVENT_INIT, System::new(vec![], make_controls)),
+            (
+                EVENT_BEFORE_UPDATE,
+                // Thomas reads the keyboard at the highest priority.
+                System::new_with_priority(
+                    Priority::lower_than(&Priority::highest()),
+                    vec![
+                        Query::new().has::<Input>(),
+                        Query::new().has::<Controls>().has_no::<ReplayPlayback>(),
+                    ],
+                    read_keyboard,
+                ),
+            ),
+            (
+                EVENT_BEFORE_TICK,
+                System::new_with_priority(
+                    Priority::highest(),
+                    vec![Query::new().has::<Controls>().has_no::<ReplayPlayback>()],
+                    advance_controls,
+                ),
+            ),
+        ]
+    }
+}
+
+fn make_controls(_: Vec<QueryResultList>, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(Controls::new())]));
+}
+
+fn read_keyboard(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [input_results, keyboard_controls_results, ..] = &results[..] {
+        if !input_results.is_empty() && !keyboard_controls_results.is_empty() {
+            let input = input_results.get_only::<Input>();
+            let mut controls = keyboard_controls_results.get_only_mut::<Controls>();
+
+            let is_any_pressed =
+                |buttons: &[Keycode]| buttons.iter().any(|button| input.is_key_pressed(button));
+
+            controls.hold(
+                [
+                    (Action::Jump, is_any_pressed(&JUMP_BUTTONS)),
+                    (Action::Slide, is_any_pressed(&SLIDE_BUTTONS)),
+                    (Action::Pause, is_any_pressed(&PAUSE_BUTTONS)),
+                    (Action::Restart, is_any_pressed(&RESTART_BUTTONS)),
+                    (Action::Left, is_any_pressed(&LEFT_BUTTONS)),
+                    (Action::Right, is_any_pressed(&RIGHT_BUTTONS)),
+                    (Action::Shop, is_any_pressed(&SHOP_BUTTONS)),
+                    (Action::AnyKey, input.is_any_key_pressed()),
+                ]
+                .into_iter()
+                .filter_map(|(action, is_pressed)| if is_pressed { Some(action) } else { None }),
+            );
+        }
+    }
+}
+
+fn advance_controls(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [keyboard_controls_results, ..] = &results[..] {
+        for keyboard_controls_result in keyboard_controls_results {
+            keyboard_controls_result
+                .components()
+                .get_mut::<Controls>()
+                .advance();
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::rc::Rc;
+
+use rand::Rng;
+use thomas::{IntCoords2d, IntVector2, TerminalCamera, TerminalRenderer, TerminalTransform};
+
+use crate::{
+    add_enemy, add_obstacle,
+    components::{
+        Enemy, Flight, FollowCamera, GameManager, GameObjectManager, Moveable, Obstacle, Pit,
+        Player, PowerUps, WorldRng,
+    },
+    make_course, make_obstacle_shape, tick_timers_system, CourseObstacle, FlightPath, GameCommand,
+    GameCommandsArg, GameConfig, ObstacleType, Query, QueryResultList, Resolution, System,
+    SystemsGenerator, ENEMY_COLOR, ENEMY_FLASH_INTERVAL_TICKS, ENEMY_FLY_TICKS, ENEMY_HOVER_HEIGHT,
+    ENEMY_HOVER_INSET, ENEMY_SHOT_WAIT_TICKS, ENEMY_STAY_TICKS, ENEMY_WIND_UP_COLOR,
+    ENEMY_WIND_UP_TICKS, EVENT_RESTART, EVENT_TICK, PROJECTILE_MOVE_INTERVAL_TICKS,
+    SINGLE_CELL_SHAPE,
+};
+
+/// How far an enemy hovering in place is from where it appeared, just off the right edge of the screen.
+const HOVER_X_OFFSET: i64 = -(ENEMY_HOVER_INSET + 1);
+
+pub struct EnemiesSystemsGenerator {}
+impl SystemsGenerator for EnemiesSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (
+                EVENT_TICK,
+                tick_timers_system::<Enemy>(|enemy| {
+                    enemy.timer.tick();
+                    enemy.shot_timer.tick();
+                }),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<GameObjectManager>(),
+                        Query::new().has::<GameManager>(),
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Resolution>(),
+                        Query::new().has::<Enemy>(),
+                    ],
+                    generate_enemies,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<Player>().has::<PowerUps>(),
+                        Query::new()
+                            .has::<Enemy>()
+                            .has::<FollowCamera>()
+                            .has::<TerminalRenderer>(),
+                    ],
+                    fly_enemies,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<GameObjectManager>(),
+                        Query::new().has::<Enemy>().has::<FollowCamera>(),
+                        Query::new()
+                            .has_where::<TerminalCamera>(|cam| cam.is_main)
+                            .has::<TerminalTransform>()
+                            .has::<Moveable>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Resolution>(),
+                        Query::new().has::<Player>().has::<FollowCamera>(),
+                        Query::new()
+                            .has::<Obstacle>()
+                            .has::<TerminalTransform>()
+                            .has_no::<Moveable>(),
+                        Query::new()
+                            .has::<Obstacle>()
+                            .has::<TerminalTransform>()
+                            .has::<Moveable>()
+                            .has::<Flight>(),
+                        Query::new().has::<Pit>().has::<TerminalTransform>(),
+                    ],
+                    fire_at_player,
+                ),
+            ),
+            (
+                EVENT_RESTART,
+                System::new(vec![Query::new().has::<Enemy>()], remove_enemies),
+            ),
+        ]
+    }
+}
+
+/// Only one enemy is out at a time, so chances of another turning up while there's one out go to waste.
+fn generate_enemies(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_object_manager_results, game_manager_results, world_rng_results, config_results, resolution_results, enemy_results, ..] =
+        &results[..]
+    {
+        let mut game_object_manager =
+            game_object_manager_results.get_only_mut::<GameObjectManager>();
+        let config = config_results.get_only::<GameConfig>();
+
+        if game_object_manager.enemy_generation_timer.elapsed_ticks() >= config.enemy_wait_ticks {
+            let game_manager = game_manager_results.get_only::<GameManager>();
+            let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+
+            let roll = world_rng.gen_range(0..100_u8);
+
+            if roll < config.enemy_chance && game_manager.is_playing() && enemy_results.is_empty() {
+                add_enemy(commands, &resolution_results.get_only::<Resolution>());
+            }
+
+            game_object_manager.enemy_generation_timer.restart();
+        }
+    }
+}
+
+/// Flies enemies in from the right edge of the screen to where they hover, and back out the way they came once they've
+/// stayed long enough. An enemy winding up a shot is lined up with the lane it's about to fire down, and flashes.
+fn fly_enemies(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [playing_game_manager_results, player_results, enemy_results, ..] = &results[..] {
+        if playing_game_manager_results.is_empty() {
+            return;
+        }
+
+        // The enemy timers don't move on while the tick's skipped, so neither do the enemies.
+        if player_results.get_only::<PowerUps>().is_tick_skipped {
+            return;
+        }
+
+        for enemy_result in enemy_results {
+            let enemy = enemy_result.components().get::<Enemy>();
+            let mut follow_cam = enemy_result.components().get_mut::<FollowCamera>();
+            let mut renderer = enemy_result.components().get_mut::<TerminalRenderer>();
+            let is_flying_this_tick = enemy.timer.elapsed_ticks().is_multiple_of(ENEMY_FLY_TICKS);
+
+            if enemy.timer.elapsed_ticks() >= ENEMY_STAY_TICKS {
+                if is_flying_this_tick {
+                    follow_cam.offset += IntVector2::right();
+                }
+
+                if follow_cam.offset.x() > 0 {
+                    commands
+                        .borrow_mut()
+                        .issue(GameCommand::DestroyEntity(*enemy_result.entity()));
+                }
+            } else if is_flying_this_tick && follow_cam.offset.x() > HOVER_X_OFFSET {
+                follow_cam.offset += IntVector2::left();
+            }
+
+            follow_cam.offset = IntCoords2d::new(
+                follow_cam.offset.x(),
+                ENEMY_HOVER_HEIGHT - enemy.lane_option.unwrap_or(ENEMY_HOVER_HEIGHT),
+            );
+
+            let is_flashed = enemy.lane_option.is_some()
+                && (enemy.shot_timer.elapsed_ticks() / ENEMY_FLASH_INTERVAL_TICKS) % 2 == 1;
+
+            renderer.foreground_color = Some(if is_flashed {
+                ENEMY_WIND_UP_COLOR
+            } else {
+                ENEMY_COLOR
+            });
+        }
+    }
+}
+
+/// Once a hovering enemy has waited long enough between shots, it lines up with the lane the player's in and winds up
+/// a shot, then fires a projectile down that lane. Shots the player couldn't get past, along with everything else
+/// coming up, are held back.
+fn fire_at_player(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [playing_game_manager_results, game_object_manager_results, enemy_results, main_cam_results, config_results, resolution_results, player_results, still_obstacle_results, moving_obstacle_results, pit_results, ..] =
+        &results[..]
+    {
+        if playing_game_manager_results.is_empty() {
+            return;
+        }
+
+        let mut game_object_manager =
+            game_object_manager_results.get_only_mut::<GameObjectManager>();
+        let resolution = resolution_results.get_only::<Resolution>();
+
+        for enemy_result in enemy_results {
+            let mut enemy = enemy_result.components().get_mut::<Enemy>();
+            let follow_cam = enemy_result.components().get::<FollowCamera>();
+
+            if enemy.timer.elapsed_ticks() >= ENEMY_STAY_TICKS
+                || follow_cam.offset.x() != HOVER_X_OFFSET
+            {
+                enemy.lane_option = None;
+                continue;
+            }
+
+            if let Some(lane) = enemy.lane_option {
+                // A pattern spawned this tick isn't out there yet, so there's no telling whether the shot could be
+                // cleared along with it.
+                if enemy.shot_timer.elapsed_ticks() >= ENEMY_WIND_UP_TICKS
+                    && game_object_manager
+                        .obstacle_generation_timer
+                        .elapsed_ticks()
+                        > 0
+                {
+                    let game_manager = playing_game_manager_results.get_only::<GameManager>();
+                    let config = config_results.get_only::<GameConfig>();
+                    let main_cam_x = main_cam_results.get_only::<TerminalTransform>().coords.x();
+                    let projectile_x =
+                        main_cam_x + follow_cam.base_position.x() + follow_cam.offset.x() - 1;
+
+                    let mut course = make_course(
+                        &game_manager,
+                        &resolution,
+                        main_cam_results,
+                        player_results,
+                        still_obstacle_results,
+                        moving_obstacle_results,
+                        pit_results,
+                    );
+                    course.obstacles.push(CourseObstacle {
+                        x: projectile_x,
+                        height: lane,
+                        move_interval_option: Some(PROJECTILE_MOVE_INTERVAL_TICKS),
+                        move_ticks: 0,
+                        flight_path: FlightPath::Level,
+                        flight_ticks: 0,
+                        drift: 0,
+                    });
+
+                    if course.is_clearable(&config) {
+                        add_obstacle(
+                            Rc::clone(&commands),
+                            game_object_manager.next_obstacle_id,
+                            ObstacleType::Projectile,
+                            &make_obstacle_shape(SINGLE_CELL_SHAPE),
+                            IntCoords2d::new(projectile_x, resolution.ground_y() - lane),
+                            Some(PROJECTILE_MOVE_INTERVAL_TICKS),
+                            FlightPath::Level,
+                        );
+
+                        game_object_manager.next_obstacle_id += 1;
+                    }
+
+                    enemy.lane_option = None;
+                    enemy.shot_timer.restart();
+                }
+            } else if enemy.shot_timer.elapsed_ticks() >= ENEMY_SHOT_WAIT_TICKS {
+                let player_height = -player_results.get_only::<FollowCamera>().offset.y();
+
+                enemy.lane_option = Some(player_height.max(0));
+                enemy.shot_timer.restart();
+            }
+        }
+    }
+}
+
+fn remove_enemies(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [enemy_results, ..] = &results[..] {
+        for enemy_result in enemy_results {
+            commands
+                .borrow_mut()
+                .issue(GameCommand::DestroyEntity(*enemy_result.entity()));
+        }
+    }
+}
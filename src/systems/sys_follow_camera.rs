@@ -1,15 +1,15 @@
-use thomas::{
-    GameCommandsArg, Priority, Query, QueryResultList, System, SystemsGenerator, TerminalCamera,
-    TerminalTransform, EVENT_UPDATE,
-};
+use thomas::{Priority, TerminalCamera, TerminalTransform};
 
-use crate::components::FollowCamera;
+use crate::{
+    components::FollowCamera, GameCommandsArg, Query, QueryResultList, System, SystemsGenerator,
+    EVENT_TICK,
+};
 
 pub struct FollowCameraSystemsGenerator {}
 impl SystemsGenerator for FollowCameraSystemsGenerator {
     fn generate(&self) -> Vec<(&'static str, System)> {
         vec![(
-            EVENT_UPDATE,
+            EVENT_TICK,
             System::new_with_priority(
                 Priority::lowest(),
                 vec![
@@ -1,38 +1,52 @@
 use thomas::{
-    GameCommand, GameCommandsArg, Input, IntCoords2d, IntVector2, Keycode, Query, QueryResultList,
-    System, SystemsGenerator, TerminalCamera, TerminalTransform, Timer, EVENT_AFTER_INIT,
-    EVENT_INIT, EVENT_UPDATE,
+    IntCoords2d, IntVector2, Priority, TerminalCamera, TerminalTransform, EVENT_AFTER_INIT,
+    EVENT_INIT,
 };
 
 use crate::{
-    components::{GameManager, GameState, Moveable, Player},
-    EVENT_DEFEAT, EVENT_GAME_PAUSE_STATE_CHANGE, EVENT_RESTART, EVENT_VICTORY, GAME_VICTORY_SCORE,
-    PLAYER_X_OFFSET,
+    components::{
+        Action, Controls, GameManager, GameState, Moveable, PickupKind, Player, PowerUps, WorldRng,
+    },
+    DifficultyMenu, GameCommand, GameCommandsArg, GameConfig, GameMode, Query, QueryResultList,
+    System, SystemsGenerator, TickTimer, EVENT_DEFEAT, EVENT_DIFFICULTY_CHANGE,
+    EVENT_GAME_PAUSE_STATE_CHANGE, EVENT_MILESTONE, EVENT_RESTART, EVENT_START, EVENT_TICK,
+    EVENT_VICTORY, PLAYER_X_OFFSET, SCORE_MULTIPLIER,
 };
 
-const CAMERA_SCROLL_WAIT_TIME_MILLIS: u128 = 100;
-
-pub struct GameManagerSystemsGenerator {}
+pub struct GameManagerSystemsGenerator {
+    pub difficulty_menu: DifficultyMenu,
+}
 impl SystemsGenerator for GameManagerSystemsGenerator {
     fn generate(&self) -> Vec<(&'static str, System)> {
+        let difficulty_menu = self.difficulty_menu.clone();
+
         vec![
+            (
+                EVENT_INIT,
+                System::new(vec![], move |_, commands| {
+                    make_difficulty_menu_and_game_config(difficulty_menu.clone(), commands)
+                }),
+            ),
             (EVENT_INIT, System::new(vec![], make_game_manager)),
             (
                 EVENT_AFTER_INIT,
                 System::new(
-                    vec![Query::new().has_where::<TerminalCamera>(|cam| cam.is_main)],
+                    vec![
+                        Query::new().has_where::<TerminalCamera>(|cam| cam.is_main),
+                        Query::new().has::<GameConfig>(),
+                    ],
                     augment_main_cam,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new().has::<GameManager>(),
                         Query::new()
                             .has_where::<TerminalCamera>(|cam| cam.is_main)
                             .has_where::<Moveable>(|moveable| {
-                                moveable.move_timer.elapsed_millis() >= moveable.move_interval
+                                moveable.move_timer.elapsed_ticks() >= moveable.move_interval
                             })
                             .has::<TerminalTransform>(),
                     ],
@@ -40,40 +54,67 @@ impl SystemsGenerator for GameManagerSystemsGenerator {
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    vec![
+                        Query::new().has::<GameManager>(),
+                        Query::new()
+                            .has_where::<TerminalCamera>(|cam| cam.is_main)
+                            .has::<Moveable>(),
+                        Query::new().has::<GameConfig>(),
+                    ],
+                    update_scroll_speed,
+                ),
+            ),
+            (
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new().has::<GameManager>(),
-                        Query::new().has::<Player>(),
+                        Query::new().has::<Player>().has::<PowerUps>(),
                     ],
                     update_score,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has::<Input>(),
+                        Query::new().has::<Controls>(),
                         Query::new().has_where::<GameManager>(|gm| gm.is_waiting_to_start()),
+                        Query::new().has::<WorldRng>(),
                     ],
                     handle_press_key_to_start,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has::<Input>(),
+                        Query::new().has::<Controls>(),
+                        Query::new().has_where::<GameManager>(|gm| gm.is_waiting_to_start()),
+                        Query::new().has_where::<DifficultyMenu>(|menu| menu.has_choices()),
+                        Query::new().has::<GameConfig>(),
+                    ],
+                    handle_difficulty_selection,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<Controls>(),
                         Query::new().has_where::<GameManager>(|gm| gm.is_game_over()),
                     ],
                     handle_press_key_to_restart,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has::<Input>(),
+                        Query::new().has::<Controls>(),
                         Query::new()
                             .has_where::<GameManager>(|gm| gm.is_playing() || gm.is_paused()),
                     ],
@@ -81,19 +122,30 @@ impl SystemsGenerator for GameManagerSystemsGenerator {
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
-                    vec![Query::new().has_where::<GameManager>(|gm| {
-                        gm.score >= GAME_VICTORY_SCORE && gm.is_playing()
-                    })],
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<GameConfig>(),
+                    ],
                     trigger_victory,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<GameConfig>(),
+                    ],
+                    trigger_milestone,
+                ),
+            ),
+            (
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has_where::<GameManager>(|gm| !gm.is_game_over()),
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
                         Query::new().has_where::<Player>(|player| player.lives == 0),
                     ],
                     trigger_defeat,
@@ -107,6 +159,7 @@ impl SystemsGenerator for GameManagerSystemsGenerator {
                         Query::new()
                             .has_where::<TerminalCamera>(|cam| cam.is_main)
                             .has::<TerminalTransform>(),
+                        Query::new().has::<WorldRng>(),
                     ],
                     handle_restart_game,
                 ),
@@ -115,27 +168,45 @@ impl SystemsGenerator for GameManagerSystemsGenerator {
     }
 }
 
+fn make_difficulty_menu_and_game_config(
+    difficulty_menu: DifficultyMenu,
+    commands: GameCommandsArg,
+) {
+    let config = difficulty_menu.selected_config().clone();
+
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(difficulty_menu)]));
+
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(config)]));
+}
+
 fn make_game_manager(_: Vec<QueryResultList>, commands: GameCommandsArg) {
     commands
         .borrow_mut()
         .issue(GameCommand::AddEntity(vec![Box::new(GameManager {
-            camera_scroll_timer: Timer::start_new(),
             score: 0,
+            bonus_score: 0,
+            coins: 0,
             game_state: GameState::WaitingToStart,
+            milestones_reached: 0,
         })]));
 }
 
 fn augment_main_cam(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [main_cam_results, ..] = &results[..] {
+    if let [main_cam_results, config_results, ..] = &results[..] {
         let main_cam = &main_cam_results[0];
+        let config = config_results.get_only::<GameConfig>();
 
         commands
             .borrow_mut()
             .issue(GameCommand::AddComponentsToEntity(
                 *main_cam.entity(),
                 vec![Box::new(Moveable {
-                    move_timer: Timer::start_new(),
-                    move_interval: CAMERA_SCROLL_WAIT_TIME_MILLIS,
+                    move_timer: TickTimer::start_new(),
+                    move_interval: config.camera_scroll_wait_ticks(0),
                 })],
             ));
     }
@@ -156,37 +227,97 @@ fn scroll_camera(results: Vec<QueryResultList>, _: GameCommandsArg) {
     }
 }
 
+fn update_scroll_speed(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [game_manager_results, main_cam_results, config_results, ..] = &results[..] {
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let config = config_results.get_only::<GameConfig>();
+        let mut main_cam_moveable = main_cam_results.get_only_mut::<Moveable>();
+
+        main_cam_moveable.move_interval = config.camera_scroll_wait_ticks(game_manager.distance());
+    }
+}
+
 fn update_score(results: Vec<QueryResultList>, _: GameCommandsArg) {
     if let [game_manager_results, player_results, ..] = &results[..] {
         let player = player_results.get_only::<Player>();
+        let power_ups = player_results.get_only::<PowerUps>();
         let mut game_manager = game_manager_results.get_only_mut::<GameManager>();
+        let distance = player.distance_traveled - PLAYER_X_OFFSET as u64;
+
+        // Every cell made it while the multiplier's going is worth more than one point.
+        if power_ups.is_active(PickupKind::ScoreMultiplier) {
+            game_manager.bonus_score +=
+                distance.saturating_sub(game_manager.distance()) * (SCORE_MULTIPLIER - 1);
+        }
 
-        game_manager.score = player.distance_traveled - PLAYER_X_OFFSET as u64;
+        game_manager.score = distance + game_manager.bonus_score;
     }
 }
 
-fn handle_press_key_to_start(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [input_results, not_playing_game_manager_results, ..] = &results[..] {
-        let input = input_results.get_only::<Input>();
+fn handle_press_key_to_start(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [controls_results, not_playing_game_manager_results, world_rng_results, ..] =
+        &results[..]
+    {
+        let controls = controls_results.get_only::<Controls>();
+
+        // Left and right pick the difficulty instead, and the shop key opens the shop.
+        let is_start_pressed = controls.is_action_down(&Action::AnyKey)
+            && !controls.is_action_pressed(&Action::Left)
+            && !controls.is_action_pressed(&Action::Right)
+            && !controls.is_action_pressed(&Action::Shop);
 
-        if !not_playing_game_manager_results.is_empty() && input.is_any_key_down() {
+        if !not_playing_game_manager_results.is_empty() && is_start_pressed {
             let mut game_manager = not_playing_game_manager_results.get_only_mut::<GameManager>();
+            let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
 
             game_manager.game_state = GameState::Playing;
+
+            world_rng.reseed();
+
+            commands
+                .borrow_mut()
+                .issue(GameCommand::TriggerEvent(EVENT_START));
         }
     }
 }
 
-fn handle_press_key_to_restart(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [input_results, victory_or_defeat_game_manager_results, ..] = &results[..] {
-        let input = input_results.get_only::<Input>();
+fn handle_difficulty_selection(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [controls_results, not_playing_game_manager_results, difficulty_menu_results, config_results, ..] =
+        &results[..]
+    {
+        if !not_playing_game_manager_results.is_empty() && !difficulty_menu_results.is_empty() {
+            let controls = controls_results.get_only::<Controls>();
 
-        if !victory_or_defeat_game_manager_results.is_empty() && input.is_key_down(&Keycode::R) {
-            let mut game_manager =
-                victory_or_defeat_game_manager_results.get_only_mut::<GameManager>();
+            let steps = match (
+                controls.is_action_down(&Action::Left),
+                controls.is_action_down(&Action::Right),
+            ) {
+                (true, false) => -1,
+                (false, true) => 1,
+                _ => return,
+            };
 
-            game_manager.game_state = GameState::Playing;
+            let mut difficulty_menu = difficulty_menu_results.get_only_mut::<DifficultyMenu>();
+            let mut config = config_results.get_only_mut::<GameConfig>();
 
+            difficulty_menu.select_by(steps);
+            *config = difficulty_menu.selected_config().clone();
+
+            commands
+                .borrow_mut()
+                .issue(GameCommand::TriggerEvent(EVENT_DIFFICULTY_CHANGE));
+        }
+    }
+}
+
+fn handle_press_key_to_restart(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [controls_results, victory_or_defeat_game_manager_results, ..] = &results[..] {
+        let controls = controls_results.get_only::<Controls>();
+
+        // The run only goes back to being played once it's been restarted, or it'd be lost all over again before then.
+        if !victory_or_defeat_game_manager_results.is_empty()
+            && controls.is_action_down(&Action::Restart)
+        {
             commands
                 .borrow_mut()
                 .issue(GameCommand::TriggerEvent(EVENT_RESTART));
@@ -195,13 +326,13 @@ fn handle_press_key_to_restart(results: Vec<QueryResultList>, commands: GameComm
 }
 
 fn handle_toggle_pause(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [input_results, playing_game_manager_results, ..] = &results[..] {
-        let input = input_results.get_only::<Input>();
+    if let [controls_results, playing_game_manager_results, ..] = &results[..] {
+        let controls = controls_results.get_only::<Controls>();
 
         if !playing_game_manager_results.is_empty() {
             let mut game_manager = playing_game_manager_results.get_only_mut::<GameManager>();
 
-            if input.is_key_down(&Keycode::Escape) {
+            if controls.is_action_down(&Action::Pause) {
                 game_manager.game_state = if game_manager.is_paused() {
                     GameState::Playing
                 } else {
@@ -217,23 +348,45 @@ fn handle_toggle_pause(results: Vec<QueryResultList>, commands: GameCommandsArg)
 }
 
 fn trigger_victory(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [winning_score_game_manager_results, ..] = &results[..] {
-        if !winning_score_game_manager_results.is_empty() {
-            let mut game_manager = winning_score_game_manager_results.get_only_mut::<GameManager>();
+    if let [playing_game_manager_results, config_results, ..] = &results[..] {
+        if !playing_game_manager_results.is_empty() {
+            let mut game_manager = playing_game_manager_results.get_only_mut::<GameManager>();
+            let config = config_results.get_only::<GameConfig>();
 
-            game_manager.game_state = GameState::Victory;
+            if config.mode == GameMode::Classic && game_manager.score >= config.victory_score {
+                game_manager.game_state = GameState::Victory;
 
-            commands
-                .borrow_mut()
-                .issue(GameCommand::TriggerEvent(EVENT_VICTORY));
+                commands
+                    .borrow_mut()
+                    .issue(GameCommand::TriggerEvent(EVENT_VICTORY));
+            }
+        }
+    }
+}
+
+fn trigger_milestone(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [playing_game_manager_results, config_results, ..] = &results[..] {
+        if !playing_game_manager_results.is_empty() {
+            let mut game_manager = playing_game_manager_results.get_only_mut::<GameManager>();
+            let config = config_results.get_only::<GameConfig>();
+
+            if config.mode == GameMode::Endless
+                && game_manager.score / config.victory_score > game_manager.milestones_reached
+            {
+                game_manager.milestones_reached += 1;
+
+                commands
+                    .borrow_mut()
+                    .issue(GameCommand::TriggerEvent(EVENT_MILESTONE));
+            }
         }
     }
 }
 
 fn trigger_defeat(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [not_game_over_game_manager_results, dead_player_results, ..] = &results[..] {
-        if !dead_player_results.is_empty() && !not_game_over_game_manager_results.is_empty() {
-            let mut game_manager = not_game_over_game_manager_results.get_only_mut::<GameManager>();
+    if let [playing_game_manager_results, dead_player_results, ..] = &results[..] {
+        if !dead_player_results.is_empty() && !playing_game_manager_results.is_empty() {
+            let mut game_manager = playing_game_manager_results.get_only_mut::<GameManager>();
 
             game_manager.game_state = GameState::Defeat;
 
@@ -245,13 +398,19 @@ fn trigger_defeat(results: Vec<QueryResultList>, commands: GameCommandsArg) {
 }
 
 fn handle_restart_game(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [game_manager_results, main_cam_results, ..] = &results[..] {
+    if let [game_manager_results, main_cam_results, world_rng_results, ..] = &results[..] {
         let mut game_manager = game_manager_results.get_only_mut::<GameManager>();
         let mut main_cam_transform = main_cam_results.get_only_mut::<TerminalTransform>();
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
 
         game_manager.game_state = GameState::Playing;
         game_manager.score = 0;
+        game_manager.bonus_score = 0;
+        game_manager.coins = 0;
+        game_manager.milestones_reached = 0;
 
         main_cam_transform.coords = IntCoords2d::new(0, main_cam_transform.coords.y());
+
+        world_rng.reseed_for_next_run();
     }
 }
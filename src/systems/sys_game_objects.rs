@@ -1,70 +1,95 @@
-use std::{ops::RangeInclusive, rc::Rc};
+use std::rc::Rc;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use thomas::{
-    GameCommand, GameCommandsArg, Identity, IntVector2, Query, QueryResult, QueryResultList,
-    System, SystemsGenerator, TerminalCamera, TerminalTransform, Timer, EVENT_INIT, EVENT_UPDATE,
+    Identity, IntCoords2d, IntVector2, Matrix, Priority, TerminalCamera, TerminalTransform,
+    EVENT_AFTER_INIT,
 };
 
 use crate::{
-    add_distance_marker,
+    add_distance_marker, add_obstacle, add_pit,
     components::{
-        CleanupOnScreenExit, FollowCamera, GameManager, GameObjectManager, Moveable, Player,
+        Bank, CleanupOnScreenExit, Flight, FollowCamera, GameManager, GameObjectManager, Moveable,
+        Obstacle, Pit, Player, PowerUps, WorldRng,
     },
-    make_extra_life, make_obstacle, ObstacleType, BUILDING_PIECE_NAME, DISTANCE_MARKER_PIECE_NAME,
-    EVENT_RESTART, OBSTACLE_NAME,
+    make_obstacle_shape, pick_flight_path, pick_obstacle_pattern, tick_timers_system, Course,
+    CourseObstacle, CoursePit, FlightPath, GameCommand, GameCommandsArg, GameConfig,
+    ObstaclePattern, ObstacleType, PatternObstacle, Query, QueryResult, QueryResultList,
+    Resolution, RunnerInput, RunnerState, System, SystemsGenerator, TickTimer, AIR_OBSTACLE_HEIGHT,
+    BUILDING_PIECE_NAME, DISTANCE_MARKER_PIECE_NAME, DISTANCE_MARKER_SPACING, EVENT_RESTART,
+    EVENT_START, EVENT_TICK, MAX_OBSTACLE_PATTERN_ROLLS, OBSTACLE_NAME, OBSTACLE_PATTERNS,
+    SINGLE_CELL_SHAPE,
 };
 
-const GENERATE_OBSTACLE_WAIT_TIME_MILLIS_RANGE: RangeInclusive<u128> = 250..=3000;
-
-const DISTANCE_MARKER_SPACING: u64 = 500;
-
-const GENERATE_PLAYER_LIFE_WAIT_TIME_MILLIS: u128 = 5000;
-const GENERATE_PLAYER_LIFE_CHANCE: u8 = 10;
-
 pub struct GameObjectsSystemsGenerator {}
 impl SystemsGenerator for GameObjectsSystemsGenerator {
     fn generate(&self) -> Vec<(&'static str, System)> {
         vec![
-            (EVENT_INIT, System::new(vec![], make_obstacle_manager)),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
+                tick_timers_system::<GameObjectManager>(|game_object_manager| {
+                    game_object_manager.obstacle_generation_timer.tick();
+                    game_object_manager.pickup_generation_timer.tick();
+                    game_object_manager.coin_generation_timer.tick();
+                    game_object_manager.enemy_generation_timer.tick();
+                }),
+            ),
+            (
+                EVENT_TICK,
+                tick_timers_system::<Moveable>(|moveable| moveable.move_timer.tick()),
+            ),
+            (
+                EVENT_TICK,
+                tick_timers_system::<Flight>(|flight| flight.timer.tick()),
+            ),
+            (
+                EVENT_AFTER_INIT,
                 System::new(
                     vec![
-                        Query::new().has::<GameObjectManager>(),
-                        Query::new().has::<GameManager>(),
-                        Query::new()
-                            .has_where::<TerminalCamera>(|cam| cam.is_main)
-                            .has::<TerminalTransform>(),
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Bank>(),
                     ],
-                    generate_obstacles,
+                    make_obstacle_manager,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has_where::<GameObjectManager>(|gom| {
-                            gom.extra_life_generation_timer.elapsed_millis()
-                                >= GENERATE_PLAYER_LIFE_WAIT_TIME_MILLIS
-                        }),
+                        Query::new().has::<GameObjectManager>(),
                         Query::new().has::<GameManager>(),
                         Query::new()
                             .has_where::<TerminalCamera>(|cam| cam.is_main)
-                            .has::<TerminalTransform>(),
+                            .has::<TerminalTransform>()
+                            .has::<Moveable>(),
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Resolution>(),
+                        Query::new().has::<Player>().has::<FollowCamera>(),
+                        Query::new()
+                            .has::<Obstacle>()
+                            .has::<TerminalTransform>()
+                            .has_no::<Moveable>(),
+                        Query::new()
+                            .has::<Obstacle>()
+                            .has::<TerminalTransform>()
+                            .has::<Moveable>()
+                            .has::<Flight>(),
+                        Query::new().has::<Pit>().has::<TerminalTransform>(),
                     ],
-                    generate_player_lives,
+                    generate_obstacles,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new().has::<GameManager>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == OBSTACLE_NAME)
+                            .has_where::<Identity>(|id| id.name == OBSTACLE_NAME)
                             .has_where::<Moveable>(|moveable| {
-                                moveable.move_timer.elapsed_millis() >= moveable.move_interval
+                                moveable.move_timer.elapsed_ticks() >= moveable.move_interval
                             })
                             .has::<TerminalTransform>(),
                     ],
@@ -72,7 +97,26 @@ impl SystemsGenerator for GameObjectsSystemsGenerator {
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
+                // The fairness check expects obstacles to go up and down before anything moves across, and its
+                // snapshot of them to be taken after they have.
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new()
+                            .has::<Player>()
+                            .has::<PowerUps>()
+                            .has::<FollowCamera>()
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<Flight>().has::<TerminalTransform>(),
+                        Query::new().has::<Resolution>(),
+                    ],
+                    fly_obstacles,
+                ),
+            ),
+            (
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new()
@@ -86,25 +130,38 @@ impl SystemsGenerator for GameObjectsSystemsGenerator {
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == DISTANCE_MARKER_PIECE_NAME),
+                            .has_where::<Identity>(|id| id.name == DISTANCE_MARKER_PIECE_NAME),
                         Query::new().has::<Player>(),
+                        Query::new().has::<Resolution>(),
                     ],
                     generate_distance_markers,
                 ),
             ),
+            (
+                EVENT_START,
+                System::new(
+                    vec![
+                        Query::new().has::<GameObjectManager>(),
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<GameConfig>(),
+                    ],
+                    prepare_first_obstacle,
+                ),
+            ),
             (
                 EVENT_RESTART,
                 System::new(
                     vec![
-                        Query::new().has_where::<Identity>(|id| &id.name == OBSTACLE_NAME),
+                        Query::new().has_where::<Identity>(|id| id.name == OBSTACLE_NAME),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == DISTANCE_MARKER_PIECE_NAME),
+                            .has_where::<Identity>(|id| id.name == DISTANCE_MARKER_PIECE_NAME),
+                        Query::new().has::<Pit>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == BUILDING_PIECE_NAME)
+                            .has_where::<Identity>(|id| id.name == BUILDING_PIECE_NAME)
                             .has::<FollowCamera>(),
                     ],
                     handle_restart_game,
@@ -114,67 +171,300 @@ impl SystemsGenerator for GameObjectsSystemsGenerator {
     }
 }
 
-fn make_obstacle_manager(_: Vec<QueryResultList>, commands: GameCommandsArg) {
-    commands
-        .borrow_mut()
-        .issue(GameCommand::AddEntity(vec![Box::new(GameObjectManager {
-            obstacle_generation_timer: Timer::start_new(),
-            extra_life_generation_timer: Timer::start_new(),
-            scroll_timer: Timer::start_new(),
-            next_obstacle_wait_time: thread_rng()
-                .gen_range(GENERATE_OBSTACLE_WAIT_TIME_MILLIS_RANGE),
-        })]));
+fn make_obstacle_manager(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [world_rng_results, config_results, bank_results, ..] = &results[..] {
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+        let config = config_results.get_only::<GameConfig>();
+        let upgrades = bank_results.get_only::<Bank>().wallet.upgrades;
+
+        commands
+            .borrow_mut()
+            .issue(GameCommand::AddEntity(vec![Box::new(GameObjectManager {
+                obstacle_generation_timer: TickTimer::start_new(),
+                pickup_generation_timer: TickTimer::start_new(),
+                coin_generation_timer: TickTimer::start_new(),
+                enemy_generation_timer: TickTimer::start_new(),
+                next_obstacle_wait_ticks: world_rng.gen_range(config.obstacle_wait_ticks_range(0)),
+                next_obstacle_id: 0,
+                pickup_spawn_weights: upgrades.pickup_spawn_weights(),
+            })]));
+    }
+}
+
+/// Rolls the wait for the first obstacle again once the run starts, from the config and seed the run is played with.
+/// That way the run plays out the same no matter what was picked or rolled before it started.
+fn prepare_first_obstacle(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [obstacle_manager_results, world_rng_results, config_results, ..] = &results[..] {
+        let mut obstacle_manager = obstacle_manager_results.get_only_mut::<GameObjectManager>();
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+        let config = config_results.get_only::<GameConfig>();
+
+        obstacle_manager.next_obstacle_wait_ticks =
+            world_rng.gen_range(config.obstacle_wait_ticks_range(0));
+    }
+}
+
+/// One obstacle of a rolled pattern, ready to be spawned.
+struct PlannedObstacle {
+    typ: ObstacleType,
+    shape: Matrix<bool>,
+    x_offset: i64,
+    height: i64,
+    move_interval_option: Option<u64>,
+    flight_path: FlightPath,
+}
+impl PlannedObstacle {
+    /// The obstacle's cells as they'd be on the course if it were spawned now, with its left edge at `spawn_x`.
+    fn course_cells(&self, spawn_x: i64) -> impl Iterator<Item = CourseObstacle> + '_ {
+        let top_height = self.height + self.shape.dimensions().height() as i64 - 1;
+
+        self.shape
+            .iter()
+            .filter(|cell| *cell.data())
+            .map(move |cell| CourseObstacle {
+                x: spawn_x + self.x_offset + cell.location().x(),
+                height: top_height - cell.location().y(),
+                move_interval_option: self.move_interval_option,
+                move_ticks: 0,
+                flight_path: self.flight_path,
+                flight_ticks: 0,
+                drift: 0,
+            })
+    }
 }
 
 fn generate_obstacles(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [obstacle_manager_results, game_manager_results, main_cam_results, ..] = &results[..] {
+    if let [obstacle_manager_results, game_manager_results, main_cam_results, world_rng_results, config_results, resolution_results, player_results, still_obstacle_results, moving_obstacle_results, pit_results, ..] =
+        &results[..]
+    {
         let mut obstacle_manager = obstacle_manager_results.get_only_mut::<GameObjectManager>();
         let game_manager = game_manager_results.get_only::<GameManager>();
         let main_cam_transform = main_cam_results.get_only::<TerminalTransform>();
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+        let config = config_results.get_only::<GameConfig>();
+        let resolution = resolution_results.get_only::<Resolution>();
 
-        if obstacle_manager.obstacle_generation_timer.elapsed_millis()
-            >= obstacle_manager.next_obstacle_wait_time
+        if obstacle_manager.obstacle_generation_timer.elapsed_ticks()
+            >= obstacle_manager.next_obstacle_wait_ticks
         {
+            // The next wait only starts counting once the whole pattern has scrolled onto the screen.
+            let mut pattern_scroll_ticks = 0;
+
             if game_manager.is_playing() {
-                commands
-                    .borrow_mut()
-                    .issue(GameCommand::AddEntity(make_obstacle(
-                        &main_cam_transform,
-                        if thread_rng().gen_bool(0.5) {
-                            ObstacleType::Ground
-                        } else {
-                            ObstacleType::Air
-                        },
-                    )));
+                let course = make_course(
+                    &game_manager,
+                    &resolution,
+                    main_cam_results,
+                    player_results,
+                    still_obstacle_results,
+                    moving_obstacle_results,
+                    pit_results,
+                );
+                let spawn_x = main_cam_transform.coords.x() + resolution.width() as i64 + 1;
+
+                // A pattern the player couldn't get past from where they are is swapped for another roll. If none of
+                // the rolls can be cleared, nothing is spawned this time around.
+                for _ in 0..MAX_OBSTACLE_PATTERN_ROLLS {
+                    let pattern = pick_obstacle_pattern(
+                        OBSTACLE_PATTERNS,
+                        config.difficulty_progress_percent(game_manager.distance()),
+                        &mut *world_rng,
+                    );
+                    let planned_obstacles = roll_pattern_obstacles(
+                        pattern,
+                        &config,
+                        game_manager.distance(),
+                        &mut world_rng,
+                    );
+
+                    let planned_pits: Vec<CoursePit> = pattern
+                        .obstacles
+                        .iter()
+                        .filter_map(|obstacle| match *obstacle {
+                            PatternObstacle::Pit { x_offset, width } => Some(CoursePit {
+                                x: spawn_x + x_offset,
+                                width,
+                            }),
+                            _ => None,
+                        })
+                        .collect();
+
+                    let mut course_with_pattern = course.clone();
+                    course_with_pattern.pits.extend(&planned_pits);
+                    course_with_pattern.obstacles.extend(
+                        planned_obstacles
+                            .iter()
+                            .flat_map(|planned| planned.course_cells(spawn_x)),
+                    );
+
+                    if course_with_pattern.is_clearable(&config) {
+                        for planned in planned_obstacles {
+                            add_obstacle(
+                                Rc::clone(&commands),
+                                obstacle_manager.next_obstacle_id,
+                                planned.typ,
+                                &planned.shape,
+                                IntCoords2d::new(
+                                    spawn_x + planned.x_offset,
+                                    resolution.ground_y() - planned.height,
+                                ),
+                                planned.move_interval_option,
+                                planned.flight_path,
+                            );
+
+                            obstacle_manager.next_obstacle_id += 1;
+                        }
+
+                        for pit in planned_pits {
+                            add_pit(Rc::clone(&commands), &resolution, pit.x, pit.width);
+                        }
+
+                        pattern_scroll_ticks = (pattern.width() - 1)
+                            * config.camera_scroll_wait_ticks(game_manager.distance());
+                        break;
+                    }
+                }
             }
 
             obstacle_manager.obstacle_generation_timer.restart();
-            obstacle_manager.next_obstacle_wait_time =
-                thread_rng().gen_range(GENERATE_OBSTACLE_WAIT_TIME_MILLIS_RANGE);
+            obstacle_manager.next_obstacle_wait_ticks = pattern_scroll_ticks
+                + world_rng.gen_range(config.obstacle_wait_ticks_range(game_manager.distance()));
         }
     }
 }
 
-fn generate_player_lives(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [generation_ready_game_object_manager_results, game_manager_results, main_cam_results, ..] =
-        &results[..]
-    {
-        if !generation_ready_game_object_manager_results.is_empty() {
-            let game_manager = game_manager_results.get_only::<GameManager>();
-            let mut game_object_manager =
-                generation_ready_game_object_manager_results.get_only_mut::<GameObjectManager>();
-            let main_cam_transform = main_cam_results.get_only::<TerminalTransform>();
+/// Rolls what kind of obstacle each of the pattern's obstacles is, and how fast and along what path the moving ones
+/// fly. Pits aren't rolled, so they're left out.
+fn roll_pattern_obstacles(
+    pattern: &ObstaclePattern,
+    config: &GameConfig,
+    score: u64,
+    world_rng: &mut WorldRng,
+) -> Vec<PlannedObstacle> {
+    pattern
+        .obstacles
+        .iter()
+        .filter_map(|obstacle| {
+            let (typ, height, is_moving) = match *obstacle {
+                PatternObstacle::Ground { height, .. } => (ObstacleType::Ground, height, false),
+                PatternObstacle::Air { .. } => (ObstacleType::Air, AIR_OBSTACLE_HEIGHT, false),
+                PatternObstacle::MovingAir { .. } => (ObstacleType::Air, AIR_OBSTACLE_HEIGHT, true),
+                PatternObstacle::Random { .. } => {
+                    if world_rng.gen_bool(1.0 - config.air_obstacle_chance(score) as f64 / 100.0) {
+                        (ObstacleType::Ground, 0, false)
+                    } else {
+                        (ObstacleType::Air, AIR_OBSTACLE_HEIGHT, true)
+                    }
+                }
+                PatternObstacle::Shape { height: 0, .. } => (ObstacleType::Ground, 0, false),
+                PatternObstacle::Shape { height, .. } => (ObstacleType::Air, height, false),
+                PatternObstacle::Pit { .. } => return None,
+            };
 
-            let roll = thread_rng().gen_range(0..100 as u8);
+            let rows = match *obstacle {
+                PatternObstacle::Shape { rows, .. } => rows,
+                _ => SINGLE_CELL_SHAPE,
+            };
 
-            if roll < GENERATE_PLAYER_LIFE_CHANCE && game_manager.is_playing() {
-                commands
-                    .borrow_mut()
-                    .issue(GameCommand::AddEntity(make_extra_life(&main_cam_transform)));
-            }
+            let (move_interval_option, flight_path) = if is_moving {
+                (
+                    Some(world_rng.gen_range(config.air_obstacle_move_interval_range(score))),
+                    pick_flight_path(config.difficulty_progress_percent(score), &mut *world_rng),
+                )
+            } else {
+                (None, FlightPath::Level)
+            };
 
-            game_object_manager.extra_life_generation_timer.restart();
-        }
+            Some(PlannedObstacle {
+                typ,
+                shape: make_obstacle_shape(rows),
+                x_offset: obstacle.x_offset(),
+                height: if is_moving {
+                    flight_path.spawn_height()
+                } else {
+                    height
+                },
+                move_interval_option,
+                flight_path,
+            })
+        })
+        .collect()
+}
+
+/// Takes a snapshot of the player and the obstacles and pits already out there, as they'll be at the end of this tick.
+pub fn make_course(
+    game_manager: &GameManager,
+    resolution: &Resolution,
+    main_cam_results: &QueryResultList,
+    player_results: &QueryResultList,
+    still_obstacle_results: &QueryResultList,
+    moving_obstacle_results: &QueryResultList,
+    pit_results: &QueryResultList,
+) -> Course {
+    let main_cam_transform = main_cam_results.get_only::<TerminalTransform>();
+    let main_cam_moveable = main_cam_results.get_only::<Moveable>();
+    let player = player_results.get_only::<Player>();
+    let player_follow_cam = player_results.get_only::<FollowCamera>();
+
+    let mut obstacles: Vec<CourseObstacle> = still_obstacle_results
+        .iter()
+        .map(|result| CourseObstacle {
+            x: result.components().get::<TerminalTransform>().coords.x(),
+            height: resolution.ground_y()
+                - result.components().get::<TerminalTransform>().coords.y(),
+            move_interval_option: None,
+            move_ticks: 0,
+            flight_path: FlightPath::Level,
+            flight_ticks: 0,
+            drift: 0,
+        })
+        .collect();
+
+    // Moving obstacles haven't taken this tick's step yet, so it's taken for them here. They've already gone up or
+    // down for the tick.
+    for result in moving_obstacle_results {
+        let transform = result.components().get::<TerminalTransform>();
+        let moveable = result.components().get::<Moveable>();
+        let flight = result.components().get::<Flight>();
+        let is_moving_this_tick = moveable.move_timer.elapsed_ticks() >= moveable.move_interval;
+
+        obstacles.push(CourseObstacle {
+            x: transform.coords.x() - if is_moving_this_tick { 1 } else { 0 },
+            height: resolution.ground_y() - transform.coords.y(),
+            move_interval_option: Some(moveable.move_interval),
+            move_ticks: if is_moving_this_tick {
+                0
+            } else {
+                moveable.move_timer.elapsed_ticks()
+            },
+            flight_path: flight.path,
+            flight_ticks: flight.timer.elapsed_ticks(),
+            drift: 0,
+        });
+    }
+
+    Course {
+        runner: RunnerState {
+            height: -player_follow_cam.offset.y(),
+            motion: player.motion,
+            // Only jump being held down is known here, so the player's taken to have let go of everything else.
+            last_input: if player.is_jump_held {
+                RunnerInput::Jump
+            } else {
+                RunnerInput::Nothing
+            },
+        },
+        camera_x: main_cam_transform.coords.x(),
+        camera_move_ticks: main_cam_moveable.move_timer.elapsed_ticks(),
+        score: game_manager.distance(),
+        obstacles,
+        pits: pit_results
+            .iter()
+            .map(|result| CoursePit {
+                x: result.components().get::<TerminalTransform>().coords.x(),
+                width: result.components().get::<Pit>().width,
+            })
+            .collect(),
     }
 }
 
@@ -195,15 +485,22 @@ fn cleanup_entities(results: Vec<QueryResultList>, commands: GameCommandsArg) {
 }
 
 fn generate_distance_markers(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [existing_distance_marker_pieces_results, player_results, ..] = &results[..] {
-        if existing_distance_marker_pieces_results.len() == 0 {
+    if let [existing_distance_marker_pieces_results, player_results, resolution_results, ..] =
+        &results[..]
+    {
+        if existing_distance_marker_pieces_results.is_empty() {
             let player = player_results.get_only::<Player>();
+            let resolution = resolution_results.get_only::<Resolution>();
 
             let next_distance_marker_distance = DISTANCE_MARKER_SPACING
                 * ((f64::floor(player.distance_traveled as f64 / DISTANCE_MARKER_SPACING as f64)
                     + 1.0) as u64);
 
-            add_distance_marker(Rc::clone(&commands), next_distance_marker_distance as u64);
+            add_distance_marker(
+                Rc::clone(&commands),
+                &resolution,
+                next_distance_marker_distance,
+            );
         }
     }
 }
@@ -227,8 +524,45 @@ fn move_moveable_obstacles(results: Vec<QueryResultList>, _: GameCommandsArg) {
     }
 }
 
+/// Moves flying obstacles up or down along their flight paths.
+fn fly_obstacles(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [playing_game_manager_results, player_results, flight_results, resolution_results, ..] =
+        &results[..]
+    {
+        if playing_game_manager_results.is_empty() {
+            return;
+        }
+
+        // The flight timers don't move on while the tick's skipped, so neither do the obstacles.
+        if player_results.get_only::<PowerUps>().is_tick_skipped {
+            return;
+        }
+
+        let player_x = player_results.get_only::<TerminalTransform>().coords.x();
+        let player_height = -player_results.get_only::<FollowCamera>().offset.y();
+        let ground_y = resolution_results.get_only::<Resolution>().ground_y();
+
+        for flight_result in flight_results {
+            let flight = flight_result.components().get::<Flight>();
+            let mut transform = flight_result.components().get_mut::<TerminalTransform>();
+            let height = ground_y - transform.coords.y();
+
+            let next_height = flight.path.next_height(
+                height,
+                flight.timer.elapsed_ticks(),
+                transform.coords.x() - player_x,
+                player_height,
+            );
+
+            transform.coords += IntCoords2d::new(0, height - next_height);
+        }
+    }
+}
+
 fn handle_restart_game(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [obstacle_results, distance_marker_results, building_piece_results, ..] = &results[..] {
+    if let [obstacle_results, distance_marker_results, pit_results, building_piece_results, ..] =
+        &results[..]
+    {
         let destroy = |result: &QueryResult| {
             commands
                 .borrow_mut()
@@ -237,6 +571,7 @@ fn handle_restart_game(results: Vec<QueryResultList>, commands: GameCommandsArg)
 
         obstacle_results.iter().for_each(destroy);
         distance_marker_results.iter().for_each(destroy);
+        pit_results.iter().for_each(destroy);
 
         for building_piece_result in building_piece_results {
             let mut follow_cam = building_piece_result.components().get_mut::<FollowCamera>();
@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use thomas::{Priority, EVENT_INIT};
+
+use crate::{
+    components::{Bank, GameManager, GameState, HighScoreTable, Player, WorldRng},
+    now_unix_seconds, DifficultyMenu, GameCommand, GameCommandsArg, GameConfig, HighScore,
+    HighScores, Outcome, Query, QueryResultList, System, SystemsGenerator, EVENT_DEFEAT,
+    EVENT_VICTORY,
+};
+
+pub struct HighScoresSystemsGenerator {
+    pub high_scores: HighScores,
+    pub path_option: Option<PathBuf>,
+}
+impl SystemsGenerator for HighScoresSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        let high_scores = self.high_scores.clone();
+        let path_option = self.path_option.clone();
+
+        vec![
+            (
+                EVENT_INIT,
+                System::new(vec![], move |_, commands| {
+                    make_high_score_table(high_scores.clone(), path_option.clone(), commands)
+                }),
+            ),
+            (
+                EVENT_VICTORY,
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    record_run_queries(),
+                    record_run,
+                ),
+            ),
+            (
+                EVENT_DEFEAT,
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    record_run_queries(),
+                    record_run,
+                ),
+            ),
+        ]
+    }
+}
+
+fn make_high_score_table(
+    high_scores: HighScores,
+    path_option: Option<PathBuf>,
+    commands: GameCommandsArg,
+) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(HighScoreTable {
+            high_scores,
+            path_option,
+            latest_place_option: None,
+        })]));
+}
+
+fn record_run_queries() -> Vec<Query> {
+    vec![
+        Query::new().has::<GameManager>(),
+        Query::new().has::<Player>(),
+        Query::new().has::<WorldRng>(),
+        Query::new().has::<HighScoreTable>(),
+        Query::new().has::<DifficultyMenu>(),
+        Query::new().has::<GameConfig>(),
+        Query::new().has::<Bank>(),
+    ]
+}
+
+fn record_run(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [game_manager_results, player_results, world_rng_results, high_score_table_results, difficulty_menu_results, config_results, bank_results, ..] =
+        &results[..]
+    {
+        let difficulty_menu = difficulty_menu_results.get_only::<DifficultyMenu>();
+        let config = config_results.get_only::<GameConfig>();
+        let bank = bank_results.get_only::<Bank>();
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let player = player_results.get_only::<Player>();
+        let world_rng = world_rng_results.get_only::<WorldRng>();
+        let mut high_score_table = high_score_table_results.get_only_mut::<HighScoreTable>();
+
+        let difficulty = difficulty_menu.selected_difficulty();
+
+        // Runs played with a tuned config aren't up against the same game as the difficulty's, so they're left off.
+        if !difficulty.is_own_config(&config) {
+            high_score_table.latest_place_option = None;
+            return;
+        }
+
+        let outcome = if game_manager.game_state == GameState::Victory {
+            Outcome::Victory
+        } else {
+            Outcome::Defeat
+        };
+
+        high_score_table.latest_place_option = high_score_table.high_scores.add(HighScore {
+            difficulty,
+            mode: config.mode,
+            score: game_manager.score,
+            seed: world_rng.seed(),
+            lives: player.lives,
+            outcome,
+            finished_at: now_unix_seconds(),
+            upgrades: bank.wallet.upgrades,
+        });
+
+        if high_score_table.latest_place_option.is_some() {
+            if let Some(path) = &high_score_table.path_option {
+                // There's nowhere to report a failed save mid-game, so the run is only kept for this session.
+                let _ = high_score_table.high_scores.save(path);
+            }
+        }
+    }
+}
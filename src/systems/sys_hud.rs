@@ -1,19 +1,26 @@
 use std::rc::Rc;
 
-use thomas::{
-    Alignment, GameCommand, GameCommandsArg, Identity, IntCoords2d, Query, QueryResult,
-    QueryResultList, Rgb, System, SystemsGenerator, Text, UiAnchor, EVENT_INIT, EVENT_UPDATE,
-};
+use thomas::{Alignment, Identity, IntCoords2d, Rgb, Text, UiAnchor, EVENT_AFTER_INIT, EVENT_INIT};
 
 use crate::{
-    add_defeat_text, add_paused_text, add_start_playing_text, add_victory_text,
-    components::{GameManager, Player},
-    DEFEAT_TEXT_NAME, EVENT_DEFEAT, EVENT_GAME_PAUSE_STATE_CHANGE, EVENT_VICTORY, PAUSED_TEXT_NAME,
-    PLAYER_LIFE_DISPLAY, START_PLAYING_TEXT_NAME, VICTORY_TEXT_NAME,
+    add_defeat_text, add_high_score_text, add_milestone_text, add_paused_text,
+    add_start_playing_text, add_victory_text,
+    components::{
+        Bank, ComboTracker, GameManager, GameState, HighScoreTable, Player, PowerUps, WorldRng,
+    },
+    Difficulty, DifficultyMenu, GameCommand, GameCommandsArg, GameConfig, GameMode, Query,
+    QueryResult, QueryResultList, System, SystemsGenerator, COIN_COLOR, DEFEAT_TEXT_NAME,
+    EVENT_DEFEAT, EVENT_DIFFICULTY_CHANGE, EVENT_GAME_PAUSE_STATE_CHANGE, EVENT_MILESTONE,
+    EVENT_SHOP_STATE_CHANGE, EVENT_TICK, EVENT_VICTORY, MILESTONE_TEXT_NAME,
+    MILESTONE_TEXT_SCORE_SPAN, NEW_BEST_COLOR, PAUSED_TEXT_NAME, PLAYER_HIT_COLOR,
+    PLAYER_LIFE_DISPLAY, START_PLAYING_TEXT_NAME, TICKS_PER_SECOND, VICTORY_TEXT_NAME,
 };
 
 const SCORE_TAG_ID: &str = "score-tag";
 const LIVES_TAG_ID: &str = "lives-tag";
+const DIFFICULTY_TAG_ID: &str = "difficulty-tag";
+const POWER_UPS_TAG_ID: &str = "power-ups-tag";
+const COINS_TAG_ID: &str = "coins-tag";
 
 pub struct HudSystemsGenerator {}
 impl SystemsGenerator for HudSystemsGenerator {
@@ -21,55 +28,152 @@ impl SystemsGenerator for HudSystemsGenerator {
         vec![
             (EVENT_INIT, System::new(vec![], add_tags)),
             (
-                EVENT_UPDATE,
+                EVENT_AFTER_INIT,
+                System::new(start_playing_text_queries(), make_start_playing_text),
+            ),
+            (
+                EVENT_DIFFICULTY_CHANGE,
+                System::new(start_playing_text_queries(), remake_start_playing_text),
+            ),
+            (
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new().has::<GameManager>(),
                         Query::new().has::<Player>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.id == SCORE_TAG_ID)
+                            .has_where::<Identity>(|id| id.id == SCORE_TAG_ID)
                             .has::<Text>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.id == LIVES_TAG_ID)
+                            .has_where::<Identity>(|id| id.id == LIVES_TAG_ID)
                             .has::<Text>(),
+                        Query::new()
+                            .has_where::<Identity>(|id| id.id == DIFFICULTY_TAG_ID)
+                            .has::<Text>(),
+                        Query::new()
+                            .has_where::<Identity>(|id| id.id == COINS_TAG_ID)
+                            .has::<Text>(),
+                        Query::new().has::<DifficultyMenu>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Bank>(),
+                        Query::new().has::<ComboTracker>(),
                     ],
                     update_tags,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has_where::<GameManager>(|gm| !gm.is_waiting_to_start()),
+                        Query::new().has::<PowerUps>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == START_PLAYING_TEXT_NAME),
+                            .has_where::<Identity>(|id| id.id == POWER_UPS_TAG_ID)
+                            .has::<Text>(),
+                    ],
+                    update_power_ups_tag,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| !gm.is_waiting_to_start()),
+                        Query::new().has_where::<Identity>(|id| id.name == START_PLAYING_TEXT_NAME),
                     ],
                     remove_start_playing_text,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new().has_where::<GameManager>(|gm| !gm.is_game_over()),
-                        Query::new().has_where::<Identity>(|id| &id.name == VICTORY_TEXT_NAME),
-                        Query::new().has_where::<Identity>(|id| &id.name == DEFEAT_TEXT_NAME),
+                        Query::new().has_where::<Identity>(|id| id.name == VICTORY_TEXT_NAME),
+                        Query::new().has_where::<Identity>(|id| id.name == DEFEAT_TEXT_NAME),
                     ],
                     remove_game_over_text,
                 ),
             ),
+            (
+                EVENT_MILESTONE,
+                System::new(
+                    vec![
+                        Query::new().has::<GameManager>(),
+                        Query::new().has::<GameConfig>(),
+                    ],
+                    make_milestone_text,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<GameManager>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has_where::<Identity>(|id| id.name == MILESTONE_TEXT_NAME),
+                    ],
+                    remove_milestone_text,
+                ),
+            ),
             (
                 EVENT_GAME_PAUSE_STATE_CHANGE,
                 System::new(
                     vec![
                         Query::new().has::<GameManager>(),
-                        Query::new().has_where::<Identity>(|id| &id.name == PAUSED_TEXT_NAME),
+                        Query::new().has_where::<Identity>(|id| id.name == PAUSED_TEXT_NAME),
                     ],
                     update_paused_text,
                 ),
             ),
-            (EVENT_VICTORY, System::new(vec![], make_victory_text)),
-            (EVENT_DEFEAT, System::new(vec![], make_defeat_text)),
+            (
+                EVENT_SHOP_STATE_CHANGE,
+                System::new(
+                    start_playing_text_queries()
+                        .into_iter()
+                        .chain([
+                            Query::new().has_where::<GameManager>(|gm| gm.is_waiting_to_start())
+                        ])
+                        .collect(),
+                    remake_start_playing_text_after_shopping,
+                ),
+            ),
+            (
+                EVENT_SHOP_STATE_CHANGE,
+                System::new(
+                    vec![
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<HighScoreTable>(),
+                        Query::new().has::<DifficultyMenu>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has_where::<GameManager>(|gm| gm.is_game_over()),
+                    ],
+                    remake_game_over_text_after_shopping,
+                ),
+            ),
+            (
+                EVENT_VICTORY,
+                System::new(
+                    vec![
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<HighScoreTable>(),
+                        Query::new().has::<DifficultyMenu>(),
+                        Query::new().has::<GameConfig>(),
+                    ],
+                    make_victory_text,
+                ),
+            ),
+            (
+                EVENT_DEFEAT,
+                System::new(
+                    vec![
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<HighScoreTable>(),
+                        Query::new().has::<DifficultyMenu>(),
+                        Query::new().has::<GameConfig>(),
+                    ],
+                    make_defeat_text,
+                ),
+            ),
         ]
     }
 }
@@ -105,20 +209,146 @@ fn add_tags(_: Vec<QueryResultList>, commands: GameCommandsArg) {
         }),
     ]));
 
-    add_start_playing_text(Rc::clone(&commands));
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Text {
+            anchor: UiAnchor::MiddleBottom,
+            justification: Alignment::Middle,
+            value: String::from(""),
+            offset: IntCoords2d::zero(),
+            background_color: None,
+            foreground_color: Some(Rgb::white()),
+        }),
+        Box::new(Identity {
+            id: String::from(DIFFICULTY_TAG_ID),
+            name: String::from(""),
+        }),
+    ]));
+
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Text {
+            anchor: UiAnchor::TopLeft,
+            justification: Alignment::Left,
+            value: String::from(""),
+            offset: IntCoords2d::zero(),
+            background_color: None,
+            foreground_color: Some(Rgb::white()),
+        }),
+        Box::new(Identity {
+            id: String::from(POWER_UPS_TAG_ID),
+            name: String::from(""),
+        }),
+    ]));
+
+    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+        Box::new(Text {
+            anchor: UiAnchor::TopRight,
+            justification: Alignment::Right,
+            value: String::from(""),
+            offset: IntCoords2d::zero(),
+            background_color: None,
+            foreground_color: Some(COIN_COLOR),
+        }),
+        Box::new(Identity {
+            id: String::from(COINS_TAG_ID),
+            name: String::from(""),
+        }),
+    ]));
+}
+
+fn start_playing_text_queries() -> Vec<Query> {
+    vec![
+        Query::new().has::<HighScoreTable>(),
+        Query::new().has::<DifficultyMenu>(),
+        Query::new().has::<GameConfig>(),
+        Query::new().has_where::<Identity>(|id| id.name == START_PLAYING_TEXT_NAME),
+    ]
+}
+
+fn make_start_playing_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [high_score_table_results, difficulty_menu_results, config_results, ..] = &results[..] {
+        let high_score_table = high_score_table_results.get_only::<HighScoreTable>();
+        let difficulty_menu = difficulty_menu_results.get_only::<DifficultyMenu>();
+        let config = config_results.get_only::<GameConfig>();
+        let difficulty = difficulty_menu.selected_difficulty();
+
+        let difficulty_choice = if difficulty_menu.has_choices() {
+            format!("← {} →", config.mode.title_with(difficulty))
+        } else {
+            config.mode.title_with(difficulty)
+        };
+
+        add_start_playing_text(
+            Rc::clone(&commands),
+            &difficulty_choice,
+            config.mode,
+            config.victory_score,
+            high_score_table
+                .high_scores
+                .best(difficulty, config.mode)
+                .map(|high_score| high_score.score),
+        );
+    }
+}
+
+fn remake_start_playing_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [.., start_playing_text_results] = &results[..] {
+        for start_playing_text_result in start_playing_text_results {
+            commands.borrow_mut().issue(GameCommand::DestroyEntity(
+                *start_playing_text_result.entity(),
+            ));
+        }
+    }
+
+    make_start_playing_text(results, commands);
+}
+
+/// The shop covers the start screen, so it's put back once the shop's closed.
+fn remake_start_playing_text_after_shopping(
+    results: Vec<QueryResultList>,
+    commands: GameCommandsArg,
+) {
+    if let [.., waiting_game_manager_results] = &results[..] {
+        if waiting_game_manager_results.is_empty() {
+            return;
+        }
+    }
+
+    make_start_playing_text(results, commands);
 }
 
 fn update_tags(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [game_manager_results, player_results, score_tag_results, lives_tag_results, ..] =
+    if let [game_manager_results, player_results, score_tag_results, lives_tag_results, difficulty_tag_results, coins_tag_results, difficulty_menu_results, config_results, bank_results, combo_tracker_results, ..] =
         &results[..]
     {
         let player = player_results.get_only::<Player>();
         let game_manager = game_manager_results.get_only::<GameManager>();
+        let difficulty_menu = difficulty_menu_results.get_only::<DifficultyMenu>();
+        let config = config_results.get_only::<GameConfig>();
+        let bank = bank_results.get_only::<Bank>();
+        let combo = combo_tracker_results.get_only::<ComboTracker>().combo;
 
         let mut score_tag = score_tag_results.get_only_mut::<Text>();
         let mut lives_tag = lives_tag_results.get_only_mut::<Text>();
+        let mut difficulty_tag = difficulty_tag_results.get_only_mut::<Text>();
+        let mut coins_tag = coins_tag_results.get_only_mut::<Text>();
 
-        score_tag.value = format!("Score: {}", game_manager.score);
+        difficulty_tag.value = config
+            .mode
+            .title_with(difficulty_menu.selected_difficulty());
+        score_tag.value = if combo.multiplier() > 1 && game_manager.is_playing() {
+            format!(
+                "Score: {}  Combo ×{}",
+                game_manager.score,
+                combo.multiplier()
+            )
+        } else {
+            format!("Score: {}", game_manager.score)
+        };
+        lives_tag.foreground_color = Some(if player.is_blinked_out() {
+            PLAYER_HIT_COLOR
+        } else {
+            Rgb::white()
+        });
         lives_tag.value = format!(
             "Lives: {}",
             (0..player.lives)
@@ -126,6 +356,43 @@ fn update_tags(results: Vec<QueryResultList>, _: GameCommandsArg) {
                 .collect::<Vec<String>>()
                 .join("")
         );
+        // The wallet's only worth showing between runs, since the run's coins go into it once the run is over.
+        coins_tag.value = if game_manager.is_waiting_to_start() || game_manager.is_shopping() {
+            format!("Wallet: {}", bank.wallet.coins)
+        } else if game_manager.is_game_over() {
+            format!(
+                "Coins: {}  Wallet: {}",
+                game_manager.coins, bank.wallet.coins
+            )
+        } else {
+            format!("Coins: {}", game_manager.coins)
+        };
+    }
+}
+
+/// Lists the power-ups the player has going, with how many seconds each has left.
+fn update_power_ups_tag(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [power_ups_results, power_ups_tag_results, ..] = &results[..] {
+        let power_ups = power_ups_results.get_only::<PowerUps>();
+        let mut power_ups_tag = power_ups_tag_results.get_only_mut::<Text>();
+
+        power_ups_tag.value = power_ups
+            .active
+            .iter()
+            .map(|power_up| {
+                let ticks_left = power_ups
+                    .duration_ticks
+                    .saturating_sub(power_up.timer.elapsed_ticks());
+
+                format!(
+                    "{} {} {}s",
+                    power_up.kind.display(),
+                    power_up.kind.title(),
+                    ticks_left.div_ceil(TICKS_PER_SECOND as u64)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("  ");
     }
 }
 
@@ -157,12 +424,120 @@ fn update_paused_text(results: Vec<QueryResultList>, commands: GameCommandsArg)
     }
 }
 
-fn make_victory_text(_: Vec<QueryResultList>, commands: GameCommandsArg) {
-    add_victory_text(Rc::clone(&commands));
+fn make_victory_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [world_rng_results, high_score_table_results, difficulty_menu_results, config_results, ..] =
+        &results[..]
+    {
+        let world_rng = world_rng_results.get_only::<WorldRng>();
+        let high_score_table = high_score_table_results.get_only::<HighScoreTable>();
+        let difficulty_menu = difficulty_menu_results.get_only::<DifficultyMenu>();
+        let config = config_results.get_only::<GameConfig>();
+
+        add_victory_text(Rc::clone(&commands), world_rng.seed());
+        add_game_over_high_score_text(
+            Rc::clone(&commands),
+            VICTORY_TEXT_NAME,
+            &high_score_table,
+            difficulty_menu.selected_difficulty(),
+            config.mode,
+        );
+    }
+}
+
+fn make_defeat_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [world_rng_results, high_score_table_results, difficulty_menu_results, config_results, ..] =
+        &results[..]
+    {
+        let world_rng = world_rng_results.get_only::<WorldRng>();
+        let high_score_table = high_score_table_results.get_only::<HighScoreTable>();
+        let difficulty_menu = difficulty_menu_results.get_only::<DifficultyMenu>();
+        let config = config_results.get_only::<GameConfig>();
+
+        add_defeat_text(Rc::clone(&commands), world_rng.seed());
+        add_game_over_high_score_text(
+            Rc::clone(&commands),
+            DEFEAT_TEXT_NAME,
+            &high_score_table,
+            difficulty_menu.selected_difficulty(),
+            config.mode,
+        );
+    }
+}
+
+fn remake_game_over_text_after_shopping(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    let game_state_option = if let [.., game_over_game_manager_results] = &results[..] {
+        game_over_game_manager_results
+            .try_get_only::<GameManager>()
+            .map(|game_manager| game_manager.game_state.clone())
+    } else {
+        None
+    };
+
+    match game_state_option {
+        Some(GameState::Victory) => make_victory_text(results, commands),
+        Some(GameState::Defeat) => make_defeat_text(results, commands),
+        _ => {}
+    }
+}
+
+fn add_game_over_high_score_text(
+    commands: GameCommandsArg,
+    text_name: &str,
+    high_score_table: &HighScoreTable,
+    difficulty: Difficulty,
+    mode: GameMode,
+) {
+    match high_score_table.latest_place_option {
+        Some(0) => add_high_score_text(commands, text_name, "NEW BEST!", NEW_BEST_COLOR),
+        Some(place) => add_high_score_text(
+            commands,
+            text_name,
+            &format!("#{} on the high scores", place + 1),
+            Rgb::white(),
+        ),
+        None => {
+            if let Some(best) = high_score_table.high_scores.best(difficulty, mode) {
+                add_high_score_text(
+                    commands,
+                    text_name,
+                    &format!("Best: {}", best.score),
+                    Rgb::white(),
+                );
+            }
+        }
+    }
+}
+
+fn make_milestone_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_manager_results, config_results, ..] = &results[..] {
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let config = config_results.get_only::<GameConfig>();
+
+        add_milestone_text(
+            Rc::clone(&commands),
+            game_manager.milestones_reached * config.victory_score,
+        );
+    }
 }
 
-fn make_defeat_text(_: Vec<QueryResultList>, commands: GameCommandsArg) {
-    add_defeat_text(Rc::clone(&commands));
+/// The banner for a milestone comes down once the run has gone a little further, or once the run is over.
+fn remove_milestone_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_manager_results, config_results, milestone_text_results, ..] = &results[..] {
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let config = config_results.get_only::<GameConfig>();
+
+        let latest_milestone_score = game_manager.milestones_reached * config.victory_score;
+
+        if game_manager.is_game_over()
+            || game_manager.score >= latest_milestone_score + MILESTONE_TEXT_SCORE_SPAN
+        {
+            for milestone_text_result in milestone_text_results {
+                commands
+                    .borrow_mut()
+                    .issue(GameCommand::DestroyEntity(*milestone_text_result.entity()));
+            }
+        }
+    }
 }
 
 fn remove_game_over_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
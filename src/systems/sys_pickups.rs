@@ -0,0 +1,224 @@
+use rand::Rng;
+use thomas::{IntCoords2d, Priority, TerminalCamera, TerminalTransform};
+
+use crate::{
+    components::{
+        Coin, Collision, GameManager, GameObjectManager, Pickup, PickupKind, Player, PowerUps,
+        RunStatsTracker, WorldRng,
+    },
+    make_pickup, tick_timers_system, GameCommand, GameCommandsArg, GameConfig, Query,
+    QueryResultList, Resolution, System, SystemsGenerator, EVENT_DIFFICULTY_CHANGE, EVENT_RESTART,
+    EVENT_TICK, MAGNET_RANGE, PICKUP_COLLISION_LAYER, PLAYER_COLLISION_LAYER,
+};
+
+pub struct PickupsSystemsGenerator {}
+impl SystemsGenerator for PickupsSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (
+                EVENT_TICK,
+                tick_timers_system::<PowerUps>(|power_ups| {
+                    for power_up in &mut power_ups.active {
+                        power_up.timer.tick();
+                    }
+                }),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<GameObjectManager>(),
+                        Query::new().has::<GameManager>(),
+                        Query::new()
+                            .has_where::<TerminalCamera>(|cam| cam.is_main)
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Resolution>(),
+                    ],
+                    generate_pickups,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<Player>().has::<PowerUps>(),
+                        Query::new().has_where::<Collision>(|coll| {
+                            coll.is_collision_between(
+                                PLAYER_COLLISION_LAYER,
+                                PICKUP_COLLISION_LAYER,
+                            )
+                        }),
+                        Query::new().has::<RunStatsTracker>(),
+                        Query::new().has::<Pickup>(),
+                    ],
+                    handle_pickup_collision,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new()
+                            .has::<Player>()
+                            .has::<PowerUps>()
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<Pickup>().has::<TerminalTransform>(),
+                        Query::new().has::<Coin>().has::<TerminalTransform>(),
+                    ],
+                    attract_pickups,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<PowerUps>(),
+                    ],
+                    end_power_ups,
+                ),
+            ),
+            (
+                EVENT_RESTART,
+                System::new(
+                    vec![Query::new().has::<PowerUps>(), Query::new().has::<Pickup>()],
+                    reset_pickups,
+                ),
+            ),
+            (
+                EVENT_DIFFICULTY_CHANGE,
+                System::new(
+                    vec![Query::new().has::<PowerUps>(), Query::new().has::<Pickup>()],
+                    reset_pickups,
+                ),
+            ),
+        ]
+    }
+}
+
+fn generate_pickups(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_object_manager_results, game_manager_results, main_cam_results, world_rng_results, config_results, resolution_results, ..] =
+        &results[..]
+    {
+        let mut game_object_manager =
+            game_object_manager_results.get_only_mut::<GameObjectManager>();
+        let config = config_results.get_only::<GameConfig>();
+
+        if game_object_manager.pickup_generation_timer.elapsed_ticks() >= config.pickup_wait_ticks {
+            let game_manager = game_manager_results.get_only::<GameManager>();
+            let main_cam_transform = main_cam_results.get_only::<TerminalTransform>();
+            let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+            let resolution = resolution_results.get_only::<Resolution>();
+
+            let roll = world_rng.gen_range(0..100_u8);
+
+            if roll < config.pickup_chance && game_manager.is_playing() {
+                let kind = game_object_manager.pick_pickup_kind(&mut *world_rng);
+
+                commands
+                    .borrow_mut()
+                    .issue(GameCommand::AddEntity(make_pickup(
+                        kind,
+                        &main_cam_transform,
+                        &resolution,
+                    )));
+            }
+
+            game_object_manager.pickup_generation_timer.restart();
+        }
+    }
+}
+
+fn handle_pickup_collision(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [player_results, collision_results, run_stats_tracker_results, pickup_results, ..] =
+        &results[..]
+    {
+        if !collision_results.is_empty() {
+            let mut player = player_results.get_only_mut::<Player>();
+            let mut power_ups = player_results.get_only_mut::<PowerUps>();
+            let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+
+            for collision_result in collision_results {
+                let pickup_entity = collision_result
+                    .components()
+                    .get::<Collision>()
+                    .get_entity_on_layer(PICKUP_COLLISION_LAYER)
+                    .unwrap();
+
+                if let Some(kind) = pickup_results
+                    .iter()
+                    .find(|result| *result.entity() == pickup_entity)
+                    .map(|result| result.components().get::<Pickup>().kind)
+                {
+                    if kind.is_power_up() {
+                        power_ups.activate(kind);
+                        run_stats_tracker.stats.power_ups_collected += 1;
+                    } else if player.lives < player.max_lives {
+                        player.lives += 1;
+                        run_stats_tracker.stats.lives_gained += 1;
+                    }
+
+                    commands
+                        .borrow_mut()
+                        .issue(GameCommand::DestroyEntity(pickup_entity));
+                }
+            }
+        }
+    }
+}
+
+/// While the player has a magnet going, the pickups and coins around them move a cell closer to them on every tick.
+fn attract_pickups(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [running_game_manager_results, player_results, pickup_results, coin_results, ..] =
+        &results[..]
+    {
+        if !running_game_manager_results.is_empty() {
+            let power_ups = player_results.get_only::<PowerUps>();
+            let player_coords = player_results.get_only::<TerminalTransform>().coords;
+
+            if power_ups.is_active(PickupKind::Magnet) && !power_ups.is_tick_skipped {
+                for pickup_result in pickup_results.iter().chain(coin_results) {
+                    let mut transform = pickup_result.components().get_mut::<TerminalTransform>();
+                    let offset = player_coords - transform.coords;
+
+                    if offset.x().abs() <= MAGNET_RANGE {
+                        transform.coords +=
+                            IntCoords2d::new(offset.x().signum(), offset.y().signum());
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn end_power_ups(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [running_game_manager_results, power_ups_results, ..] = &results[..] {
+        if !running_game_manager_results.is_empty() {
+            let mut power_ups = power_ups_results.get_only_mut::<PowerUps>();
+            let duration_ticks = power_ups.duration_ticks;
+
+            power_ups
+                .active
+                .retain(|power_up| power_up.timer.elapsed_ticks() < duration_ticks);
+        }
+    }
+}
+
+fn reset_pickups(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [power_ups_results, pickup_results, ..] = &results[..] {
+        let mut power_ups = power_ups_results.get_only_mut::<PowerUps>();
+
+        power_ups.active.clear();
+        power_ups.is_tick_skipped = false;
+
+        for pickup_result in pickup_results {
+            commands
+                .borrow_mut()
+                .issue(GameCommand::DestroyEntity(*pickup_result.entity()));
+        }
+    }
+}
@@ -1,106 +1,144 @@
 use thomas::{
-    GameCommand, GameCommandsArg, Input, IntCoords2d, Keycode, Layer, Priority, Query,
-    QueryResultList, Rgb, System, SystemsGenerator, TerminalCamera, TerminalCollider,
-    TerminalCollision, TerminalRenderer, TerminalTransform, Timer, EVENT_INIT, EVENT_UPDATE,
+    IntCoords2d, Layer, Priority, Rgb, TerminalCamera, TerminalCollider, TerminalRenderer,
+    TerminalTransform, EVENT_AFTER_INIT,
 };
 
 use crate::{
-    components::{FollowCamera, GameManager, Player},
-    EVENT_RESTART, GROUND_COLLISION_LAYER, OBSTACLE_COLLISION_LAYER, PLAYER_COLLISION_LAYER,
-    PLAYER_DISPLAY, PLAYER_X_OFFSET, PLAYER_Y_OFFSET, SCREEN_HEIGHT,
+    components::{
+        Action, Bank, Collision, ComboTracker, Controls, FollowCamera, GameManager, Obstacle,
+        PickupKind, Pit, Player, PlayerHead, PowerUps, RunStatsTracker,
+    },
+    tick_timers_system, GameCommand, GameCommandsArg, GameConfig, Jump, LifeLostTo, LostLife,
+    MotionInput, ObstacleType, PlayerMotion, Query, QueryResultList, Resolution, System,
+    SystemsGenerator, TickTimer, EVENT_DIFFICULTY_CHANGE, EVENT_RESTART, EVENT_TICK,
+    GROUND_COLLISION_LAYER, OBSTACLE_COLLISION_LAYER, PLAYER_COLLISION_LAYER, PLAYER_DISPLAY,
+    PLAYER_HEAD_DISPLAY, PLAYER_SLIDE_DISPLAY, PLAYER_X_OFFSET,
 };
 
-const MAX_AIR_JUMPS: u8 = 1;
-const JUMP_FORCE: i8 = -50;
-const JUMP_BUTTONS: [Keycode; 1] = [Keycode::Space];
-const GRAVITY: i8 = 15;
-const MAX_LIVES: u8 = 3;
-
 pub struct PlayerSystemsGenerator {}
 impl SystemsGenerator for PlayerSystemsGenerator {
     fn generate(&self) -> Vec<(&'static str, System)> {
         vec![
-            (EVENT_INIT, System::new(vec![], make_player)),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
+                tick_timers_system::<Player>(|player| {
+                    player.motion.tick();
+                    player.invulnerability_timer.tick();
+                }),
+            ),
+            (
+                EVENT_AFTER_INIT,
                 System::new(
                     vec![
-                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
-                        Query::new().has::<Player>(),
-                        Query::new().has::<Input>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Resolution>(),
+                        Query::new().has::<Bank>(),
                     ],
-                    handle_input,
+                    make_player,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has::<Player>(),
-                        Query::new().has_where::<TerminalCollision>(|coll| {
-                            coll.is_collision_between(
-                                PLAYER_COLLISION_LAYER,
-                                OBSTACLE_COLLISION_LAYER,
-                            )
-                        }),
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new()
+                            .has::<Player>()
+                            .has::<PowerUps>()
+                            .has::<FollowCamera>(),
+                        Query::new().has::<Controls>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<RunStatsTracker>(),
+                        Query::new().has::<ComboTracker>(),
                     ],
-                    handle_obstacle_collision,
+                    move_player,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has::<Player>(),
-                        Query::new().has_where::<TerminalCollision>(|coll| {
+                        Query::new().has::<Player>().has::<PowerUps>(),
+                        Query::new().has_where::<Collision>(|coll| {
                             coll.is_collision_between(
                                 PLAYER_COLLISION_LAYER,
-                                PLAYER_COLLISION_LAYER,
+                                OBSTACLE_COLLISION_LAYER,
                             )
                         }),
+                        Query::new().has::<RunStatsTracker>(),
+                        Query::new().has::<ComboTracker>(),
+                        Query::new().has::<Obstacle>(),
                     ],
-                    handle_extra_life_collision,
+                    handle_obstacle_collision,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
-                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
-                        Query::new().has::<Player>().has::<FollowCamera>(),
+                        Query::new()
+                            .has::<Player>()
+                            .has::<PowerUps>()
+                            .has::<FollowCamera>()
+                            .has::<TerminalRenderer>(),
+                        Query::new()
+                            .has::<PlayerHead>()
+                            .has::<FollowCamera>()
+                            .has::<TerminalRenderer>()
+                            .has::<TerminalCollider>(),
                     ],
-                    apply_velocity,
+                    update_player_pose,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new_with_priority(
                     Priority::higher_than(&Priority::default()),
                     vec![
                         Query::new().has::<Player>().has::<TerminalTransform>(),
-                        Query::new().has_where::<TerminalCollision>(|coll| {
+                        Query::new().has_where::<Collision>(|coll| {
                             coll.is_collision_between(
                                 PLAYER_COLLISION_LAYER,
                                 GROUND_COLLISION_LAYER,
                             )
                         }),
+                        Query::new().has::<Pit>().has::<TerminalTransform>(),
                     ],
                     detect_ground,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new()
+                            .has::<Player>()
+                            .has::<PowerUps>()
+                            .has::<FollowCamera>()
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<Pit>().has::<TerminalTransform>(),
+                        Query::new().has::<RunStatsTracker>(),
+                        Query::new().has::<ComboTracker>(),
+                    ],
+                    handle_pit_fall,
+                ),
+            ),
+            (
+                EVENT_TICK,
                 System::new_with_priority(
                     Priority::higher_than(&Priority::default()),
                     vec![
-                        Query::new().has::<GameManager>(),
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
                         Query::new().has::<Player>(),
+                        Query::new().has::<GameConfig>(),
                     ],
-                    update_velocity,
+                    end_invulnerability,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new().has::<Player>(),
@@ -113,153 +151,326 @@ impl SystemsGenerator for PlayerSystemsGenerator {
             ),
             (
                 EVENT_RESTART,
-                System::new(vec![Query::new().has::<Player>()], handle_restart_game),
+                System::new(vec![Query::new().has::<Player>()], reset_lives),
+            ),
+            (
+                EVENT_DIFFICULTY_CHANGE,
+                System::new(vec![Query::new().has::<Player>()], reset_lives),
             ),
         ]
     }
 }
 
-fn make_player(_: Vec<QueryResultList>, commands: GameCommandsArg) {
-    let coords = IntCoords2d::new(PLAYER_X_OFFSET, SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET);
-
-    commands.borrow_mut().issue(GameCommand::AddEntity(vec![
-        Box::new(Player {
-            jump_timer: Timer::start_new(),
-            gravity_timer: Timer::start_new(),
-            velocity_timer: Timer::start_new(),
-            num_times_jumped_since_landing: 0,
-            vertical_velocity: 0,
-            is_on_ground: false,
-            distance_traveled: 0,
-            lives: MAX_LIVES,
-        }),
-        Box::new(TerminalTransform { coords }),
-        Box::new(FollowCamera {
-            base_position: coords,
-            offset: IntCoords2d::zero(),
-        }),
-        Box::new(TerminalRenderer {
-            display: PLAYER_DISPLAY,
-            layer: Layer::base(),
-            background_color: None,
-            foreground_color: Some(Rgb::white()),
-        }),
-        Box::new(TerminalCollider {
-            is_active: true,
-            layer: PLAYER_COLLISION_LAYER,
-        }),
-    ]))
+fn make_player(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [config_results, resolution_results, bank_results, ..] = &results[..] {
+        let config = config_results.get_only::<GameConfig>();
+        let resolution = resolution_results.get_only::<Resolution>();
+        let upgrades = bank_results.get_only::<Bank>().wallet.upgrades;
+        let coords = IntCoords2d::new(PLAYER_X_OFFSET, resolution.ground_y());
+        let head_coords = coords + IntCoords2d::down();
+
+        commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+            Box::new(Player {
+                motion: PlayerMotion::default(),
+                is_jump_held: false,
+                is_on_ground: false,
+                distance_traveled: 0,
+                lives: upgrades.max_lives(&config),
+                max_lives: upgrades.max_lives(&config),
+                max_air_jumps: upgrades.max_air_jumps(&config),
+                is_invulnerable: false,
+                invulnerability_timer: TickTimer::start_new(),
+            }),
+            Box::new(PowerUps::new(upgrades.power_up_ticks(&config))),
+            Box::new(TerminalTransform { coords }),
+            Box::new(FollowCamera {
+                base_position: coords,
+                offset: IntCoords2d::zero(),
+            }),
+            Box::new(TerminalRenderer {
+                display: PLAYER_DISPLAY,
+                layer: Layer::base(),
+                background_color: None,
+                foreground_color: Some(Rgb::white()),
+            }),
+            Box::new(TerminalCollider {
+                is_active: true,
+                layer: PLAYER_COLLISION_LAYER,
+            }),
+        ]));
+
+        commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+            Box::new(PlayerHead {}),
+            Box::new(TerminalTransform {
+                coords: head_coords,
+            }),
+            Box::new(FollowCamera {
+                base_position: head_coords,
+                offset: IntCoords2d::zero(),
+            }),
+            Box::new(TerminalRenderer {
+                display: PLAYER_HEAD_DISPLAY,
+                layer: Layer::base(),
+                background_color: None,
+                foreground_color: Some(Rgb::white()),
+            }),
+            Box::new(TerminalCollider {
+                is_active: true,
+                layer: PLAYER_COLLISION_LAYER,
+            }),
+        ]));
+    }
 }
 
-fn handle_input(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [running_game_manager, player_results, input_results, ..] = &results[..] {
+/// Moves the player up or down and starts and ends their jumps and slides, the same way `RunnerState` does for the
+/// course checks.
+fn move_player(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [running_game_manager, player_results, controls_results, config_results, run_stats_tracker_results, combo_tracker_results, ..] =
+        &results[..]
+    {
         if !running_game_manager.is_empty() {
-            let input = input_results.get_only::<Input>();
+            let controls = controls_results.get_only::<Controls>();
+            let config = config_results.get_only::<GameConfig>();
             let mut player = player_results.get_only_mut::<Player>();
+            let power_ups = player_results.get_only::<PowerUps>();
+            let mut follow_cam = player_results.get_only_mut::<FollowCamera>();
+            let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
 
-            if JUMP_BUTTONS.iter().any(|button| input.is_key_down(&button))
-                && (player.is_on_ground || player.num_times_jumped_since_landing < MAX_AIR_JUMPS)
-            {
-                if !player.is_on_ground {
-                    player.num_times_jumped_since_landing += 1;
+            let max_air_jumps = if power_ups.is_active(PickupKind::ExtraAirJump) {
+                player.max_air_jumps + 1
+            } else {
+                player.max_air_jumps
+            };
+            let input = MotionInput {
+                is_jump_down: controls.is_action_down(&Action::Jump),
+                is_slide_down: controls.is_action_down(&Action::Slide),
+                was_jump_held: player.is_jump_held,
+            };
+            let is_on_ground = player.is_on_ground;
+
+            let motion_step = player
+                .motion
+                .step(&config, input, is_on_ground, max_air_jumps);
+
+            match motion_step.jump_option {
+                Some(Jump::FromGround) => run_stats_tracker.stats.jumps += 1,
+                Some(Jump::InAir) => {
+                    run_stats_tracker.stats.jumps += 1;
+                    run_stats_tracker.stats.double_jumps += 1;
+                    combo_tracker_results
+                        .get_only_mut::<ComboTracker>()
+                        .is_double_jump_pending = true;
                 }
+                None => {}
+            }
 
-                player.vertical_velocity = JUMP_FORCE as i64;
+            if motion_step.climb > 0 {
+                follow_cam.offset += IntCoords2d::down();
+            } else if motion_step.climb < 0 {
+                follow_cam.offset += IntCoords2d::up();
             }
+
+            player.is_jump_held = controls.is_action_pressed(&Action::Jump);
         }
     }
 }
 
-fn apply_velocity(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [running_game_manager_results, player_results, ..] = &results[..] {
+fn end_invulnerability(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [running_game_manager_results, player_results, config_results, ..] = &results[..] {
         if !running_game_manager_results.is_empty() {
+            let config = config_results.get_only::<GameConfig>();
             let mut player = player_results.get_only_mut::<Player>();
-            let mut follow_cam = player_results.get_only_mut::<FollowCamera>();
 
-            if player.vertical_velocity != 0
-                && player.velocity_timer.elapsed_millis()
-                    >= 1000 / i64::abs(player.vertical_velocity) as u128
+            if player.is_invulnerable
+                && player.invulnerability_timer.elapsed_ticks() >= config.invulnerability_ticks
             {
-                follow_cam.offset += if player.vertical_velocity > 0 {
-                    IntCoords2d::up()
-                } else {
-                    IntCoords2d::down()
-                };
-
-                player.velocity_timer.restart();
+                player.is_invulnerable = false;
             }
         }
     }
 }
 
-fn update_velocity(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [game_manager_results, player_results, ..] = &results[..] {
-        let game_manager = game_manager_results.get_only::<GameManager>();
-        let mut player = player_results.get_only_mut::<Player>();
+/// Keeps the player's head on top of them, tucks it away while they slide, blinks the player while they're
+/// invulnerable, and tints them while they're shielded.
+fn update_player_pose(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [player_results, player_head_results, ..] = &results[..] {
+        let player = player_results.get_only::<Player>();
+        let power_ups = player_results.get_only::<PowerUps>();
+        let follow_cam = player_results.get_only::<FollowCamera>();
+        let mut renderer = player_results.get_only_mut::<TerminalRenderer>();
+        let mut head_follow_cam = player_head_results.get_only_mut::<FollowCamera>();
+        let mut head_renderer = player_head_results.get_only_mut::<TerminalRenderer>();
+        let mut head_collider = player_head_results.get_only_mut::<TerminalCollider>();
 
-        if game_manager.is_playing() {
-            if player.is_on_ground {
-                player.vertical_velocity = 0;
-                player.num_times_jumped_since_landing = 0;
-            } else if GRAVITY != 0
-                && player.gravity_timer.elapsed_millis() >= 1000 / i8::abs(GRAVITY) as u128
-            {
-                player.vertical_velocity += GRAVITY as i64;
+        head_follow_cam.offset = follow_cam.offset;
+        head_collider.is_active = !player.motion.is_sliding;
 
-                player.gravity_timer.restart();
-            }
+        let color = if power_ups.is_active(PickupKind::Shield) {
+            PickupKind::Shield.color()
+        } else {
+            Rgb::white()
+        };
+        renderer.foreground_color = Some(color);
+        head_renderer.foreground_color = Some(color);
+
+        if player.is_blinked_out() {
+            renderer.display = ' ';
+            head_renderer.display = ' ';
+        } else if player.motion.is_sliding {
+            renderer.display = PLAYER_SLIDE_DISPLAY;
+            head_renderer.display = ' ';
+        } else {
+            renderer.display = PLAYER_DISPLAY;
+            head_renderer.display = PLAYER_HEAD_DISPLAY;
         }
     }
 }
 
 fn detect_ground(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [player_results, player_ground_collision_results, ..] = &results[..] {
+    if let [player_results, player_ground_collision_results, pit_results, ..] = &results[..] {
         let mut player = player_results.get_only_mut::<Player>();
+        let player_transform = player_results.get_only::<TerminalTransform>();
 
-        player.is_on_ground = !player_ground_collision_results.is_empty();
+        player.is_on_ground = !player_ground_collision_results.is_empty()
+            && !is_over_pit(player_transform.coords.x(), pit_results);
     }
 }
 
+/// Once the player has dropped below the ground into a pit, they're hit, unless they're still invulnerable from the
+/// last hit, and the pit's filled in under them so they can carry on from where they fell.
+fn handle_pit_fall(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [playing_game_manager_results, player_results, pit_results, run_stats_tracker_results, combo_tracker_results, ..] =
+        &results[..]
+    {
+        if !playing_game_manager_results.is_empty() {
+            let mut follow_cam = player_results.get_only_mut::<FollowCamera>();
+
+            if follow_cam.offset.y() > 0 {
+                let mut player = player_results.get_only_mut::<Player>();
+                let mut power_ups = player_results.get_only_mut::<PowerUps>();
+                let player_transform = player_results.get_only::<TerminalTransform>();
+                let mut run_stats_tracker =
+                    run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+                let mut combo_tracker = combo_tracker_results.get_only_mut::<ComboTracker>();
+
+                if !player.is_invulnerable {
+                    hit_player(
+                        &mut player,
+                        &mut power_ups,
+                        &mut run_stats_tracker,
+                        &mut combo_tracker,
+                        LifeLostTo::Pit,
+                    );
+                }
+
+                for pit_result in pit_results {
+                    let pit = pit_result.components().get::<Pit>();
+                    let pit_transform = pit_result.components().get::<TerminalTransform>();
+
+                    if pit.is_under(pit_transform.coords.x(), player_transform.coords.x()) {
+                        commands
+                            .borrow_mut()
+                            .issue(GameCommand::DestroyEntity(*pit_result.entity()));
+                    }
+                }
+
+                follow_cam.offset = IntCoords2d::zero();
+                player.motion.vertical_velocity = 0;
+                player.motion.air_jumps = 0;
+                player.is_on_ground = true;
+            }
+        }
+    }
+}
+
+fn is_over_pit(x: i64, pit_results: &QueryResultList) -> bool {
+    pit_results.iter().any(|pit_result| {
+        pit_result.components().get::<Pit>().is_under(
+            pit_result
+                .components()
+                .get::<TerminalTransform>()
+                .coords
+                .x(),
+            x,
+        )
+    })
+}
+
 fn handle_obstacle_collision(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [player_results, collision_results, ..] = &results[..] {
-        if !collision_results.is_empty() {
-            let mut player = player_results.get_only_mut::<Player>();
+    if let [player_results, collision_results, run_stats_tracker_results, combo_tracker_results, obstacle_results, ..] =
+        &results[..]
+    {
+        let mut player = player_results.get_only_mut::<Player>();
+        let mut power_ups = player_results.get_only_mut::<PowerUps>();
+
+        // Obstacles pass straight through the player while they're invulnerable.
+        if !collision_results.is_empty() && !player.is_invulnerable {
+            let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+            let mut combo_tracker = combo_tracker_results.get_only_mut::<ComboTracker>();
             let obstacle_entity = collision_results[0]
                 .components()
-                .get::<TerminalCollision>()
+                .get::<Collision>()
                 .get_entity_on_layer(OBSTACLE_COLLISION_LAYER)
                 .unwrap();
 
-            player.lives = player.lives.saturating_sub(1);
+            if let Some((hit_obstacle_id, hit_obstacle_type)) = obstacle_results
+                .iter()
+                .find(|result| *result.entity() == obstacle_entity)
+                .map(|result| {
+                    let obstacle = result.components().get::<Obstacle>();
 
-            commands
-                .borrow_mut()
-                .issue(GameCommand::DestroyEntity(obstacle_entity));
+                    (obstacle.obstacle_id, obstacle.obstacle_type)
+                })
+            {
+                hit_player(
+                    &mut player,
+                    &mut power_ups,
+                    &mut run_stats_tracker,
+                    &mut combo_tracker,
+                    match hit_obstacle_type {
+                        ObstacleType::Ground => LifeLostTo::GroundObstacle,
+                        ObstacleType::Air => LifeLostTo::AirObstacle,
+                        ObstacleType::Projectile => LifeLostTo::Projectile,
+                    },
+                );
+
+                // The whole obstacle goes, so the rest of its cells can't cost another life.
+                for obstacle_result in obstacle_results {
+                    if obstacle_result.components().get::<Obstacle>().obstacle_id == hit_obstacle_id
+                    {
+                        commands
+                            .borrow_mut()
+                            .issue(GameCommand::DestroyEntity(*obstacle_result.entity()));
+                    }
+                }
+            }
         }
     }
 }
 
-fn handle_extra_life_collision(results: Vec<QueryResultList>, commands: GameCommandsArg) {
-    if let [player_results, collision_results, ..] = &results[..] {
-        if !collision_results.is_empty() {
-            let mut player = player_results.get_only_mut::<Player>();
-            let extra_life_entity = collision_results[0]
-                .components()
-                .get::<TerminalCollision>()
-                .bodies
-                .iter()
-                .find(|(entity, _)| entity != player_results[0].entity())
-                .unwrap()
-                .0;
-
-            if player.lives < MAX_LIVES {
-                player.lives += 1;
-            }
+/// A shield takes the hit if the player has one going, and otherwise it costs them a life to `cause`. Either way, they
+/// can't be hit again for a while, and their combo's broken.
+fn hit_player(
+    player: &mut Player,
+    power_ups: &mut PowerUps,
+    run_stats_tracker: &mut RunStatsTracker,
+    combo_tracker: &mut ComboTracker,
+    cause: LifeLostTo,
+) {
+    combo_tracker.combo.break_combo();
+    combo_tracker.is_double_jump_pending = false;
 
-            commands
-                .borrow_mut()
-                .issue(GameCommand::DestroyEntity(extra_life_entity));
-        }
+    if power_ups.is_active(PickupKind::Shield) {
+        power_ups.deactivate(PickupKind::Shield);
+        player.start_invulnerability();
+        run_stats_tracker.stats.hits_shielded += 1;
+    } else {
+        player.lose_life();
+        run_stats_tracker.stats.lives_lost += 1;
+        run_stats_tracker.stats.lost_lives.push(LostLife {
+            cause,
+            distance_traveled: player.distance_traveled,
+        });
     }
 }
 
@@ -272,10 +483,11 @@ fn update_distance_traveled(results: Vec<QueryResultList>, _: GameCommandsArg) {
     }
 }
 
-fn handle_restart_game(results: Vec<QueryResultList>, _: GameCommandsArg) {
+fn reset_lives(results: Vec<QueryResultList>, _: GameCommandsArg) {
     if let [player_results, ..] = &results[..] {
         let mut player = player_results.get_only_mut::<Player>();
 
-        player.lives = MAX_LIVES;
+        player.lives = player.max_lives;
+        player.is_invulnerable = false;
     }
 }
@@ -0,0 +1,127 @@
+use std::{cell::RefCell, rc::Rc};
+
+use thomas::{Priority, EVENT_AFTER_INIT};
+
+use crate::{
+    components::{Controls, ReplayPlayback},
+    DifficultyMenu, GameCommand, GameCommandsArg, GameConfig, Query, QueryResultList, Replay,
+    System, SystemsGenerator, EVENT_BEFORE_TICK, EVENT_START,
+};
+
+pub enum ReplayMode {
+    /// Records the actions performed on every tick into the shared replay.
+    Record(Rc<RefCell<Replay>>),
+    /// Drives the controls from the replay instead of the keyboard.
+    Play(Replay),
+}
+
+pub struct ReplaySystemsGenerator {
+    pub mode: ReplayMode,
+}
+impl SystemsGenerator for ReplaySystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        match &self.mode {
+            ReplayMode::Record(replay) => {
+                let replay = Rc::clone(replay);
+                let start_replay = Rc::clone(&replay);
+
+                vec![
+                    (
+                        EVENT_BEFORE_TICK,
+                        System::new_with_priority(
+                            Priority::lower_than(&Priority::highest()),
+                            vec![Query::new().has::<Controls>()],
+                            move |results, _| record_controls(&replay, results),
+                        ),
+                    ),
+                    (
+                        EVENT_START,
+                        System::new(
+                            vec![
+                                Query::new().has::<DifficultyMenu>(),
+                                Query::new().has::<GameConfig>(),
+                            ],
+                            move |results, _| record_difficulty(&start_replay, results),
+                        ),
+                    ),
+                ]
+            }
+            ReplayMode::Play(replay) => {
+                let replay = replay.clone();
+
+                vec![
+                    (
+                        EVENT_AFTER_INIT,
+                        System::new(
+                            vec![Query::new().has::<Controls>()],
+                            move |results, commands| {
+                                start_playback(replay.clone(), results, commands)
+                            },
+                        ),
+                    ),
+                    (
+                        EVENT_BEFORE_TICK,
+                        System::new_with_priority(
+                            Priority::highest(),
+                            vec![Query::new().has::<Controls>().has::<ReplayPlayback>()],
+                            play_replay,
+                        ),
+                    ),
+                ]
+            }
+        }
+    }
+}
+
+fn record_controls(replay: &RefCell<Replay>, results: Vec<QueryResultList>) {
+    if let [controls_results, ..] = &results[..] {
+        let controls = controls_results.get_only::<Controls>();
+
+        replay.borrow_mut().record(controls.pressed_actions());
+    }
+}
+
+/// The difficulty can be changed up until the run starts, so the replay keeps whichever one the run started with.
+fn record_difficulty(replay: &RefCell<Replay>, results: Vec<QueryResultList>) {
+    if let [difficulty_menu_results, config_results, ..] = &results[..] {
+        let difficulty_menu = difficulty_menu_results.get_only::<DifficultyMenu>();
+        let config = config_results.get_only::<GameConfig>();
+
+        replay
+            .borrow_mut()
+            .set_difficulty(difficulty_menu.selected_difficulty(), config.clone());
+    }
+}
+
+fn start_playback(replay: Replay, results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [controls_results, ..] = &results[..] {
+        commands
+            .borrow_mut()
+            .issue(GameCommand::AddComponentsToEntity(
+                *controls_results[0].entity(),
+                vec![Box::new(ReplayPlayback {
+                    replay,
+                    ticks_played: 0,
+                })],
+            ));
+    }
+}
+
+fn play_replay(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [playback_results, ..] = &results[..] {
+        if !playback_results.is_empty() {
+            let mut playback = playback_results.get_only_mut::<ReplayPlayback>();
+            let mut controls = playback_results.get_only_mut::<Controls>();
+
+            controls.update(
+                playback
+                    .replay
+                    .actions_at(playback.ticks_played)
+                    .iter()
+                    .copied(),
+            );
+
+            playback.ticks_played += 1;
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use thomas::{TerminalTransform, Timer, EVENT_INIT};
+
+use crate::{
+    components::{GameManager, GameState, Obstacle, Player, RunStatsTracker, WorldRng, WorldTime},
+    now_unix_seconds, DifficultyMenu, GameCommand, GameCommandsArg, GameConfig, ObstacleType,
+    Outcome, Query, QueryResultList, RunStats, System, SystemsGenerator, DISTANCE_MARKER_SPACING,
+    EVENT_DEFEAT, EVENT_GAME_PAUSE_STATE_CHANGE, EVENT_RESTART, EVENT_TICK, EVENT_VICTORY,
+};
+
+pub struct RunStatsSystemsGenerator {
+    /// Where to export the stats of each run once it's over. Nothing is exported when there isn't a path.
+    pub export_path_option: Option<PathBuf>,
+}
+impl SystemsGenerator for RunStatsSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        let victory_export_path_option = self.export_path_option.clone();
+        let defeat_export_path_option = self.export_path_option.clone();
+
+        vec![
+            (EVENT_INIT, System::new(vec![], make_run_stats_tracker)),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                        Query::new().has::<Player>().has::<TerminalTransform>(),
+                        Query::new()
+                            .has_where::<Obstacle>(|obstacle| !obstacle.is_dodged)
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<RunStatsTracker>(),
+                    ],
+                    count_dodged_obstacles,
+                ),
+            ),
+            (
+                EVENT_GAME_PAUSE_STATE_CHANGE,
+                System::new(
+                    vec![
+                        Query::new().has::<GameManager>(),
+                        Query::new().has::<RunStatsTracker>(),
+                    ],
+                    track_paused_time,
+                ),
+            ),
+            (
+                EVENT_VICTORY,
+                System::new(finish_run_queries(), move |results, commands| {
+                    finish_run(results, commands, &victory_export_path_option)
+                }),
+            ),
+            (
+                EVENT_DEFEAT,
+                System::new(finish_run_queries(), move |results, commands| {
+                    finish_run(results, commands, &defeat_export_path_option)
+                }),
+            ),
+            (
+                EVENT_RESTART,
+                System::new(vec![Query::new().has::<RunStatsTracker>()], reset_run_stats),
+            ),
+        ]
+    }
+}
+
+fn make_run_stats_tracker(_: Vec<QueryResultList>, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(RunStatsTracker {
+            stats: RunStats::default(),
+            pause_timer: Timer::new(),
+        })]));
+}
+
+fn count_dodged_obstacles(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [playing_game_manager_results, player_results, obstacle_results, run_stats_tracker_results, ..] =
+        &results[..]
+    {
+        if !playing_game_manager_results.is_empty() {
+            let player = player_results.get_only::<Player>();
+            let player_transform = player_results.get_only::<TerminalTransform>();
+            let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+
+            // An obstacle's only dodged once every one of its cells is behind the player.
+            let obstacle_ids_ahead: HashSet<u64> = obstacle_results
+                .iter()
+                .filter(|result| {
+                    result.components().get::<TerminalTransform>().coords.x()
+                        >= player_transform.coords.x()
+                })
+                .map(|result| result.components().get::<Obstacle>().obstacle_id)
+                .collect();
+            let mut dodged_obstacle_ids = HashSet::new();
+
+            for obstacle_result in obstacle_results {
+                let mut obstacle = obstacle_result.components().get_mut::<Obstacle>();
+
+                if !obstacle_ids_ahead.contains(&obstacle.obstacle_id) {
+                    obstacle.is_dodged = true;
+
+                    // Obstacles passed while invulnerable are marked dodged so they're never counted, since the
+                    // player didn't have to get out of their way.
+                    if dodged_obstacle_ids.insert(obstacle.obstacle_id) && !player.is_invulnerable {
+                        match obstacle.obstacle_type {
+                            ObstacleType::Ground => {
+                                run_stats_tracker.stats.ground_obstacles_dodged += 1
+                            }
+                            ObstacleType::Air => run_stats_tracker.stats.air_obstacles_dodged += 1,
+                            ObstacleType::Projectile => {
+                                run_stats_tracker.stats.projectiles_dodged += 1
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn track_paused_time(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [game_manager_results, run_stats_tracker_results, ..] = &results[..] {
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+
+        if game_manager.is_paused() {
+            run_stats_tracker.pause_timer.restart();
+        } else if run_stats_tracker.pause_timer.is_running() {
+            run_stats_tracker.stats.paused_millis +=
+                run_stats_tracker.pause_timer.elapsed_millis() as u64;
+
+            run_stats_tracker.pause_timer.stop();
+        }
+    }
+}
+
+fn finish_run_queries() -> Vec<Query> {
+    vec![
+        Query::new().has::<GameManager>(),
+        Query::new().has::<Player>(),
+        Query::new().has::<WorldRng>(),
+        Query::new().has::<WorldTime>(),
+        Query::new().has::<RunStatsTracker>(),
+        Query::new().has::<GameConfig>(),
+        Query::new().has::<DifficultyMenu>(),
+    ]
+}
+
+fn finish_run(
+    results: Vec<QueryResultList>,
+    _: GameCommandsArg,
+    export_path_option: &Option<PathBuf>,
+) {
+    if let [game_manager_results, player_results, world_rng_results, world_time_results, run_stats_tracker_results, config_results, difficulty_menu_results, ..] =
+        &results[..]
+    {
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let player = player_results.get_only::<Player>();
+        let world_rng = world_rng_results.get_only::<WorldRng>();
+        let world_time = world_time_results.get_only::<WorldTime>();
+        let config = config_results.get_only::<GameConfig>();
+        let difficulty_menu = difficulty_menu_results.get_only::<DifficultyMenu>();
+        let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+        let stats = &mut run_stats_tracker.stats;
+
+        stats.seed = world_rng.seed();
+        stats.difficulty = difficulty_menu.selected_difficulty().name();
+        stats.mode = config.mode.name();
+        stats.outcome_option = Some(if game_manager.game_state == GameState::Victory {
+            Outcome::Victory
+        } else {
+            Outcome::Defeat
+        });
+        stats.score = game_manager.score;
+        stats.milestones_reached = game_manager.milestones_reached;
+        stats.distance_traveled = player.distance_traveled;
+        stats.distance_markers_passed = player.distance_traveled / DISTANCE_MARKER_SPACING;
+        stats.end_hour = world_time.current_time;
+        stats.end_time_of_day = world_time.time_of_day().name();
+        stats.finished_at = now_unix_seconds();
+
+        if let Some(export_path) = export_path_option {
+            // There's nowhere to report a failed export mid-game, so the run's stats are dropped instead.
+            let _ = stats.append_to(export_path);
+        }
+    }
+}
+
+fn reset_run_stats(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [run_stats_tracker_results, ..] = &results[..] {
+        let mut run_stats_tracker = run_stats_tracker_results.get_only_mut::<RunStatsTracker>();
+
+        run_stats_tracker.stats = RunStats::default();
+        run_stats_tracker.pause_timer.stop();
+    }
+}
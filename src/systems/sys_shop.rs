@@ -0,0 +1,222 @@
+use std::rc::Rc;
+
+use thomas::{Identity, Priority, EVENT_INIT};
+
+use crate::{
+    add_shop_text,
+    components::{
+        Action, Bank, Controls, GameManager, GameObjectManager, GameState, Player, PowerUps, Shop,
+    },
+    GameCommand, GameCommandsArg, GameConfig, Query, QueryResultList, System, SystemsGenerator,
+    Upgrade, UpgradeLevels, EVENT_DIFFICULTY_CHANGE, EVENT_SHOP_STATE_CHANGE, EVENT_TICK,
+    SHOP_TEXT_NAME,
+};
+
+pub struct ShopSystemsGenerator {}
+impl SystemsGenerator for ShopSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (EVENT_INIT, System::new(vec![], make_shop)),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<Controls>(),
+                        Query::new().has_where::<GameManager>(|gm| {
+                            gm.is_waiting_to_start() || gm.is_game_over() || gm.is_shopping()
+                        }),
+                        Query::new().has::<Shop>(),
+                    ],
+                    handle_toggle_shop,
+                ),
+            ),
+            (
+                EVENT_TICK,
+                System::new(
+                    vec![
+                        Query::new().has::<Controls>(),
+                        Query::new().has_where::<GameManager>(|gm| gm.is_shopping()),
+                        Query::new().has::<Shop>(),
+                        Query::new().has::<Bank>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Player>().has::<PowerUps>(),
+                        Query::new().has::<GameObjectManager>(),
+                        Query::new().has_where::<Identity>(|id| id.name == SHOP_TEXT_NAME),
+                    ],
+                    handle_shop_input,
+                ),
+            ),
+            (
+                EVENT_SHOP_STATE_CHANGE,
+                System::new(
+                    vec![
+                        Query::new().has::<GameManager>(),
+                        Query::new().has::<Shop>(),
+                        Query::new().has::<Bank>(),
+                        Query::new().has_where::<Identity>(|id| id.name == SHOP_TEXT_NAME),
+                    ],
+                    update_shop_text,
+                ),
+            ),
+            (
+                EVENT_DIFFICULTY_CHANGE,
+                // The player's lives are topped back up on a difficulty change, so they need to know how many that is
+                // first.
+                System::new_with_priority(
+                    Priority::higher_than(&Priority::default()),
+                    vec![
+                        Query::new().has::<Bank>(),
+                        Query::new().has::<GameConfig>(),
+                        Query::new().has::<Player>().has::<PowerUps>(),
+                        Query::new().has::<GameObjectManager>(),
+                    ],
+                    refresh_upgrades,
+                ),
+            ),
+        ]
+    }
+}
+
+fn make_shop(_: Vec<QueryResultList>, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(Shop {
+            selected_index: 0,
+            return_state: GameState::WaitingToStart,
+        })]));
+}
+
+/// Opens the shop from the start screen or once a run is over, and goes back to that screen when it's closed.
+fn handle_toggle_shop(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [controls_results, between_runs_game_manager_results, shop_results, ..] = &results[..] {
+        let controls = controls_results.get_only::<Controls>();
+
+        if !between_runs_game_manager_results.is_empty() && controls.is_action_down(&Action::Shop) {
+            let mut game_manager = between_runs_game_manager_results.get_only_mut::<GameManager>();
+            let mut shop = shop_results.get_only_mut::<Shop>();
+
+            if game_manager.is_shopping() {
+                game_manager.game_state = shop.return_state.clone();
+            } else {
+                shop.return_state = game_manager.game_state.clone();
+                game_manager.game_state = GameState::Shopping;
+            }
+
+            commands
+                .borrow_mut()
+                .issue(GameCommand::TriggerEvent(EVENT_SHOP_STATE_CHANGE));
+        }
+    }
+}
+
+fn handle_shop_input(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [controls_results, shopping_game_manager_results, shop_results, bank_results, config_results, player_results, game_object_manager_results, shop_text_results, ..] =
+        &results[..]
+    {
+        if shopping_game_manager_results.is_empty() {
+            return;
+        }
+
+        let controls = controls_results.get_only::<Controls>();
+        let mut shop = shop_results.get_only_mut::<Shop>();
+        let mut bank = bank_results.get_only_mut::<Bank>();
+
+        if controls.is_action_down(&Action::Left) {
+            shop.selected_index =
+                (shop.selected_index + Upgrade::ALL.len() - 1) % Upgrade::ALL.len();
+        } else if controls.is_action_down(&Action::Right) {
+            shop.selected_index = (shop.selected_index + 1) % Upgrade::ALL.len();
+        } else if controls.is_action_down(&Action::Jump)
+            && bank.wallet.buy(Upgrade::ALL[shop.selected_index])
+        {
+            if let Some(path) = &bank.path_option {
+                // Like a deposit, a failed save only loses the purchase once the game is closed.
+                let _ = bank.wallet.save(path);
+            }
+
+            let config = config_results.get_only::<GameConfig>();
+            let mut player = player_results.get_only_mut::<Player>();
+            let mut power_ups = player_results.get_only_mut::<PowerUps>();
+            let mut game_object_manager =
+                game_object_manager_results.get_only_mut::<GameObjectManager>();
+
+            apply_upgrades(
+                &bank.wallet.upgrades,
+                &config,
+                &mut player,
+                &mut power_ups,
+                &mut game_object_manager,
+            );
+
+            // A run that's over gets its lives back when it restarts, but one that hasn't started should show the new
+            // life straight away.
+            if shop.return_state == GameState::WaitingToStart {
+                player.lives = player.max_lives;
+            }
+        } else {
+            return;
+        }
+
+        remove_shop_text(shop_text_results, Rc::clone(&commands));
+        add_shop_text(commands, &bank.wallet, shop.selected_index);
+    }
+}
+
+fn update_shop_text(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_manager_results, shop_results, bank_results, shop_text_results, ..] = &results[..]
+    {
+        let game_manager = game_manager_results.get_only::<GameManager>();
+
+        remove_shop_text(shop_text_results, Rc::clone(&commands));
+
+        if game_manager.is_shopping() {
+            let shop = shop_results.get_only::<Shop>();
+            let bank = bank_results.get_only::<Bank>();
+
+            add_shop_text(commands, &bank.wallet, shop.selected_index);
+        }
+    }
+}
+
+fn remove_shop_text(shop_text_results: &QueryResultList, commands: GameCommandsArg) {
+    for shop_text_result in shop_text_results {
+        commands
+            .borrow_mut()
+            .issue(GameCommand::DestroyEntity(*shop_text_result.entity()));
+    }
+}
+
+fn refresh_upgrades(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [bank_results, config_results, player_results, game_object_manager_results, ..] =
+        &results[..]
+    {
+        let bank = bank_results.get_only::<Bank>();
+        let config = config_results.get_only::<GameConfig>();
+        let mut player = player_results.get_only_mut::<Player>();
+        let mut power_ups = player_results.get_only_mut::<PowerUps>();
+        let mut game_object_manager =
+            game_object_manager_results.get_only_mut::<GameObjectManager>();
+
+        apply_upgrades(
+            &bank.wallet.upgrades,
+            &config,
+            &mut player,
+            &mut power_ups,
+            &mut game_object_manager,
+        );
+    }
+}
+
+/// Brings everything the upgrades change up to date with how many times each has been bought.
+fn apply_upgrades(
+    upgrades: &UpgradeLevels,
+    config: &GameConfig,
+    player: &mut Player,
+    power_ups: &mut PowerUps,
+    game_object_manager: &mut GameObjectManager,
+) {
+    player.max_lives = upgrades.max_lives(config);
+    player.max_air_jumps = upgrades.max_air_jumps(config);
+    power_ups.duration_ticks = upgrades.power_up_ticks(config);
+    game_object_manager.pickup_spawn_weights = upgrades.pickup_spawn_weights();
+}
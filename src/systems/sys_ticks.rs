@@ -0,0 +1,109 @@
+use thomas::{Component, Priority, Timer, EVENT_BEFORE_UPDATE, EVENT_INIT};
+
+use crate::{
+    components::{GameClock, GameManager, PowerUps},
+    GameCommand, GameCommandsArg, Query, QueryResultList, System, SystemsGenerator,
+    EVENT_AFTER_TICK, EVENT_BEFORE_TICK, EVENT_TICK, MAX_TICKS_PER_FRAME, TICKS_PER_SECOND,
+};
+
+/// Ticks the game's clock in real time. A `Simulation` ticks the clock itself instead, once every frame.
+pub struct ClockSystemsGenerator {}
+impl SystemsGenerator for ClockSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![
+            (EVENT_INIT, System::new(vec![], make_game_clock)),
+            (
+                EVENT_BEFORE_UPDATE,
+                System::new(vec![Query::new().has::<GameClock>()], run_due_ticks),
+            ),
+        ]
+    }
+}
+
+pub struct TicksSystemsGenerator {}
+impl SystemsGenerator for TicksSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        vec![(
+            EVENT_TICK,
+            System::new_with_priority(
+                Priority::highest(),
+                vec![
+                    Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+                    Query::new().has::<PowerUps>(),
+                ],
+                skip_slow_motion_ticks,
+            ),
+        )]
+    }
+}
+
+/// Makes a system that advances the `TickTimer`s of every entity with a `T` whenever game time passes. Each feature
+/// adds one of these for the timers it keeps.
+///
+/// Game time only passes while the game is being played, so nothing driven by the clock moves on while the game is
+/// waiting, paused, or over. In slow motion, it only passes every other tick.
+pub fn tick_timers_system<T: Component + 'static>(tick: fn(&mut T)) -> System {
+    System::new_with_priority(
+        Priority::lower_than(&Priority::highest()),
+        vec![
+            Query::new().has_where::<GameManager>(|gm| gm.is_playing()),
+            Query::new().has_where::<PowerUps>(|power_ups| !power_ups.is_tick_skipped),
+            Query::new().has::<T>(),
+        ],
+        move |results, _| {
+            if let [running_game_manager_results, ticking_power_ups_results, timed_results, ..] =
+                &results[..]
+            {
+                if !running_game_manager_results.is_empty() && !ticking_power_ups_results.is_empty()
+                {
+                    for timed_result in timed_results {
+                        tick(&mut timed_result.components().get_mut::<T>());
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn make_game_clock(_: Vec<QueryResultList>, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(GameClock {
+            timer: Timer::start_new(),
+            ticks_run: 0,
+        })]));
+}
+
+/// Runs however many ticks have come due since the last frame. They run once the keyboard's been read for the frame.
+fn run_due_ticks(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [game_clock_results, ..] = &results[..] {
+        let mut game_clock = game_clock_results.get_only_mut::<GameClock>();
+
+        let ticks_due =
+            (game_clock.timer.elapsed_millis() * TICKS_PER_SECOND as u128 / 1000) as u64;
+        let num_ticks = ticks_due
+            .saturating_sub(game_clock.ticks_run)
+            .min(MAX_TICKS_PER_FRAME);
+
+        game_clock.ticks_run = ticks_due;
+
+        for _ in 0..num_ticks {
+            for event_name in [EVENT_BEFORE_TICK, EVENT_TICK, EVENT_AFTER_TICK] {
+                commands
+                    .borrow_mut()
+                    .issue(GameCommand::TriggerEvent(event_name));
+            }
+        }
+    }
+}
+
+/// Works out whether game time passes this tick, before any of the `TickTimer`s are advanced.
+fn skip_slow_motion_ticks(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [running_game_manager_results, power_ups_results, ..] = &results[..] {
+        if !running_game_manager_results.is_empty() {
+            let mut power_ups = power_ups_results.get_only_mut::<PowerUps>();
+
+            power_ups.is_tick_skipped = power_ups.is_next_tick_skipped();
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use thomas::EVENT_INIT;
+
+use crate::{
+    components::{Bank, GameManager},
+    GameCommand, GameCommandsArg, Query, QueryResultList, System, SystemsGenerator, Wallet,
+    EVENT_DEFEAT, EVENT_VICTORY,
+};
+
+pub struct WalletSystemsGenerator {
+    /// The wallet as it stands when the game starts.
+    pub wallet: Wallet,
+    /// The wallet's only kept for this session when there isn't a path.
+    pub path_option: Option<PathBuf>,
+}
+impl SystemsGenerator for WalletSystemsGenerator {
+    fn generate(&self) -> Vec<(&'static str, System)> {
+        let wallet = self.wallet;
+        let path_option = self.path_option.clone();
+
+        vec![
+            (
+                EVENT_INIT,
+                System::new(vec![], move |_, commands| {
+                    make_bank(wallet, path_option.clone(), commands)
+                }),
+            ),
+            (
+                EVENT_VICTORY,
+                System::new(deposit_coins_queries(), deposit_coins),
+            ),
+            (
+                EVENT_DEFEAT,
+                System::new(deposit_coins_queries(), deposit_coins),
+            ),
+        ]
+    }
+}
+
+fn make_bank(wallet: Wallet, path_option: Option<PathBuf>, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(Bank {
+            wallet,
+            path_option,
+        })]));
+}
+
+fn deposit_coins_queries() -> Vec<Query> {
+    vec![
+        Query::new().has::<GameManager>(),
+        Query::new().has::<Bank>(),
+    ]
+}
+
+/// Puts the coins picked up over the run into the wallet once the run is over.
+fn deposit_coins(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [game_manager_results, bank_results, ..] = &results[..] {
+        let game_manager = game_manager_results.get_only::<GameManager>();
+        let mut bank = bank_results.get_only_mut::<Bank>();
+
+        if game_manager.coins > 0 {
+            bank.wallet.deposit(game_manager.coins);
+
+            if let Some(path) = &bank.path_option {
+                // A failed save only loses the run's coins once the game is closed, and there's nowhere to report it
+                // mid-game. There's no path when the wallet there couldn't be read, so it's never saved over.
+                let _ = bank.wallet.save(path);
+            }
+        }
+    }
+}
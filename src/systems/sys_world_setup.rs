@@ -1,41 +1,96 @@
 use std::{collections::HashMap, rc::Rc};
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use thomas::{
-    Dimensions2d, GameCommand, GameCommandsArg, Identity, IntCoords2d, Layer, Matrix,
-    QueryResultList, Rgb, System, SystemsGenerator, TerminalCollider, TerminalRenderer,
-    TerminalTransform, Timer, EVENT_INIT,
+    Dimensions2d, Identity, IntCoords2d, Layer, Matrix, Rgb, TerminalCollider, TerminalRenderer,
+    TerminalTransform, Timer, EVENT_AFTER_INIT, EVENT_INIT,
 };
 
 use crate::{
     add_building,
-    components::{FollowCamera, WorldTime},
-    GROUND_COLLISION_LAYER, PLAYER_X_OFFSET, PLAYER_Y_OFFSET, SCREEN_HEIGHT, SCREEN_WIDTH,
-    SKY_COLOR_TRANSITION_TIMER_NAME, STAR_COLOR_TRANSITION_TIMER_NAME, STAR_DISPLAY, STAR_LAYER,
-    STAR_NAME, SUN_COLOR_TRANSITION_TIMER_NAME, SUN_ID, SUN_LAYER, SUN_PIECE_NAME,
-    WINDOW_COLOR_TRANSITION_TIMER_NAME,
+    components::{FollowCamera, WorldRng, WorldTime, RUN_START_TIME},
+    GameCommand, GameCommandsArg, Query, QueryResultList, Resolution, System, SystemsGenerator,
+    TickTimer, GROUND_COLLISION_LAYER, GROUND_COLOR, GROUND_PIECE_NAME, PLAYER_X_OFFSET,
+    PLAYER_Y_OFFSET, SKY_COLOR_TRANSITION_TIMER_NAME, STAR_COLOR_TRANSITION_TIMER_NAME,
+    STAR_DISPLAY, STAR_LAYER, STAR_NAME, SUN_COLOR_TRANSITION_TIMER_NAME, SUN_ID, SUN_LAYER,
+    SUN_PIECE_NAME,
 };
 
-const AVAILABLE_BACKGROUND_HEIGHT: u64 = SCREEN_HEIGHT as u64 - PLAYER_Y_OFFSET as u64;
+/// How many stars there are on a screen of the default resolution.
+const NUM_STARS: u64 = 26;
+/// How many buildings there are on a screen of the default resolution.
+const NUM_START_BUILDINGS: u64 = 15;
 
-const GROUND_COLOR: Rgb = Rgb(94, 153, 84);
-
-const NUM_STARS: u8 = 26;
-const NUM_START_BUILDINGS: u8 = 15;
-
-pub struct WorldSetupSystemsGenerator {}
+pub struct WorldSetupSystemsGenerator {
+    pub seed: u64,
+    /// Whether every run is generated from `seed`. See `WorldRng::reseed_for_next_run`.
+    pub is_seed_fixed: bool,
+    pub resolution: Resolution,
+}
 impl SystemsGenerator for WorldSetupSystemsGenerator {
     fn generate(&self) -> Vec<(&'static str, System)> {
+        let seed = self.seed;
+        let is_seed_fixed = self.is_seed_fixed;
+        let resolution = self.resolution;
+
         vec![
+            (
+                EVENT_INIT,
+                System::new(vec![], move |_, commands| {
+                    make_world_rng(seed, is_seed_fixed, commands)
+                }),
+            ),
+            (
+                EVENT_INIT,
+                System::new(vec![], move |_, commands| {
+                    make_resolution(resolution, commands)
+                }),
+            ),
             (EVENT_INIT, System::new(vec![], make_world_time)),
-            (EVENT_INIT, System::new(vec![], make_ground)),
-            (EVENT_INIT, System::new(vec![], make_skyline)),
-            (EVENT_INIT, System::new(vec![], make_stars)),
+            (
+                EVENT_AFTER_INIT,
+                System::new(vec![Query::new().has::<Resolution>()], make_ground),
+            ),
+            (
+                EVENT_AFTER_INIT,
+                System::new(
+                    vec![
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<Resolution>(),
+                    ],
+                    make_skyline,
+                ),
+            ),
+            (
+                EVENT_AFTER_INIT,
+                System::new(
+                    vec![
+                        Query::new().has::<WorldRng>(),
+                        Query::new().has::<Resolution>(),
+                    ],
+                    make_stars,
+                ),
+            ),
             (EVENT_INIT, System::new(vec![], make_sun)),
         ]
     }
 }
 
+fn make_world_rng(seed: u64, is_seed_fixed: bool, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(WorldRng::new(
+            seed,
+            is_seed_fixed,
+        ))]));
+}
+
+fn make_resolution(resolution: Resolution, commands: GameCommandsArg) {
+    commands
+        .borrow_mut()
+        .issue(GameCommand::AddEntity(vec![Box::new(resolution)]));
+}
+
 fn make_sun(_: Vec<QueryResultList>, commands: GameCommandsArg) {
     let coords = IntCoords2d::zero();
 
@@ -57,7 +112,7 @@ fn make_sun(_: Vec<QueryResultList>, commands: GameCommandsArg) {
             foreground_color: None,
             layer: SUN_LAYER,
         }),
-        Box::new(TerminalTransform { coords: coords }),
+        Box::new(TerminalTransform { coords }),
         Box::new(FollowCamera {
             base_position: coords,
             offset: IntCoords2d::zero(),
@@ -88,30 +143,35 @@ fn make_sun(_: Vec<QueryResultList>, commands: GameCommandsArg) {
     ]));
 }
 
-fn make_stars(_: Vec<QueryResultList>, commands: GameCommandsArg) {
-    for _ in 0..NUM_STARS {
-        let coords = IntCoords2d::new(
-            thread_rng().gen_range(0..SCREEN_WIDTH as i64),
-            thread_rng().gen_range(0..AVAILABLE_BACKGROUND_HEIGHT as i64),
-        );
+fn make_stars(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [world_rng_results, resolution_results, ..] = &results[..] {
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+        let resolution = resolution_results.get_only::<Resolution>();
 
-        commands.borrow_mut().issue(GameCommand::AddEntity(vec![
-            Box::new(TerminalRenderer {
-                display: STAR_DISPLAY,
-                layer: STAR_LAYER,
-                foreground_color: None,
-                background_color: None,
-            }),
-            Box::new(TerminalTransform { coords }),
-            Box::new(FollowCamera {
-                base_position: coords,
-                offset: IntCoords2d::zero(),
-            }),
-            Box::new(Identity {
-                id: String::from(""),
-                name: String::from(STAR_NAME),
-            }),
-        ]))
+        for _ in 0..resolution.scale_by_area(NUM_STARS) {
+            let coords = IntCoords2d::new(
+                world_rng.gen_range(0..resolution.width() as i64),
+                world_rng.gen_range(0..resolution.background_height() as i64),
+            );
+
+            commands.borrow_mut().issue(GameCommand::AddEntity(vec![
+                Box::new(TerminalRenderer {
+                    display: STAR_DISPLAY,
+                    layer: STAR_LAYER,
+                    foreground_color: None,
+                    background_color: None,
+                }),
+                Box::new(TerminalTransform { coords }),
+                Box::new(FollowCamera {
+                    base_position: coords,
+                    offset: IntCoords2d::zero(),
+                }),
+                Box::new(Identity {
+                    id: String::from(""),
+                    name: String::from(STAR_NAME),
+                }),
+            ]))
+        }
     }
 }
 
@@ -119,27 +179,31 @@ fn make_world_time(_: Vec<QueryResultList>, commands: GameCommandsArg) {
     commands
         .borrow_mut()
         .issue(GameCommand::AddEntity(vec![Box::new(WorldTime {
-            current_time: 9,
-            advance_time_timer: Timer::start_new(),
+            current_time: RUN_START_TIME,
+            advance_time_timer: TickTimer::start_new(),
+            window_timer: TickTimer::start_new(),
             color_transition_timers: HashMap::from([
                 (SKY_COLOR_TRANSITION_TIMER_NAME, Timer::new()),
                 (STAR_COLOR_TRANSITION_TIMER_NAME, Timer::new()),
                 (SUN_COLOR_TRANSITION_TIMER_NAME, Timer::new()),
-                (WINDOW_COLOR_TRANSITION_TIMER_NAME, Timer::new()),
             ]),
         })]))
 }
 
-fn make_ground(_: Vec<QueryResultList>, commands: GameCommandsArg) {
-    make_real_ground(Rc::clone(&commands));
-    make_decorative_ground(Rc::clone(&commands));
+fn make_ground(results: Vec<QueryResultList>, commands: GameCommandsArg) {
+    if let [resolution_results, ..] = &results[..] {
+        let resolution = resolution_results.get_only::<Resolution>();
+
+        make_real_ground(Rc::clone(&commands), &resolution);
+        make_decorative_ground(Rc::clone(&commands), &resolution);
+    }
 }
 
-fn make_real_ground(commands: GameCommandsArg) {
-    let coords = IntCoords2d::new(PLAYER_X_OFFSET, SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET);
+fn make_real_ground(commands: GameCommandsArg, resolution: &Resolution) {
+    let coords = IntCoords2d::new(PLAYER_X_OFFSET, resolution.ground_y());
 
     commands.borrow_mut().issue(GameCommand::AddEntity(vec![
-        Box::new(TerminalTransform { coords: coords }),
+        Box::new(TerminalTransform { coords }),
         Box::new(FollowCamera {
             base_position: coords,
             offset: IntCoords2d::zero(),
@@ -151,12 +215,12 @@ fn make_real_ground(commands: GameCommandsArg) {
     ]));
 }
 
-fn make_decorative_ground(commands: GameCommandsArg) {
+fn make_decorative_ground(commands: GameCommandsArg, resolution: &Resolution) {
     let ground_fill_matrix = Matrix::new(
-        Dimensions2d::new(PLAYER_Y_OFFSET as u64, SCREEN_WIDTH as u64),
+        Dimensions2d::new(PLAYER_Y_OFFSET as u64, resolution.width() as u64),
         || (),
     );
-    let ground_start_coords = IntCoords2d::new(0, SCREEN_HEIGHT as i64 - PLAYER_Y_OFFSET + 1);
+    let ground_start_coords = IntCoords2d::new(0, resolution.ground_y() + 1);
 
     for cell in &ground_fill_matrix {
         let coords = ground_start_coords + *cell.location();
@@ -173,28 +237,43 @@ fn make_decorative_ground(commands: GameCommandsArg) {
                 base_position: coords,
                 offset: IntCoords2d::zero(),
             }),
+            Box::new(Identity {
+                id: String::from(""),
+                name: String::from(GROUND_PIECE_NAME),
+            }),
         ]));
     }
 }
 
-fn make_skyline(_: Vec<QueryResultList>, commands: GameCommandsArg) {
+fn make_skyline(results: Vec<QueryResultList>, commands: GameCommandsArg) {
     const BUILDING_MIN_WIDTH: u64 = 3;
     const BUILDING_MAX_WIDTH: u64 = 6;
 
-    const BUILDING_MIN_HEIGHT: u64 =
-        AVAILABLE_BACKGROUND_HEIGHT - (AVAILABLE_BACKGROUND_HEIGHT as f64 * 0.8) as u64;
-    const BUILDING_MAX_HEIGHT: u64 = AVAILABLE_BACKGROUND_HEIGHT - 1;
+    if let [world_rng_results, resolution_results, ..] = &results[..] {
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
+        let resolution = resolution_results.get_only::<Resolution>();
+
+        let background_height = resolution.background_height();
+        let building_min_height = background_height - (background_height as f64 * 0.8) as u64;
+        let building_max_height = background_height - 1;
 
-    let mut x_coord = thread_rng().gen_range(1..5);
+        let mut x_coord = world_rng.gen_range(1..5);
 
-    for _ in 0..NUM_START_BUILDINGS {
-        let size = Dimensions2d::new(
-            thread_rng().gen_range(BUILDING_MIN_HEIGHT..=BUILDING_MAX_HEIGHT),
-            thread_rng().gen_range(BUILDING_MIN_WIDTH..=BUILDING_MAX_WIDTH),
-        );
+        for _ in 0..resolution.scale_by_width(NUM_START_BUILDINGS) {
+            let size = Dimensions2d::new(
+                world_rng.gen_range(building_min_height..=building_max_height),
+                world_rng.gen_range(BUILDING_MIN_WIDTH..=BUILDING_MAX_WIDTH),
+            );
 
-        add_building(Rc::clone(&commands), x_coord, size.clone());
+            add_building(
+                Rc::clone(&commands),
+                &mut *world_rng,
+                &resolution,
+                x_coord,
+                size,
+            );
 
-        x_coord += size.width() as i64;
+            x_coord += size.width() as i64;
+        }
     }
 }
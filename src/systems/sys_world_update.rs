@@ -1,27 +1,28 @@
 use std::collections::HashSet;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use thomas::{
-    GameCommand, GameCommandsArg, Identity, IntCoords2d, IntVector2, Lerp, Query, QueryResultList,
-    Rgb, System, SystemsGenerator, TerminalRenderer, TerminalRendererState, Timer, EVENT_UPDATE,
+    Identity, IntCoords2d, IntVector2, Lerp, Rgb, TerminalCamera, TerminalRenderer,
+    TerminalRendererState, TerminalTransform, Timer, EVENT_UPDATE,
 };
 
 use crate::{
     components::{
-        FollowCamera, Player, SkylineBuilding, TimeOfDay, WorldTime, NOON_TIME, SUNRISE_TIME,
-        SUNSET_TIME,
+        FollowCamera, Pit, Player, SkylineBuilding, TimeOfDay, WorldRng, WorldTime, NOON_TIME,
+        RUN_START_TIME, SUNRISE_TIME, SUNSET_TIME,
     },
-    get_color, BUILDING_PIECE_NAME, EVENT_TIME_OF_DAY_CHANGE, SCREEN_HEIGHT, SCREEN_WIDTH,
-    SKY_COLORS, SKY_COLOR_TRANSITION_TIMER_NAME, STAR_COLORS, STAR_COLOR_TRANSITION_TIMER_NAME,
-    STAR_DISPLAY, STAR_NAME, SUN_COLORS, SUN_COLOR_TRANSITION_TIMER_NAME, SUN_ID, SUN_PIECE_NAME,
-    WINDOW_COLOR_TRANSITION_TIMER_NAME, WINDOW_DISPLAY,
+    get_color, tick_timers_system, GameCommand, GameCommandsArg, Query, QueryResultList,
+    Resolution, System, SystemsGenerator, BUILDING_PIECE_NAME, EVENT_RESTART, EVENT_START,
+    EVENT_TICK, EVENT_TIME_OF_DAY_CHANGE, GROUND_COLOR, GROUND_PIECE_NAME, PIT_COLOR, SKY_COLORS,
+    SKY_COLOR_TRANSITION_TIMER_NAME, STAR_COLORS, STAR_COLOR_TRANSITION_TIMER_NAME, STAR_DISPLAY,
+    STAR_NAME, SUN_COLORS, SUN_COLOR_TRANSITION_TIMER_NAME, SUN_ID, SUN_PIECE_NAME, WINDOW_DISPLAY,
 };
 
-const ADVANCE_TIME_WAIT_TIME_MILLIS: u128 = 5000;
+const ADVANCE_TIME_WAIT_TICKS: u64 = 150;
 
 const COLOR_TRANSITION_TIME_MILLIS: u128 = 5000;
 
-const WINDOW_TURN_OFF_TIME_MILLIS: u128 = 800;
+const WINDOW_TURN_OFF_TICKS: u64 = 24;
 
 const SKYLINE_MOVE_INTERVAL: u64 = 200;
 
@@ -34,7 +35,7 @@ impl SystemsGenerator for WorldUpdateSystemsGenerator {
                 System::new(
                     vec![
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == BUILDING_PIECE_NAME)
+                            .has_where::<Identity>(|id| id.name == BUILDING_PIECE_NAME)
                             .has::<FollowCamera>()
                             .has::<SkylineBuilding>(),
                         Query::new().has::<Player>(),
@@ -43,9 +44,24 @@ impl SystemsGenerator for WorldUpdateSystemsGenerator {
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
+                tick_timers_system::<WorldTime>(|world_time| {
+                    world_time.advance_time_timer.tick();
+                    world_time.window_timer.tick();
+                }),
+            ),
+            (
+                EVENT_TICK,
                 System::new(vec![Query::new().has::<WorldTime>()], update_world_time),
             ),
+            (
+                EVENT_START,
+                System::new(reset_world_time_queries(), reset_world_time),
+            ),
+            (
+                EVENT_RESTART,
+                System::new(reset_world_time_queries(), reset_world_time),
+            ),
             (
                 EVENT_UPDATE,
                 System::new(
@@ -56,7 +72,7 @@ impl SystemsGenerator for WorldUpdateSystemsGenerator {
                             .has_where::<Identity>(|id| id.name == STAR_NAME)
                             .has::<TerminalRenderer>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == SUN_PIECE_NAME)
+                            .has_where::<Identity>(|id| id.name == SUN_PIECE_NAME)
                             .has::<TerminalRenderer>(),
                     ],
                     update_world_colors_from_time,
@@ -68,22 +84,20 @@ impl SystemsGenerator for WorldUpdateSystemsGenerator {
                     vec![
                         Query::new().has::<WorldTime>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == BUILDING_PIECE_NAME)
+                            .has_where::<Identity>(|id| id.name == BUILDING_PIECE_NAME)
                             .has_where::<TerminalRenderer>(|renderer| renderer.display == ' '),
+                        Query::new().has::<WorldRng>(),
                     ],
                     turn_on_windows,
                 ),
             ),
             (
-                EVENT_UPDATE,
+                EVENT_TICK,
                 System::new(
                     vec![
                         Query::new().has::<WorldTime>(),
-                        Query::new()
-                            .has_where::<Identity>(|id| &id.name == BUILDING_PIECE_NAME)
-                            .has_where::<TerminalRenderer>(|renderer| {
-                                renderer.display == WINDOW_DISPLAY
-                            }),
+                        lit_windows_query(),
+                        Query::new().has::<WorldRng>(),
                     ],
                     turn_off_windows,
                 ),
@@ -94,15 +108,32 @@ impl SystemsGenerator for WorldUpdateSystemsGenerator {
                     vec![
                         Query::new().has::<WorldTime>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.id == SUN_ID)
+                            .has_where::<Identity>(|id| id.id == SUN_ID)
                             .has::<FollowCamera>(),
                         Query::new()
-                            .has_where::<Identity>(|id| &id.name == SUN_PIECE_NAME)
+                            .has_where::<Identity>(|id| id.name == SUN_PIECE_NAME)
                             .has::<FollowCamera>(),
+                        Query::new().has::<Resolution>(),
                     ],
                     update_sun_position,
                 ),
             ),
+            (
+                EVENT_UPDATE,
+                System::new(
+                    vec![
+                        Query::new()
+                            .has_where::<Identity>(|id| id.name == GROUND_PIECE_NAME)
+                            .has::<FollowCamera>()
+                            .has::<TerminalRenderer>(),
+                        Query::new()
+                            .has_where::<TerminalCamera>(|cam| cam.is_main)
+                            .has::<TerminalTransform>(),
+                        Query::new().has::<Pit>().has::<TerminalTransform>(),
+                    ],
+                    show_pits,
+                ),
+            ),
         ]
     }
 }
@@ -128,11 +159,42 @@ fn move_skyline(results: Vec<QueryResultList>, _: GameCommandsArg) {
     }
 }
 
+/// Leaves the ground dark wherever there's a pit under it.
+fn show_pits(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [ground_piece_results, main_cam_results, pit_results, ..] = &results[..] {
+        let main_cam_x = main_cam_results.get_only::<TerminalTransform>().coords.x();
+
+        for ground_piece_result in ground_piece_results {
+            let x = main_cam_x
+                + ground_piece_result
+                    .components()
+                    .get::<FollowCamera>()
+                    .base_position
+                    .x();
+            let is_over_pit = pit_results.iter().any(|pit_result| {
+                pit_result.components().get::<Pit>().is_under(
+                    pit_result
+                        .components()
+                        .get::<TerminalTransform>()
+                        .coords
+                        .x(),
+                    x,
+                )
+            });
+
+            ground_piece_result
+                .components()
+                .get_mut::<TerminalRenderer>()
+                .background_color = Some(if is_over_pit { PIT_COLOR } else { GROUND_COLOR });
+        }
+    }
+}
+
 fn update_world_time(results: Vec<QueryResultList>, commands: GameCommandsArg) {
     if let [world_time_results, ..] = &results[..] {
         let mut world_time = world_time_results.get_only_mut::<WorldTime>();
 
-        if world_time.advance_time_timer.elapsed_millis() >= ADVANCE_TIME_WAIT_TIME_MILLIS {
+        if world_time.advance_time_timer.elapsed_ticks() >= ADVANCE_TIME_WAIT_TICKS {
             let prev_time_of_day = world_time.time_of_day();
 
             if world_time.current_time == 23 {
@@ -152,9 +214,38 @@ fn update_world_time(results: Vec<QueryResultList>, commands: GameCommandsArg) {
     }
 }
 
-fn turn_on_windows(results: Vec<QueryResultList>, _: GameCommandsArg) {
+fn reset_world_time_queries() -> Vec<Query> {
+    vec![Query::new().has::<WorldTime>(), lit_windows_query()]
+}
+
+fn lit_windows_query() -> Query {
+    Query::new()
+        .has_where::<Identity>(|id| id.name == BUILDING_PIECE_NAME)
+        .has_where::<TerminalRenderer>(|renderer| renderer.display == WINDOW_DISPLAY)
+}
+
+/// Every run starts at the same hour with the windows dark, so the windows roll the same way whenever a seed's played.
+fn reset_world_time(results: Vec<QueryResultList>, _: GameCommandsArg) {
     if let [world_time_results, windows_results, ..] = &results[..] {
+        let mut world_time = world_time_results.get_only_mut::<WorldTime>();
+
+        world_time.current_time = RUN_START_TIME;
+        world_time.advance_time_timer.restart();
+        world_time.window_timer.restart();
+
+        for window_result in windows_results {
+            window_result
+                .components()
+                .get_mut::<TerminalRenderer>()
+                .display = ' ';
+        }
+    }
+}
+
+fn turn_on_windows(results: Vec<QueryResultList>, _: GameCommandsArg) {
+    if let [world_time_results, windows_results, world_rng_results, ..] = &results[..] {
         let world_time = world_time_results.get_only::<WorldTime>();
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
 
         let num_windows_to_turn_on = match world_time.time_of_day() {
             TimeOfDay::Dusk => windows_results.len() / 5,
@@ -165,9 +256,11 @@ fn turn_on_windows(results: Vec<QueryResultList>, _: GameCommandsArg) {
         let mut picked_window_indices: HashSet<usize> = HashSet::new();
 
         for _ in 0..num_windows_to_turn_on {
-            if let Some(picked_window_index) =
-                pick_window_index(windows_results.len(), &picked_window_indices)
-            {
+            if let Some(picked_window_index) = pick_window_index(
+                &mut *world_rng,
+                windows_results.len(),
+                &picked_window_indices,
+            ) {
                 picked_window_indices.insert(picked_window_index);
 
                 windows_results[picked_window_index]
@@ -180,19 +273,12 @@ fn turn_on_windows(results: Vec<QueryResultList>, _: GameCommandsArg) {
 }
 
 fn turn_off_windows(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [world_time_results, windows_results, ..] = &results[..] {
+    if let [world_time_results, windows_results, world_rng_results, ..] = &results[..] {
         let mut world_time = world_time_results.get_only_mut::<WorldTime>();
-        let transition_timer = world_time
-            .color_transition_timers
-            .get_mut(WINDOW_COLOR_TRANSITION_TIMER_NAME)
-            .unwrap();
-
-        if !transition_timer.is_running() {
-            transition_timer.restart();
-        }
+        let mut world_rng = world_rng_results.get_only_mut::<WorldRng>();
 
-        if transition_timer.elapsed_millis() >= WINDOW_TURN_OFF_TIME_MILLIS {
-            transition_timer.restart();
+        if world_time.window_timer.elapsed_ticks() >= WINDOW_TURN_OFF_TICKS {
+            world_time.window_timer.restart();
 
             let mut num_windows_to_turn_off = windows_results.len() / 3;
             if num_windows_to_turn_off == 0 && !windows_results.is_empty() {
@@ -203,9 +289,11 @@ fn turn_off_windows(results: Vec<QueryResultList>, _: GameCommandsArg) {
 
             if world_time.is_light() {
                 for _ in 0..num_windows_to_turn_off {
-                    if let Some(picked_window_index) =
-                        pick_window_index(windows_results.len(), &picked_window_indices)
-                    {
+                    if let Some(picked_window_index) = pick_window_index(
+                        &mut *world_rng,
+                        windows_results.len(),
+                        &picked_window_indices,
+                    ) {
                         picked_window_indices.insert(picked_window_index);
 
                         windows_results[picked_window_index]
@@ -220,6 +308,7 @@ fn turn_off_windows(results: Vec<QueryResultList>, _: GameCommandsArg) {
 }
 
 fn pick_window_index(
+    rng: &mut impl Rng,
     window_collection_len: usize,
     unavailable_indices: &HashSet<usize>,
 ) -> Option<usize> {
@@ -227,7 +316,7 @@ fn pick_window_index(
         return None;
     }
 
-    let mut index = thread_rng().gen_range(0..window_collection_len);
+    let mut index = rng.gen_range(0..window_collection_len);
 
     while unavailable_indices.contains(&index) {
         if index + 1 >= window_collection_len {
@@ -237,7 +326,7 @@ fn pick_window_index(
         }
     }
 
-    return Some(index);
+    Some(index)
 }
 
 fn update_world_colors_from_time(results: Vec<QueryResultList>, _: GameCommandsArg) {
@@ -245,22 +334,26 @@ fn update_world_colors_from_time(results: Vec<QueryResultList>, _: GameCommandsA
         &results[..]
     {
         let mut world_time = world_time_results.get_only_mut::<WorldTime>();
-        let mut terminal_renderer_state =
-            terminal_renderer_state_results.get_only_mut::<TerminalRendererState>();
 
         let time_of_day = world_time.time_of_day();
 
-        let current_sky_color_option = terminal_renderer_state.options.default_background_color;
         let target_sky_color_option = get_color(&SKY_COLORS, &time_of_day);
 
-        terminal_renderer_state.options.default_background_color = blend_color_to_target(
-            &current_sky_color_option,
-            target_sky_color_option,
-            world_time
-                .color_transition_timers
-                .get_mut(SKY_COLOR_TRANSITION_TIMER_NAME)
-                .unwrap(),
-        );
+        // There's no renderer state when the game runs without a terminal.
+        if let Some(mut terminal_renderer_state) =
+            terminal_renderer_state_results.try_get_only_mut::<TerminalRendererState>()
+        {
+            let current_sky_color_option = terminal_renderer_state.options.default_background_color;
+
+            terminal_renderer_state.options.default_background_color = blend_color_to_target(
+                &current_sky_color_option,
+                target_sky_color_option,
+                world_time
+                    .color_transition_timers
+                    .get_mut(SKY_COLOR_TRANSITION_TIMER_NAME)
+                    .unwrap(),
+            );
+        }
 
         for star_result in stars_results {
             let mut renderer = star_result.components().get_mut::<TerminalRenderer>();
@@ -344,13 +437,16 @@ fn blend_color_to_target(
 }
 
 fn update_sun_position(results: Vec<QueryResultList>, _: GameCommandsArg) {
-    if let [world_time_results, sun_results, sun_pieces_results, ..] = &results[..] {
+    if let [world_time_results, sun_results, sun_pieces_results, resolution_results, ..] =
+        &results[..]
+    {
         let world_time = world_time_results.get_only::<WorldTime>();
+        let resolution = resolution_results.get_only::<Resolution>();
         let mut sun_follow_cam = sun_results.get_only_mut::<FollowCamera>();
 
         sun_follow_cam.offset = IntCoords2d::new(
-            get_sun_x(world_time.current_time),
-            get_sun_y(world_time.current_time),
+            get_sun_x(&resolution, world_time.current_time),
+            get_sun_y(&resolution, world_time.current_time),
         );
 
         for i in 0..sun_pieces_results.len() {
@@ -363,24 +459,25 @@ fn update_sun_position(results: Vec<QueryResultList>, _: GameCommandsArg) {
     }
 }
 
-fn get_sun_x(current_time: u8) -> i64 {
+fn get_sun_x(resolution: &Resolution, current_time: u8) -> i64 {
     if current_time <= SUNRISE_TIME || current_time > SUNSET_TIME {
         -100
     } else {
         f64::round(
-            SCREEN_WIDTH as f64
+            resolution.width() as f64
                 * ((current_time as f64 - SUNRISE_TIME as f64)
                     / (SUNSET_TIME as f64 - SUNRISE_TIME as f64)),
         ) as i64
     }
 }
 
-fn get_sun_y(current_time: u8) -> i64 {
+fn get_sun_y(resolution: &Resolution, current_time: u8) -> i64 {
     if current_time <= SUNRISE_TIME || current_time > SUNSET_TIME {
         -100
     } else {
         f64::round(
-            ((SCREEN_HEIGHT as f64 - 2.0) / (NOON_TIME as f64 - SUNRISE_TIME as f64).powf(2.0))
+            ((resolution.height() as f64 - 2.0)
+                / (NOON_TIME as f64 - SUNRISE_TIME as f64).powf(2.0))
                 * (current_time as f64 - NOON_TIME as f64).powf(2.0)
                 + 1.0,
         ) as i64
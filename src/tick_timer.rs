@@ -0,0 +1,59 @@
+/// A way to track the passage of game time. Unlike a Thomas `Timer`, which measures real time, a `TickTimer` counts
+/// the ticks of the game's fixed-timestep clock. The clock ticks `TICKS_PER_SECOND` times a second however many frames
+/// are drawn, and `TickTimer`s only advance while the game's being played, so anything driven by one plays out the
+/// same way no matter how long each frame really took.
+#[derive(Clone, Debug)]
+pub struct TickTimer {
+    elapsed_ticks: u64,
+    is_running: bool,
+}
+impl TickTimer {
+    /// Creates a new `TickTimer` instance that isn't started.
+    pub fn new() -> Self {
+        Self {
+            elapsed_ticks: 0,
+            is_running: false,
+        }
+    }
+
+    /// Creates a new `TickTimer` and starts it.
+    pub fn start_new() -> Self {
+        Self {
+            elapsed_ticks: 0,
+            is_running: true,
+        }
+    }
+
+    /// Resets the timer such that its elapsed ticks at the moment of this call would be 0. The timer continues to
+    /// run after this call.
+    pub fn restart(&mut self) {
+        self.elapsed_ticks = 0;
+        self.is_running = true;
+    }
+
+    /// Stops the timer. Any future calls to `elapsed_ticks` will give 0.
+    pub fn stop(&mut self) {
+        self.elapsed_ticks = 0;
+        self.is_running = false;
+    }
+
+    /// Advances the timer by one tick of the game clock.
+    pub fn tick(&mut self) {
+        if self.is_running {
+            self.elapsed_ticks += 1;
+        }
+    }
+
+    pub fn elapsed_ticks(&self) -> u64 {
+        self.elapsed_ticks
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.is_running
+    }
+}
+impl Default for TickTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,226 @@
+use std::{fmt, str::FromStr};
+
+use crate::{components::PickupKind, GameConfig, PICKUP_SPAWN_WEIGHTS};
+
+/// How much longer power-ups last with each level of the upgrade, as a percent of how long they last without it.
+pub const LONGER_POWER_UPS_PERCENT: u64 = 25;
+/// How much more likely extra lives are to be the pickup that appears with each level of the upgrade, as spawn weight.
+pub const LIKELIER_EXTRA_LIVES_WEIGHT: u32 = 2;
+
+/// A permanent upgrade that can be bought in the shop with the coins in the wallet.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Upgrade {
+    /// Starts every run with one more life, and raises the most lives the player can have by one.
+    ExtraLife,
+    /// Lets the player jump once more in the air before they land.
+    ExtraAirJump,
+    LongerPowerUps,
+    /// Makes extra lives more likely to be the pickup that appears.
+    LikelierExtraLives,
+}
+impl Upgrade {
+    pub const ALL: [Upgrade; 4] = [
+        Upgrade::ExtraLife,
+        Upgrade::ExtraAirJump,
+        Upgrade::LongerPowerUps,
+        Upgrade::LikelierExtraLives,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::ExtraLife => "extra_life",
+            Upgrade::ExtraAirJump => "extra_air_jump",
+            Upgrade::LongerPowerUps => "longer_power_ups",
+            Upgrade::LikelierExtraLives => "likelier_extra_lives",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Upgrade::ExtraLife => "Extra life",
+            Upgrade::ExtraAirJump => "Extra air jump",
+            Upgrade::LongerPowerUps => "Longer power-ups",
+            Upgrade::LikelierExtraLives => "More extra lives",
+        }
+    }
+
+    /// How many times the upgrade can be bought.
+    pub fn max_level(&self) -> u8 {
+        match self {
+            Upgrade::ExtraLife => 2,
+            Upgrade::ExtraAirJump => 1,
+            Upgrade::LongerPowerUps => 3,
+            Upgrade::LikelierExtraLives => 3,
+        }
+    }
+
+    /// How many coins it costs to raise the upgrade from `level` to the level after it. Each level costs more than the
+    /// last.
+    pub fn cost(&self, level: u8) -> u64 {
+        let base_cost = match self {
+            Upgrade::ExtraLife => 150,
+            Upgrade::ExtraAirJump => 400,
+            Upgrade::LongerPowerUps => 60,
+            Upgrade::LikelierExtraLives => 80,
+        };
+
+        base_cost * (level as u64 + 1)
+    }
+
+    fn index(&self) -> usize {
+        Upgrade::ALL
+            .iter()
+            .position(|upgrade| upgrade == self)
+            .expect("Every upgrade is in the list of all of them.")
+    }
+}
+impl fmt::Display for Upgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+impl FromStr for Upgrade {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Upgrade::ALL
+            .into_iter()
+            .find(|upgrade| upgrade.name() == s)
+            .ok_or_else(|| format!("'{}' isn't an upgrade", s))
+    }
+}
+
+/// How many times each upgrade has been bought, and what that does to the game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpgradeLevels {
+    levels: [u8; Upgrade::ALL.len()],
+}
+impl UpgradeLevels {
+    pub fn level(&self, upgrade: Upgrade) -> u8 {
+        self.levels[upgrade.index()]
+    }
+
+    /// Sets the upgrade's level, up to the most it can be.
+    pub fn set_level(&mut self, upgrade: Upgrade, level: u8) {
+        self.levels[upgrade.index()] = level.min(upgrade.max_level());
+    }
+
+    pub fn is_maxed(&self, upgrade: Upgrade) -> bool {
+        self.level(upgrade) >= upgrade.max_level()
+    }
+
+    /// How many coins the next level of the upgrade costs. There isn't a next level once it's maxed out.
+    pub fn next_cost(&self, upgrade: Upgrade) -> Option<u64> {
+        if self.is_maxed(upgrade) {
+            None
+        } else {
+            Some(upgrade.cost(self.level(upgrade)))
+        }
+    }
+
+    pub fn max_lives(&self, config: &GameConfig) -> u8 {
+        config
+            .max_lives
+            .saturating_add(self.level(Upgrade::ExtraLife))
+    }
+
+    pub fn max_air_jumps(&self, config: &GameConfig) -> u8 {
+        config
+            .max_air_jumps
+            .saturating_add(self.level(Upgrade::ExtraAirJump))
+    }
+
+    pub fn power_up_ticks(&self, config: &GameConfig) -> u64 {
+        config.power_up_ticks
+            * (100 + LONGER_POWER_UPS_PERCENT * self.level(Upgrade::LongerPowerUps) as u64)
+            / 100
+    }
+
+    pub fn pickup_spawn_weights(&self) -> Vec<(PickupKind, u32)> {
+        PICKUP_SPAWN_WEIGHTS
+            .iter()
+            .map(|&(kind, weight)| {
+                if kind == PickupKind::ExtraLife {
+                    (
+                        kind,
+                        weight
+                            + LIKELIER_EXTRA_LIVES_WEIGHT
+                                * self.level(Upgrade::LikelierExtraLives) as u32,
+                    )
+                } else {
+                    (kind, weight)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Difficulty;
+
+    #[test]
+    fn each_level_costs_more_than_the_last_until_the_upgrade_is_maxed() {
+        for upgrade in Upgrade::ALL {
+            let mut levels = UpgradeLevels::default();
+            let mut last_cost = 0;
+
+            for level in 0..upgrade.max_level() {
+                let cost = levels.next_cost(upgrade).expect("isn't maxed yet");
+                assert!(cost > last_cost, "{}", upgrade);
+
+                last_cost = cost;
+                levels.set_level(upgrade, level + 1);
+            }
+
+            assert!(levels.is_maxed(upgrade));
+            assert_eq!(levels.next_cost(upgrade), None);
+        }
+    }
+
+    #[test]
+    fn levels_cannot_go_past_the_most_they_can_be() {
+        let mut levels = UpgradeLevels::default();
+
+        levels.set_level(Upgrade::ExtraAirJump, u8::MAX);
+
+        assert_eq!(
+            levels.level(Upgrade::ExtraAirJump),
+            Upgrade::ExtraAirJump.max_level()
+        );
+    }
+
+    #[test]
+    fn upgrades_change_the_game_a_level_at_a_time() {
+        let config = Difficulty::Normal.config();
+        let mut levels = UpgradeLevels::default();
+
+        assert_eq!(levels.max_lives(&config), config.max_lives);
+        assert_eq!(levels.max_air_jumps(&config), config.max_air_jumps);
+        assert_eq!(levels.power_up_ticks(&config), config.power_up_ticks);
+        assert_eq!(levels.pickup_spawn_weights(), PICKUP_SPAWN_WEIGHTS.to_vec());
+
+        for upgrade in Upgrade::ALL {
+            levels.set_level(upgrade, 1);
+        }
+
+        assert_eq!(levels.max_lives(&config), config.max_lives + 1);
+        assert_eq!(levels.max_air_jumps(&config), config.max_air_jumps + 1);
+        assert_eq!(
+            levels.power_up_ticks(&config),
+            config.power_up_ticks * (100 + LONGER_POWER_UPS_PERCENT) / 100
+        );
+        for (&(kind, weight), (upgraded_kind, upgraded_weight)) in PICKUP_SPAWN_WEIGHTS
+            .iter()
+            .zip(levels.pickup_spawn_weights())
+        {
+            assert_eq!(kind, upgraded_kind);
+            if kind == PickupKind::ExtraLife {
+                assert_eq!(upgraded_weight, weight + LIKELIER_EXTRA_LIVES_WEIGHT);
+            } else {
+                assert_eq!(upgraded_weight, weight);
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{data_file_path, Upgrade, UpgradeLevels};
+
+/// The coins collected over every run and the upgrades bought with them, kept between sessions.
+///
+/// Wallets are saved as `key=value` lines: a `coins` line, and a line for each upgrade giving how many times it's been
+/// bought.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Wallet {
+    pub coins: u64,
+    pub upgrades: UpgradeLevels,
+}
+impl Wallet {
+    /// Where the wallet is kept when none is given.
+    pub fn default_path() -> Option<PathBuf> {
+        data_file_path("wallet.txt")
+    }
+
+    /// Reads the wallet kept at `path`. A missing file means nothing has been collected yet. A file that can't be read
+    /// is an error rather than an empty wallet, so it's never saved over.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                Self::parse(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Reads a wallet from `key=value` lines. Blank lines and lines starting with `#` are skipped.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut wallet = Self::default();
+
+        for (line_index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                format!(
+                    "line {}: expected 'key=value', got '{}'",
+                    line_index + 1,
+                    line
+                )
+            })?;
+
+            wallet
+                .set(key.trim(), value.trim())
+                .map_err(|e| format!("line {}: {}", line_index + 1, e))?;
+        }
+
+        Ok(wallet)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, self.to_string())
+    }
+
+    /// Every value in the wallet by name, in the order they're saved.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![("coins", self.coins.to_string())];
+
+        for upgrade in Upgrade::ALL {
+            entries.push((upgrade.name(), self.upgrades.level(upgrade).to_string()));
+        }
+
+        entries
+    }
+
+    /// Sets the value called `key` from its text form.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let parse_error = |_| format!("invalid value for {}: '{}'", key, value);
+
+        if key == "coins" {
+            self.coins = value.parse().map_err(parse_error)?;
+        } else {
+            let upgrade: Upgrade = key.parse()?;
+            self.upgrades
+                .set_level(upgrade, value.parse().map_err(parse_error)?);
+        }
+
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, coins: u64) {
+        self.coins = self.coins.saturating_add(coins);
+    }
+
+    /// Buys the next level of the upgrade if there's one to buy and enough coins to pay for it. Gives whether it was
+    /// bought.
+    pub fn buy(&mut self, upgrade: Upgrade) -> bool {
+        match self.upgrades.next_cost(upgrade) {
+            Some(cost) if cost <= self.coins => {
+                self.coins -= cost;
+                self.upgrades
+                    .set_level(upgrade, self.upgrades.level(upgrade) + 1);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+impl fmt::Display for Wallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# runner wallet")?;
+
+        for (key, value) in self.entries() {
+            writeln!(f, "{}={}", key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn files_that_cant_be_read_are_an_error_rather_than_an_empty_wallet() {
+        assert_eq!(
+            Wallet::load(env::temp_dir().join("runner-test-wallet-that-isnt-there.txt")).ok(),
+            Some(Wallet::default())
+        );
+        assert!(Wallet::load(env::temp_dir()).is_err());
+    }
+
+    #[test]
+    fn wallets_with_values_that_cant_be_read_are_an_error() {
+        assert_eq!(
+            Wallet::parse("# runner wallet\ncoins=lots\n"),
+            Err(String::from("line 2: invalid value for coins: 'lots'"))
+        );
+        assert!(Wallet::parse("coins=5\nextra_life=1\nwings=2\n").is_err());
+        assert!(Wallet::parse("coins 5\n").is_err());
+    }
+
+    #[test]
+    fn upgrades_are_only_bought_with_enough_coins_to_pay_for_them() {
+        let cost = Upgrade::ExtraLife.cost(0);
+        let mut wallet = Wallet {
+            coins: cost * 2,
+            ..Wallet::default()
+        };
+
+        assert!(wallet.buy(Upgrade::ExtraLife));
+        assert_eq!(wallet.coins, cost);
+        assert_eq!(wallet.upgrades.level(Upgrade::ExtraLife), 1);
+
+        // The second level costs more than the first.
+        assert!(!wallet.buy(Upgrade::ExtraLife));
+        assert_eq!(wallet.coins, cost);
+        assert_eq!(wallet.upgrades.level(Upgrade::ExtraLife), 1);
+
+        assert_eq!(Wallet::parse(&wallet.to_string()), Ok(wallet));
+    }
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::{quiet_config, simulation_options};
+use runner::{components::Action, Bonus, Simulation};
+
+#[test]
+fn landing_a_jump_made_in_the_air_scores_a_bonus() {
+    let mut simulation = Simulation::new(simulation_options(quiet_config()));
+    simulation.step([Action::AnyKey]);
+
+    while !simulation.report().is_on_ground {
+        simulation.step(vec![]);
+    }
+
+    simulation.step([Action::Jump, Action::AnyKey]);
+    simulation.step(vec![]);
+    assert!(!simulation.report().is_on_ground);
+
+    simulation.step([Action::Jump, Action::AnyKey]);
+    while !simulation.report().is_on_ground {
+        assert_eq!(simulation.report().bonus_score, 0);
+
+        simulation.step(vec![]);
+    }
+    simulation.step(vec![]);
+
+    let report = simulation.report();
+    assert_eq!(report.bonus_score, Bonus::CleanDoubleJump.points());
+    assert_eq!(report.combo_multiplier, 2);
+    assert_eq!(report.stats.best_combo, 1);
+}
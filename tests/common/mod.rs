@@ -0,0 +1,30 @@
+// Each test file is its own crate and only uses some of these.
+#![allow(dead_code)]
+
+use runner::{Difficulty, GameConfig, Resolution, SimulationOptions, Wallet};
+
+/// The normal config with nothing coming at the player and nothing to pick up, for tests that spawn only what they
+/// turn back on.
+pub fn quiet_config() -> GameConfig {
+    let mut config = Difficulty::Normal.config();
+    config.obstacle_wait_ticks_min = 10_000;
+    config.obstacle_wait_ticks_max = 10_000;
+    config.pickup_chance = 0;
+    config.coin_chance = 0;
+
+    config
+}
+
+/// Options for a headless run at normal difficulty with `config` from a fixed seed, on a small screen with an empty
+/// wallet.
+pub fn simulation_options(config: GameConfig) -> SimulationOptions {
+    SimulationOptions {
+        max_frame_rate: 0,
+        seed: 1,
+        is_seed_fixed: true,
+        difficulty: Difficulty::Normal,
+        config,
+        resolution: Resolution::new(40, 10).unwrap(),
+        wallet: Wallet::default(),
+    }
+}
@@ -0,0 +1,175 @@
+use std::{env, fs, path::PathBuf};
+
+use runner::{ConfigError, Difficulty, GameConfig, GameMode};
+
+/// Writes `contents` to a config file of its own in the temp directory.
+fn config_file(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("runner-test-config-{}.txt", name));
+    fs::write(&path, contents).unwrap();
+
+    path
+}
+
+/// The problems found applying `contents` on top of the default config.
+fn problems_with(contents: &str) -> Vec<String> {
+    match GameConfig::default().apply(contents) {
+        Err(ConfigError::Invalid(problems)) => problems,
+        result => panic!("expected the config to be invalid, got {:?}", result),
+    }
+}
+
+#[test]
+fn config_files_apply_their_settings_and_skip_comments_and_blank_lines() {
+    let path = config_file(
+        "settings",
+        "# Floatier jumps.\n\ngravity = 10\n  # Indented comments too.\njump_force=40\n",
+    );
+    let mut config = Difficulty::Hard.config();
+
+    config.apply_file(&path).unwrap();
+
+    assert_eq!(
+        config,
+        GameConfig {
+            gravity: 10,
+            jump_force: 40,
+            ..Difficulty::Hard.config()
+        }
+    );
+}
+
+#[test]
+fn unknown_keys_and_bad_values_are_reported_with_their_line() {
+    for (name, contents) in [
+        ("unknown-key", "gravity = 10\nwarp_speed = 9\n"),
+        ("bad-value", "gravity = 10\njump_force = high\n"),
+        ("negative-value", "gravity = 10\njump_force = -5\n"),
+        ("no-equals", "gravity = 10\njump_force 40\n"),
+    ] {
+        let path = config_file(name, contents);
+        let mut config = GameConfig::default();
+
+        match config.apply_file(&path) {
+            Err(ConfigError::Parse { line_number, .. }) => assert_eq!(line_number, 2, "{name}"),
+            result => panic!("expected {name} to fail to parse, got {:?}", result),
+        }
+        // Nothing's applied from a file with anything wrong with it.
+        assert_eq!(config, GameConfig::default(), "{name}");
+    }
+}
+
+#[test]
+fn missing_config_files_cant_be_read() {
+    let mut config = GameConfig::default();
+
+    assert!(matches!(
+        config.apply_file(env::temp_dir().join("runner-test-config-that-isnt-there.txt")),
+        Err(ConfigError::Io(_))
+    ));
+}
+
+#[test]
+fn every_difficulty_is_valid() {
+    for difficulty in Difficulty::ALL {
+        assert!(
+            difficulty.config().validate().is_ok(),
+            "{}",
+            difficulty.name()
+        );
+    }
+}
+
+#[test]
+fn settings_that_make_for_an_unplayable_game_are_invalid() {
+    for (contents, problem) in [
+        ("gravity = 0", "gravity must be greater than 0"),
+        (
+            "jump_force = 0\nshort_jump_force = 0",
+            "jump_force must be greater than 0",
+        ),
+        (
+            "short_jump_force = 51",
+            "short_jump_force can't be greater than jump_force",
+        ),
+        ("max_air_jumps = 6", "max_air_jumps can't be greater than 5"),
+        ("slide_ticks = 0", "slide_ticks must be greater than 0"),
+        ("max_lives = 0", "max_lives must be greater than 0"),
+        (
+            "camera_scroll_wait_ticks = 0",
+            "camera_scroll_wait_ticks must be greater than 0",
+        ),
+        (
+            "obstacle_wait_ticks_min = 0",
+            "obstacle_wait_ticks_min must be greater than 0",
+        ),
+        (
+            "obstacle_wait_ticks_min = 91",
+            "obstacle_wait_ticks_min can't be greater than obstacle_wait_ticks_max",
+        ),
+        (
+            "air_obstacle_move_interval_ticks_min = 0",
+            "air_obstacle_move_interval_ticks_min must be greater than 0",
+        ),
+        (
+            "air_obstacle_move_interval_ticks_min = 25",
+            "air_obstacle_move_interval_ticks_min can't be greater than air_obstacle_move_interval_ticks_max",
+        ),
+        ("pickup_wait_ticks = 0", "pickup_wait_ticks must be greater than 0"),
+        (
+            "pickup_chance = 101",
+            "pickup_chance is a percentage, so it can't be greater than 100",
+        ),
+        ("power_up_ticks = 0", "power_up_ticks must be greater than 0"),
+        ("coin_wait_ticks = 0", "coin_wait_ticks must be greater than 0"),
+        (
+            "coin_chance = 101",
+            "coin_chance is a percentage, so it can't be greater than 100",
+        ),
+        ("enemy_wait_ticks = 0", "enemy_wait_ticks must be greater than 0"),
+        (
+            "enemy_chance = 101",
+            "enemy_chance is a percentage, so it can't be greater than 100",
+        ),
+        ("victory_score = 0", "victory_score must be greater than 0"),
+    ] {
+        assert_eq!(problems_with(contents), [problem], "{contents}");
+    }
+}
+
+#[test]
+fn every_problem_with_a_config_is_reported_at_once() {
+    assert_eq!(
+        problems_with("gravity = 0\nmax_lives = 0\n"),
+        [
+            "gravity must be greater than 0",
+            "max_lives must be greater than 0"
+        ]
+    );
+}
+
+#[test]
+fn only_a_difficultys_own_config_counts_as_its_own_in_either_mode() {
+    let mut config = Difficulty::Hard.config();
+    assert!(Difficulty::Hard.is_own_config(&config));
+    assert!(!Difficulty::Normal.is_own_config(&config));
+
+    config.mode = GameMode::Endless;
+    assert!(Difficulty::Hard.is_own_config(&config));
+
+    config.gravity += 1;
+    assert!(!Difficulty::Hard.is_own_config(&config));
+}
+
+#[test]
+fn the_longest_waits_a_config_can_have_still_scale() {
+    let mut config = GameConfig::default();
+    config
+        .apply(&format!(
+            "camera_scroll_wait_ticks = {max}\nobstacle_wait_ticks_min = {max}\nobstacle_wait_ticks_max = {max}\n",
+            max = u64::MAX
+        ))
+        .unwrap();
+
+    assert!(config.camera_scroll_wait_ticks(u64::MAX) > 0);
+    assert!(!config.obstacle_wait_ticks_range(u64::MAX).is_empty());
+}
@@ -0,0 +1,28 @@
+use runner::components::{Action, Controls};
+
+#[test]
+fn a_key_tapped_between_ticks_is_pressed_on_the_next_tick() {
+    let mut controls = Controls::new();
+
+    controls.hold([Action::Jump]);
+    controls.hold([]);
+    controls.advance();
+
+    assert!(controls.is_action_down(&Action::Jump));
+
+    controls.advance();
+
+    assert!(!controls.is_action_pressed(&Action::Jump));
+}
+
+#[test]
+fn a_key_held_over_several_ticks_only_goes_down_on_the_first() {
+    let mut controls = Controls::new();
+
+    controls.hold([Action::Slide]);
+    controls.advance();
+    controls.advance();
+
+    assert!(controls.is_action_pressed(&Action::Slide));
+    assert!(!controls.is_action_down(&Action::Slide));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{quiet_config, simulation_options};
+use runner::{components::Action, Simulation};
+
+#[test]
+fn enemy_projectiles_cost_a_life_unless_they_are_dodged() {
+    let mut config = quiet_config();
+    // Nothing but an enemy, turning up straight away.
+    config.enemy_wait_ticks = 1;
+    config.enemy_chance = 100;
+
+    let start = || {
+        let mut simulation = Simulation::new(simulation_options(config.clone()));
+        simulation.step([Action::AnyKey]);
+
+        simulation
+    };
+
+    // Standing still, the first shot comes straight down the lane the player's running in.
+    let mut simulation = start();
+    for _ in 0..400 {
+        simulation.step(vec![]);
+    }
+
+    assert!(simulation.report().stats.lives_lost > 0);
+
+    let mut simulation = start();
+    let mut plan = vec![];
+    let mut planned_num_obstacles_option = None;
+
+    for _ in 0..400 {
+        let course = simulation.course();
+
+        if plan.is_empty() || planned_num_obstacles_option != Some(course.obstacles.len()) {
+            plan = course
+                .plan_inputs(&config)
+                .expect("every shot can be cleared");
+            plan.reverse();
+            planned_num_obstacles_option = Some(course.obstacles.len());
+        }
+
+        simulation.step(plan.pop().unwrap_or_default().actions());
+    }
+
+    let stats = simulation.report().stats;
+
+    assert!(stats.projectiles_dodged > 0);
+    assert_eq!(stats.lives_lost, 0);
+}
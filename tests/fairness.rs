@@ -0,0 +1,540 @@
+mod common;
+
+use common::{quiet_config, simulation_options};
+use runner::{
+    components::{Action, GameState, PickupKind},
+    make_obstacle_shape, Course, CourseObstacle, CoursePit, Difficulty, FlightPath, LifeLostTo,
+    PatternObstacle, Resolution, RunnerInput, RunnerState, Simulation, SimulationOptions,
+    AIR_OBSTACLE_HEIGHT, OBSTACLE_PATTERNS, PLAYER_X_OFFSET, SINGLE_CELL_SHAPE,
+};
+
+fn start_simulation(difficulty: Difficulty, seed: u64) -> Simulation {
+    let mut simulation = Simulation::new(SimulationOptions {
+        seed,
+        difficulty,
+        ..simulation_options(difficulty.config())
+    });
+
+    simulation.step([Action::AnyKey]);
+
+    simulation
+}
+
+fn still_obstacle(x_from_player: i64, height: i64) -> CourseObstacle {
+    CourseObstacle {
+        x: PLAYER_X_OFFSET + x_from_player,
+        height,
+        move_interval_option: None,
+        move_ticks: 0,
+        flight_path: FlightPath::Level,
+        flight_ticks: 0,
+        drift: 0,
+    }
+}
+
+fn course_from_ground(obstacles: Vec<CourseObstacle>) -> Course {
+    Course {
+        runner: RunnerState::on_ground(),
+        camera_x: 0,
+        camera_move_ticks: 0,
+        score: 0,
+        obstacles,
+        pits: vec![],
+    }
+}
+
+#[test]
+fn runner_state_follows_the_players_jump_arc() {
+    let config = Difficulty::Normal.config();
+    let mut simulation = start_simulation(Difficulty::Normal, 1);
+    let mut runner = simulation.course().runner;
+
+    // A full jump with a short hop on top, then a jump cut short partway up.
+    for frame in 0..70 {
+        let input = match frame {
+            2..=7 | 9 | 40..=42 => RunnerInput::Jump,
+            _ => RunnerInput::Nothing,
+        };
+
+        simulation.step(input.actions());
+        runner = runner.step(&config, input, false);
+
+        assert_eq!(simulation.report().height, runner.height, "frame {frame}");
+    }
+}
+
+#[test]
+fn holding_jump_jumps_higher() {
+    let peak_height = |hold_frames| {
+        let mut simulation = start_simulation(Difficulty::Normal, 1);
+
+        (0..40)
+            .map(|frame| {
+                simulation.step(if frame < hold_frames {
+                    vec![Action::Jump]
+                } else {
+                    vec![]
+                });
+                simulation.report().height
+            })
+            .max()
+            .unwrap()
+    };
+
+    assert!(peak_height(1) > 0);
+    assert!(peak_height(1) < peak_height(30));
+}
+
+#[test]
+fn runner_state_follows_the_players_slides() {
+    let config = Difficulty::Normal.config();
+    let mut simulation = start_simulation(Difficulty::Normal, 1);
+    let mut runner = simulation.course().runner;
+
+    // A slide that runs out, one that's held down and then kept going by pressing slide again, and one that's cut
+    // short by a jump.
+    for frame in 0..80 {
+        let input = match frame {
+            2 | 30 | 31 | 40 | 60 => RunnerInput::Slide,
+            65 => RunnerInput::Jump,
+            _ => RunnerInput::Nothing,
+        };
+
+        simulation.step(input.actions());
+        runner = runner.step(&config, input, false);
+
+        assert_eq!(
+            simulation.report().is_sliding,
+            runner.motion.is_sliding,
+            "frame {frame}"
+        );
+        assert_eq!(simulation.report().height, runner.height, "frame {frame}");
+    }
+}
+
+#[test]
+fn lone_obstacles_and_hurdles_can_be_cleared() {
+    let config = Difficulty::Normal.config();
+
+    assert!(course_from_ground(vec![still_obstacle(20, 0)]).is_clearable(&config));
+    assert!(
+        course_from_ground(vec![still_obstacle(20, AIR_OBSTACLE_HEIGHT)]).is_clearable(&config)
+    );
+    assert!(
+        course_from_ground(vec![still_obstacle(20, 0), still_obstacle(20, 1)])
+            .is_clearable(&config)
+    );
+}
+
+#[test]
+fn long_low_obstacles_are_slid_under() {
+    let config = Difficulty::Normal.config();
+    let ceiling = |height| (0..40).map(|i| still_obstacle(20 + i, height)).collect();
+
+    let plan = course_from_ground(ceiling(1))
+        .plan_inputs(&config)
+        .expect("the player can slide under it");
+
+    assert!(plan.contains(&RunnerInput::Slide));
+    assert!(!plan.contains(&RunnerInput::Jump));
+
+    // The player's head runs under anything higher up, but their feet can't get under anything on the ground.
+    assert!(course_from_ground(ceiling(2))
+        .plan_inputs(&config)
+        .expect("the player can run under it")
+        .iter()
+        .all(|input| *input == RunnerInput::Nothing));
+    assert!(!course_from_ground(ceiling(0)).is_clearable(&config));
+}
+
+#[test]
+fn walls_wider_than_a_double_jump_cannot_be_cleared() {
+    let config = Difficulty::Normal.config();
+    let wall = |width| (0..width).map(|i| still_obstacle(20 + i, 0)).collect();
+
+    assert!(course_from_ground(wall(6)).is_clearable(&config));
+    assert!(!course_from_ground(wall(20)).is_clearable(&config));
+}
+
+#[test]
+fn stacks_taller_than_a_double_jump_cannot_be_cleared() {
+    let config = Difficulty::Normal.config();
+    let stack = (0..12).map(|height| still_obstacle(20, height)).collect();
+
+    assert!(!course_from_ground(stack).is_clearable(&config));
+}
+
+#[test]
+fn moving_obstacles_are_tracked_as_they_close_in() {
+    let config = Difficulty::Normal.config();
+
+    // The player has used up their air jumps and lands 15 frames from now. An obstacle closing in a cell a tick gets
+    // to them just as they land, while the same obstacle standing still gets there long after they could jump again.
+    let mut runner = RunnerState::on_ground();
+    for frame in 0..8 {
+        let input = if frame == 5 {
+            RunnerInput::Nothing
+        } else {
+            RunnerInput::Jump
+        };
+
+        runner = runner.step(&config, input, false);
+    }
+
+    let mut course = course_from_ground(vec![CourseObstacle {
+        move_interval_option: Some(1),
+        ..still_obstacle(20, 0)
+    }]);
+    course.runner = runner;
+
+    assert!(!course.is_clearable(&config));
+
+    course.obstacles[0].move_interval_option = None;
+
+    assert!(course.is_clearable(&config));
+}
+
+#[test]
+fn obstacles_coming_down_at_the_player_are_tracked_as_they_fly_in() {
+    let config = Difficulty::Normal.config();
+
+    for flight_path in [FlightPath::Swoop, FlightPath::Homing, FlightPath::Drop] {
+        let obstacle = CourseObstacle {
+            height: flight_path.spawn_height(),
+            move_interval_option: Some(10),
+            flight_path,
+            ..still_obstacle(30, 0)
+        };
+
+        // Flying along where it spawned, it passes over the player's head.
+        let level_plan = course_from_ground(vec![CourseObstacle {
+            flight_path: FlightPath::Level,
+            ..obstacle
+        }])
+        .plan_inputs(&config)
+        .unwrap();
+
+        assert!(
+            level_plan
+                .iter()
+                .all(|input| *input == RunnerInput::Nothing),
+            "{:?}",
+            flight_path
+        );
+
+        let plan = course_from_ground(vec![obstacle])
+            .plan_inputs(&config)
+            .unwrap();
+
+        assert!(
+            plan.iter().any(|input| *input != RunnerInput::Nothing),
+            "{:?}",
+            flight_path
+        );
+    }
+}
+
+#[test]
+fn pits_wider_than_a_double_jump_cannot_be_cleared() {
+    let config = Difficulty::Normal.config();
+    let pit = |width| Course {
+        pits: vec![CoursePit {
+            x: PLAYER_X_OFFSET + 20,
+            width,
+        }],
+        ..course_from_ground(vec![])
+    };
+
+    assert!(pit(3).is_clearable(&config));
+    assert!(pit(6).is_clearable(&config));
+    assert!(!pit(20).is_clearable(&config));
+}
+
+#[test]
+fn walking_into_a_pit_costs_a_life_and_puts_the_player_back_on_the_ground() {
+    let config = Difficulty::Normal.config();
+    let mut simulation = start_simulation(Difficulty::Normal, 6);
+
+    // Never jumping, the player runs into something within a few screens, and on this seed one of those is a pit they
+    // can still lose a life to.
+    let course = (0..300)
+        .map(|_| {
+            simulation.step(vec![]);
+            (simulation.course(), simulation.report().is_invulnerable)
+        })
+        .find(|(course, is_invulnerable)| {
+            let player_x = course.camera_x + PLAYER_X_OFFSET;
+
+            !is_invulnerable
+                && course
+                    .pits
+                    .iter()
+                    .any(|pit| player_x >= pit.x && player_x < pit.x + pit.width as i64)
+        })
+        .map(|(course, _)| course)
+        .expect("the player gets to a pit");
+    let lives_lost = simulation.report().stats.lives_lost;
+
+    simulation.step(vec![]);
+
+    assert_eq!(
+        simulation.report().height,
+        course
+            .runner
+            .step(&config, RunnerInput::Nothing, true)
+            .height
+    );
+    assert!(simulation.report().height < 0);
+
+    simulation.step(vec![]);
+
+    let report = simulation.report();
+    assert_eq!(report.stats.lives_lost, lives_lost + 1);
+    assert_eq!(
+        report
+            .stats
+            .lost_lives
+            .last()
+            .map(|lost_life| lost_life.cause),
+        Some(LifeLostTo::Pit)
+    );
+    assert_eq!(report.height, 0);
+}
+
+#[test]
+fn lives_cannot_be_lost_again_while_the_player_is_invulnerable() {
+    let config = Difficulty::Normal.config();
+    let mut simulation = start_simulation(Difficulty::Normal, 1);
+    let mut life_lost_frames = vec![];
+
+    // Never jumping, the player runs into one obstacle after another.
+    for frame in 0..400 {
+        let lives_lost = simulation.report().stats.lives_lost;
+
+        simulation.step(vec![]);
+
+        if simulation.report().stats.lives_lost > lives_lost {
+            life_lost_frames.push(frame);
+        }
+    }
+
+    assert!(life_lost_frames.len() > 1);
+    assert!(life_lost_frames
+        .windows(2)
+        .all(|frames| frames[1] - frames[0] >= config.invulnerability_ticks));
+}
+
+#[test]
+fn slow_motion_halves_how_fast_the_run_goes_and_the_multiplier_adds_bonus_points() {
+    let mut config = quiet_config();
+    // Nothing but a pickup on every tick, so a jump runs into plenty of them, and a magnet keeps pulling more in.
+    config.pickup_wait_ticks = 1;
+    config.pickup_chance = 100;
+
+    let mut simulation = Simulation::new(simulation_options(config.clone()));
+    simulation.step([Action::AnyKey]);
+
+    // Jumping over and over, double jumping at the top of each jump.
+    let report = (0..400)
+        .map(|frame| {
+            simulation.step(if frame % 40 < 12 || (14..26).contains(&(frame % 40)) {
+                vec![Action::Jump]
+            } else {
+                vec![]
+            });
+            simulation.report()
+        })
+        .find(|report| {
+            report.power_ups.contains(&PickupKind::SlowMotion)
+                && report.power_ups.contains(&PickupKind::ScoreMultiplier)
+        })
+        .expect("the player picks up slow motion and a score multiplier");
+    assert!(report.score > report.distance_traveled - PLAYER_X_OFFSET as u64);
+
+    let num_frames = 120;
+    for _ in 0..num_frames {
+        simulation.step(vec![]);
+    }
+
+    let distance_covered = simulation.report().distance_traveled - report.distance_traveled;
+    assert!(distance_covered <= num_frames / 2 / config.camera_scroll_wait_ticks + 1);
+    assert!(distance_covered >= num_frames / 2 / config.camera_scroll_wait_ticks - 1);
+}
+
+#[test]
+fn plans_are_empty_when_nothing_is_coming() {
+    let config = Difficulty::Normal.config();
+
+    assert_eq!(
+        course_from_ground(vec![]).plan_inputs(&config),
+        Some(vec![])
+    );
+    assert_eq!(
+        course_from_ground(vec![still_obstacle(-5, 0)]).plan_inputs(&config),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn every_pattern_can_be_cleared_on_its_own_wherever_it_can_be_picked() {
+    let resolution = Resolution::default();
+
+    for difficulty in Difficulty::ALL {
+        let config = difficulty.config();
+
+        for score in [0, config.victory_score] {
+            let move_interval_range = config.air_obstacle_move_interval_range(score);
+
+            for pattern in OBSTACLE_PATTERNS
+                .iter()
+                .filter(|pattern| pattern.weight(config.difficulty_progress_percent(score)) > 0)
+            {
+                for (move_interval, flight_path) in
+                    [*move_interval_range.start(), *move_interval_range.end()]
+                        .into_iter()
+                        .flat_map(|move_interval| {
+                            FlightPath::ALL.map(|flight_path| (move_interval, flight_path))
+                        })
+                {
+                    for is_random_in_air in [false, true] {
+                        let has_moving_obstacles = pattern.obstacles.iter().any(|obstacle| {
+                            matches!(obstacle, PatternObstacle::MovingAir { .. })
+                                || (is_random_in_air
+                                    && matches!(obstacle, PatternObstacle::Random { .. }))
+                        });
+
+                        // Flight paths only make a difference to obstacles that fly.
+                        if !has_moving_obstacles && flight_path != FlightPath::Level {
+                            continue;
+                        }
+
+                        let obstacles = pattern
+                            .obstacles
+                            .iter()
+                            .flat_map(|obstacle| {
+                                let (height, rows, move_interval_option) = match *obstacle {
+                                    PatternObstacle::Ground { height, .. } => {
+                                        (height, SINGLE_CELL_SHAPE, None)
+                                    }
+                                    PatternObstacle::Air { .. } => {
+                                        (AIR_OBSTACLE_HEIGHT, SINGLE_CELL_SHAPE, None)
+                                    }
+                                    PatternObstacle::MovingAir { .. } => (
+                                        flight_path.spawn_height(),
+                                        SINGLE_CELL_SHAPE,
+                                        Some(move_interval),
+                                    ),
+                                    PatternObstacle::Random { .. } if is_random_in_air => (
+                                        flight_path.spawn_height(),
+                                        SINGLE_CELL_SHAPE,
+                                        Some(move_interval),
+                                    ),
+                                    PatternObstacle::Random { .. } => (0, SINGLE_CELL_SHAPE, None),
+                                    PatternObstacle::Shape { height, rows, .. } => {
+                                        (height, rows, None)
+                                    }
+                                    PatternObstacle::Pit { .. } => return vec![],
+                                };
+                                let x = score as i64
+                                    + resolution.width() as i64
+                                    + 1
+                                    + obstacle.x_offset();
+                                let top_height = height + rows.len() as i64 - 1;
+
+                                make_obstacle_shape(rows)
+                                    .iter()
+                                    .filter(|cell| *cell.data())
+                                    .map(|cell| CourseObstacle {
+                                        x: x + cell.location().x(),
+                                        height: top_height - cell.location().y(),
+                                        move_interval_option,
+                                        move_ticks: 0,
+                                        flight_path: if move_interval_option.is_some() {
+                                            flight_path
+                                        } else {
+                                            FlightPath::Level
+                                        },
+                                        flight_ticks: 0,
+                                        drift: 0,
+                                    })
+                                    .collect::<Vec<_>>()
+                            })
+                            .collect();
+
+                        let pits = pattern
+                            .obstacles
+                            .iter()
+                            .filter_map(|obstacle| match *obstacle {
+                                PatternObstacle::Pit { x_offset, width } => Some(CoursePit {
+                                    x: score as i64 + resolution.width() as i64 + 1 + x_offset,
+                                    width,
+                                }),
+                                _ => None,
+                            })
+                            .collect();
+
+                        // The score is how far the camera has scrolled.
+                        let course = Course {
+                            camera_x: score as i64,
+                            score,
+                            pits,
+                            ..course_from_ground(obstacles)
+                        };
+
+                        assert!(
+                            course.is_clearable(&config),
+                            "{} on {} at {} flying {:?}",
+                            pattern.name,
+                            difficulty.name(),
+                            score,
+                            flight_path
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn following_the_plans_never_loses_a_life() {
+    for difficulty in Difficulty::ALL {
+        let config = difficulty.config();
+        let mut simulation = start_simulation(difficulty, 7);
+
+        // The plan only changes once obstacles or pits are spawned or cleaned up, so it's only worked out again then,
+        // or once it's run out.
+        let mut plan = vec![];
+        let mut planned_num_hazards_option = None;
+
+        for _ in 0..900 {
+            let course = simulation.course();
+            let num_hazards = course.obstacles.len() + course.pits.len();
+
+            if plan.is_empty() || planned_num_hazards_option != Some(num_hazards) {
+                plan = course
+                    .plan_inputs(&config)
+                    .expect("every spawn can be cleared");
+                plan.reverse();
+                planned_num_hazards_option = Some(num_hazards);
+            }
+
+            simulation.step(plan.pop().unwrap_or_default().actions());
+        }
+
+        let report = simulation.report();
+
+        assert_eq!(
+            report.game_state,
+            GameState::Playing,
+            "{}",
+            difficulty.name()
+        );
+        assert!(
+            report.stats.ground_obstacles_dodged + report.stats.air_obstacles_dodged > 0,
+            "{}",
+            difficulty.name()
+        );
+        assert_eq!(report.stats.lives_lost, 0, "{}", difficulty.name());
+    }
+}
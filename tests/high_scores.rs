@@ -0,0 +1,36 @@
+mod common;
+
+use common::simulation_options;
+use runner::{
+    components::{Action, GameState},
+    Difficulty, GameConfig, Simulation, SimulationOptions,
+};
+
+/// Plays a run at normal difficulty with `config` without pressing anything until it's lost, and gives where it placed
+/// on the high-score table.
+fn place_of_lost_run(config: GameConfig) -> Option<usize> {
+    let mut simulation = Simulation::new(SimulationOptions {
+        seed: 4,
+        ..simulation_options(config)
+    });
+
+    simulation.step([Action::AnyKey]);
+    while simulation.report().game_state == GameState::Playing {
+        simulation.step([]);
+    }
+
+    simulation.report().high_score_place_option
+}
+
+#[test]
+fn runs_played_at_a_difficulty_go_on_its_table() {
+    assert_eq!(place_of_lost_run(Difficulty::Normal.config()), Some(0));
+}
+
+#[test]
+fn runs_played_with_a_tuned_config_are_left_off_the_table() {
+    let mut config = Difficulty::Normal.config();
+    config.max_lives = 1;
+
+    assert_eq!(place_of_lost_run(config), None);
+}
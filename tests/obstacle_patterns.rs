@@ -0,0 +1,70 @@
+use rand::{rngs::StdRng, SeedableRng};
+use runner::{pick_obstacle_pattern, ObstaclePattern, OBSTACLE_PATTERNS};
+
+const EASY: ObstaclePattern = ObstaclePattern {
+    name: "easy",
+    obstacles: &[],
+    weight_at_start: 10,
+    weight_at_hardest: 0,
+};
+const HARD: ObstaclePattern = ObstaclePattern {
+    name: "hard",
+    obstacles: &[],
+    weight_at_start: 0,
+    weight_at_hardest: 30,
+};
+
+/// How many times each of `patterns` is picked out of 1000 picks at `progress_percent`.
+fn count_picks(patterns: &[ObstaclePattern], progress_percent: u8) -> Vec<u32> {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut counts = vec![0; patterns.len()];
+
+    for _ in 0..1000 {
+        let picked_pattern = pick_obstacle_pattern(patterns, progress_percent, &mut rng);
+        let index = patterns
+            .iter()
+            .position(|pattern| pattern.name == picked_pattern.name)
+            .unwrap();
+
+        counts[index] += 1;
+    }
+
+    counts
+}
+
+#[test]
+fn pattern_weights_ramp_evenly_along_the_curve() {
+    assert_eq!(EASY.weight(0), 10);
+    assert_eq!(EASY.weight(50), 5);
+    assert_eq!(EASY.weight(100), 0);
+
+    assert_eq!(HARD.weight(0), 0);
+    assert_eq!(HARD.weight(50), 15);
+    assert_eq!(HARD.weight(100), 30);
+    assert_eq!(HARD.weight(u8::MAX), 30);
+}
+
+#[test]
+fn patterns_are_picked_by_their_weight_where_the_game_is_on_the_curve() {
+    assert_eq!(count_picks(&[EASY, HARD], 0), [1000, 0]);
+    assert_eq!(count_picks(&[EASY, HARD], 100), [0, 1000]);
+
+    // Halfway along, hard is three times as likely as easy.
+    let counts = count_picks(&[EASY, HARD], 50);
+    assert!((200..300).contains(&counts[0]), "{:?}", counts);
+    assert!((700..800).contains(&counts[1]), "{:?}", counts);
+}
+
+#[test]
+fn every_pattern_can_be_picked_somewhere_along_the_curve() {
+    for pattern in OBSTACLE_PATTERNS {
+        assert!(
+            pattern.weight(0) > 0 || pattern.weight(100) > 0,
+            "{} is never picked",
+            pattern.name
+        );
+    }
+    assert!(OBSTACLE_PATTERNS
+        .iter()
+        .any(|pattern| pattern.weight(0) > 0));
+}
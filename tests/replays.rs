@@ -0,0 +1,125 @@
+mod common;
+
+use common::simulation_options;
+use runner::{
+    components::{Action, GameState},
+    Difficulty, Replay, ReplayError, Resolution, Simulation, SimulationOptions, Wallet,
+};
+
+fn parse_error_line(contents: &str) -> Option<usize> {
+    match Replay::parse(contents) {
+        Err(ReplayError::Parse { line_number, .. }) => Some(line_number),
+        _ => None,
+    }
+}
+
+#[test]
+fn replays_read_back_the_same_as_they_were_saved() {
+    let mut config = Difficulty::Hard.config();
+    config.gravity = 12;
+    let wallet = Wallet {
+        coins: 40,
+        ..Wallet::default()
+    };
+    let mut replay = Replay::new(
+        9,
+        true,
+        Difficulty::Hard,
+        config,
+        wallet,
+        Resolution::new(60, 14).unwrap(),
+    );
+
+    replay.record([Action::AnyKey]);
+    replay.record([]);
+    replay.record([Action::Slide, Action::Jump, Action::Jump]);
+
+    let parsed_replay = Replay::parse(&replay.to_string()).unwrap();
+
+    assert_eq!(parsed_replay, replay);
+    assert_eq!(parsed_replay.num_ticks(), 3);
+    assert_eq!(parsed_replay.actions_at(2), [Action::Jump, Action::Slide]);
+}
+
+#[test]
+fn replays_that_arent_in_the_expected_format_are_rejected() {
+    let header = "# runner replay\nseed 1\nticks 10\n";
+
+    assert_eq!(parse_error_line(&format!("{header}3 fly\n")), Some(4));
+    assert_eq!(parse_error_line(&format!("{header}three jump\n")), Some(4));
+    assert_eq!(
+        parse_error_line(&format!("{header}config gravity\n")),
+        Some(4)
+    );
+    assert_eq!(
+        parse_error_line(&format!("{header}config gravity up\n")),
+        Some(4)
+    );
+    assert_eq!(
+        parse_error_line(&format!("{header}difficulty hardest\n")),
+        Some(4)
+    );
+    assert_eq!(
+        parse_error_line(&format!("{header}resolution 10 10\n")),
+        Some(4)
+    );
+    assert_eq!(parse_error_line("seed 1 always\nticks 10\n"), Some(1));
+    // Ticks past the end, and a config that doesn't hold together, are problems with the replay as a whole.
+    assert_eq!(parse_error_line(&format!("{header}10 jump\n")), Some(0));
+    assert_eq!(
+        parse_error_line(&format!("{header}config max_lives 0\n")),
+        Some(0)
+    );
+}
+
+#[test]
+fn replays_need_a_seed_and_a_tick_count() {
+    assert_eq!(parse_error_line("# runner replay\nticks 10\n"), Some(0));
+    assert_eq!(parse_error_line("# runner replay\nseed 1\n"), Some(0));
+}
+
+#[test]
+fn a_recorded_run_replays_to_the_same_place() {
+    let options = || SimulationOptions {
+        seed: 21,
+        is_seed_fixed: false,
+        ..simulation_options(Difficulty::Normal.config())
+    };
+    let mut replay = Replay::new(
+        21,
+        false,
+        Difficulty::Normal,
+        Difficulty::Normal.config(),
+        Wallet::default(),
+        Resolution::new(40, 10).unwrap(),
+    );
+    let mut simulation = Simulation::new(options());
+
+    // Jumping every so often, and starting another run once one's over.
+    for tick in 0..1500 {
+        let actions = if tick == 0 {
+            vec![Action::AnyKey]
+        } else if simulation.report().game_state != GameState::Playing {
+            vec![Action::Restart]
+        } else if tick % 25 < 3 {
+            vec![Action::Jump]
+        } else {
+            vec![]
+        };
+
+        replay.record(actions.iter().copied());
+        simulation.step(actions);
+    }
+
+    let mut report = simulation.report();
+    let replay = Replay::parse(&replay.to_string()).unwrap();
+    let mut replayed_report = Simulation::replay(&replay, 0);
+
+    // A run that's over is marked with when it finished in real time, which is the one thing a replay can't repeat.
+    report.stats.finished_at = 0;
+    replayed_report.stats.finished_at = 0;
+
+    // The seed's only different once another run's been started.
+    assert_ne!(report.seed, 21);
+    assert_eq!(replayed_report, report);
+}
@@ -0,0 +1,21 @@
+use runner::{Resolution, MIN_SCREEN_HEIGHT, MIN_SCREEN_WIDTH};
+
+#[test]
+fn screens_smaller_than_the_game_can_be_played_on_are_rejected() {
+    assert!(Resolution::new(MIN_SCREEN_WIDTH - 1, MIN_SCREEN_HEIGHT).is_err());
+    assert!(Resolution::new(MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT - 1).is_err());
+    assert!(Resolution::new(0, 0).is_err());
+
+    let resolution = Resolution::new(MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT).unwrap();
+    assert_eq!(resolution.width(), 40);
+    assert_eq!(resolution.height(), 10);
+}
+
+#[test]
+fn amounts_scale_with_the_size_of_the_screen() {
+    let resolution = Resolution::new(160, 24).unwrap();
+
+    assert_eq!(resolution.scale_by_width(10), 20);
+    assert_eq!(resolution.scale_by_area(10), 40);
+    assert_eq!(Resolution::default().scale_by_area(10), 10);
+}
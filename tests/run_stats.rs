@@ -0,0 +1,150 @@
+mod common;
+
+use std::{env, fs, path::PathBuf};
+
+use common::simulation_options;
+use runner::{
+    components::{Action, GameState},
+    Difficulty, LifeLostTo, LostLife, Outcome, RunStats, Simulation,
+};
+
+fn stats() -> RunStats {
+    RunStats {
+        seed: 12,
+        difficulty: "hard",
+        mode: "classic",
+        outcome_option: Some(Outcome::Defeat),
+        score: 340,
+        lives_lost: 2,
+        lost_lives: vec![
+            LostLife {
+                cause: LifeLostTo::AirObstacle,
+                distance_traveled: 120,
+            },
+            LostLife {
+                cause: LifeLostTo::Pit,
+                distance_traveled: 305,
+            },
+        ],
+        end_time_of_day: "dusk",
+        ..RunStats::default()
+    }
+}
+
+/// A path of its own in the temp directory, with nothing there yet.
+fn export_path(file_name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("runner-test-stats-{}", file_name));
+    let _ = fs::remove_file(&path);
+
+    path
+}
+
+#[test]
+fn csv_rows_line_up_with_the_header() {
+    let stats = stats();
+    let header = stats.csv_header();
+    let row = stats.to_csv_row();
+    let columns: Vec<&str> = header.split(',').collect();
+    let values: Vec<&str> = row.split(',').collect();
+
+    assert_eq!(columns.len(), stats.entries().len());
+    assert_eq!(values.len(), columns.len());
+
+    let value_of = |column: &str| values[columns.iter().position(|c| *c == column).unwrap()];
+    assert_eq!(value_of("seed"), "12");
+    assert_eq!(value_of("difficulty"), "hard");
+    assert_eq!(value_of("mode"), "classic");
+    assert_eq!(value_of("outcome"), "defeat");
+    assert_eq!(value_of("lives_lost"), "2");
+    assert_eq!(value_of("lost_lives"), "air_obstacle@120 pit@305");
+    assert_eq!(value_of("end_time_of_day"), "dusk");
+}
+
+#[test]
+fn text_is_escaped_for_the_format_its_exported_in() {
+    let stats = RunStats {
+        mode: "say \"hi\", then\\leave\n",
+        ..RunStats::default()
+    };
+
+    assert!(stats
+        .to_json()
+        .contains(r#""mode":"say \"hi\", then\\leave\n","#));
+    assert!(stats
+        .to_csv_row()
+        .contains("\"say \"\"hi\"\", then\\leave\n\""));
+}
+
+#[test]
+fn json_has_every_stat() {
+    assert_eq!(
+        stats().to_json(),
+        "{\"seed\":12,\"difficulty\":\"hard\",\"mode\":\"classic\",\"outcome\":\"defeat\",\"score\":340,\
+         \"milestones_reached\":0,\"distance_traveled\":0,\"jumps\":0,\"double_jumps\":0,\
+         \"ground_obstacles_dodged\":0,\"air_obstacles_dodged\":0,\"projectiles_dodged\":0,\"lives_lost\":2,\
+         \"lost_lives\":\"air_obstacle@120 pit@305\",\"lives_gained\":0,\"power_ups_collected\":0,\
+         \"hits_shielded\":0,\"coins_collected\":0,\"near_misses\":0,\"best_combo\":0,\
+         \"distance_markers_passed\":0,\"paused_millis\":0,\"end_hour\":0,\"end_time_of_day\":\"dusk\",\
+         \"finished_at\":0}"
+    );
+}
+
+#[test]
+fn csv_files_get_a_header_then_a_row_per_run() {
+    let path = export_path("rows.csv");
+
+    stats().append_to(&path).unwrap();
+    stats().append_to(&path).unwrap();
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            stats().csv_header(),
+            stats().to_csv_row(),
+            stats().to_csv_row()
+        ]
+    );
+}
+
+#[test]
+fn csv_files_started_with_other_columns_are_left_alone() {
+    let path = export_path("other-columns.csv");
+    let contents = "seed,mode,score\n3,classic,100\n";
+    fs::write(&path, contents).unwrap();
+
+    assert!(RunStats::check_export_path(&path).is_err());
+    assert!(stats().append_to(&path).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+}
+
+#[test]
+fn other_files_get_a_line_of_json_per_run() {
+    let path = export_path("runs.jsonl");
+
+    assert_eq!(RunStats::check_export_path(&path).ok(), Some(true));
+    stats().append_to(&path).unwrap();
+    stats().append_to(&path).unwrap();
+    assert_eq!(RunStats::check_export_path(&path).ok(), Some(false));
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!("{}\n{}\n", stats().to_json(), stats().to_json())
+    );
+}
+
+#[test]
+fn obstacles_that_hit_the_player_are_not_counted_as_dodged() {
+    let mut simulation = Simulation::new(simulation_options(Difficulty::Normal.config()));
+    simulation.step([Action::AnyKey]);
+
+    // A player who never jumps can't get past anything along the ground.
+    while simulation.report().game_state == GameState::Playing {
+        simulation.step([]);
+    }
+
+    let stats = simulation.report().stats;
+    assert!(stats.lives_lost > 0);
+    assert_eq!(stats.ground_obstacles_dodged, 0);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{quiet_config, simulation_options};
+use runner::{
+    components::{Action, GameState, SUNSET_TIME},
+    CourseObstacle, Difficulty, Simulation, SimulationOptions,
+};
+
+/// Every obstacle on the course on each frame of a run where the player never does anything.
+fn obstacles_over_a_run(
+    seed: u64,
+    max_frame_rate: u16,
+    num_frames: u64,
+) -> Vec<Vec<CourseObstacle>> {
+    let mut simulation = Simulation::new(SimulationOptions {
+        max_frame_rate,
+        seed,
+        ..simulation_options(Difficulty::Normal.config())
+    });
+
+    simulation.step([Action::AnyKey]);
+
+    (0..num_frames)
+        .map(|_| {
+            simulation.step([]);
+            simulation.course().obstacles
+        })
+        .collect()
+}
+
+#[test]
+fn a_seed_spawns_the_same_obstacles_however_fast_the_frames_go() {
+    // One run waits out real time between frames and the other doesn't, so nothing rolled on the wall clock can be
+    // allowed to change what's spawned.
+    let unlimited_obstacles = obstacles_over_a_run(5, 0, 120);
+    let limited_obstacles = obstacles_over_a_run(5, 30, 120);
+
+    assert!(unlimited_obstacles
+        .iter()
+        .any(|obstacles| !obstacles.is_empty()));
+    assert_eq!(unlimited_obstacles, limited_obstacles);
+}
+
+/// Runs until the player runs out of lives, then starts another run, and gives the seed of each run.
+fn seeds_over_two_runs(is_seed_fixed: bool) -> (u64, u64) {
+    let mut simulation = Simulation::new(SimulationOptions {
+        seed: 5,
+        is_seed_fixed,
+        ..simulation_options(Difficulty::Normal.config())
+    });
+
+    simulation.step([Action::AnyKey]);
+    let first_seed = simulation.report().seed;
+
+    while simulation.report().game_state == GameState::Playing {
+        simulation.step([]);
+    }
+    simulation.step([Action::Restart]);
+
+    let report = simulation.report();
+    assert_eq!(report.game_state, GameState::Playing);
+
+    (first_seed, report.seed)
+}
+
+#[test]
+fn a_fixed_seed_generates_every_run() {
+    assert_eq!(seeds_over_two_runs(true), (5, 5));
+}
+
+#[test]
+fn each_run_after_the_first_gets_a_new_seed_when_the_seed_isnt_fixed() {
+    let (first_seed, second_seed) = seeds_over_two_runs(false);
+
+    assert_eq!(first_seed, 5);
+    assert_ne!(second_seed, 5);
+    // Rolled from the first seed, so a replay of the whole session still knows what it'll be.
+    assert_eq!(seeds_over_two_runs(false).1, second_seed);
+}
+
+/// The coins collected, the hour it ended at and how many frames it took, for a run that goes on until after dark.
+fn run_until_after_dark(seed: u64) -> (u64, u8, u64) {
+    let mut config = quiet_config();
+    config.coin_wait_ticks = 1;
+    config.coin_chance = 100;
+    config.victory_score = 600;
+
+    let mut simulation = Simulation::new(SimulationOptions {
+        seed,
+        ..simulation_options(config)
+    });
+
+    simulation.step([Action::AnyKey]);
+    while simulation.report().game_state == GameState::Playing {
+        simulation.step([]);
+    }
+
+    let report = simulation.report();
+    (report.coins, report.stats.end_hour, report.frames)
+}
+
+#[test]
+fn the_time_of_day_and_the_windows_it_lights_play_out_the_same_from_a_seed() {
+    let (coins, end_hour, frames) = run_until_after_dark(5);
+
+    // The day moves on with the game's ticks, so a run that's played out as fast as it can be still gets dark.
+    assert!(end_hour >= SUNSET_TIME, "{}", end_hour);
+    assert!(coins > 0);
+    assert_eq!(run_until_after_dark(5), (coins, end_hour, frames));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::simulation_options;
+use runner::{
+    components::{Action, GameState},
+    Difficulty, Simulation, SimulationOptions, Upgrade, Wallet,
+};
+
+#[test]
+fn upgrades_bought_in_the_shop_are_paid_for_and_apply_straight_away() {
+    let config = Difficulty::Normal.config();
+    let cost = Upgrade::ExtraLife.cost(0);
+
+    let mut simulation = Simulation::new(SimulationOptions {
+        wallet: Wallet {
+            coins: cost + 1,
+            ..Wallet::default()
+        },
+        ..simulation_options(config.clone())
+    });
+
+    // Pressing a key also counts as pressing any key, which mustn't start the run.
+    simulation.step([Action::Shop, Action::AnyKey]);
+    assert_eq!(simulation.report().game_state, GameState::Shopping);
+
+    // Extra lives are the first upgrade on the list, and there's only enough for one of them.
+    for _ in 0..2 {
+        simulation.step(vec![]);
+        simulation.step([Action::Jump, Action::AnyKey]);
+    }
+
+    let report = simulation.report();
+    assert_eq!(report.game_state, GameState::Shopping);
+    assert_eq!(report.wallet_coins, 1);
+    assert_eq!(report.lives, config.max_lives + 1);
+
+    simulation.step(vec![]);
+    simulation.step([Action::Shop, Action::AnyKey]);
+    assert_eq!(simulation.report().game_state, GameState::WaitingToStart);
+
+    simulation.step(vec![]);
+    simulation.step([Action::AnyKey]);
+
+    let report = simulation.report();
+    assert_eq!(report.game_state, GameState::Playing);
+    assert_eq!(report.lives, config.max_lives + 1);
+}
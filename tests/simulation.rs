@@ -0,0 +1,221 @@
+mod common;
+
+use common::simulation_options;
+use runner::{
+    components::{Action, GameState},
+    Difficulty, GameConfig, GameMode, LifeLostTo, Simulation, SimulationOptions, PLAYER_X_OFFSET,
+};
+
+fn start_simulation(config: GameConfig) -> Simulation {
+    let mut simulation = Simulation::new(SimulationOptions {
+        seed: 3,
+        ..simulation_options(config)
+    });
+
+    simulation.step([Action::AnyKey]);
+
+    simulation
+}
+
+/// Nothing but obstacles, so the only way to lose a life is to run into one.
+fn obstacles_only_config() -> GameConfig {
+    let mut config = Difficulty::Normal.config();
+    config.pickup_chance = 0;
+    config.coin_chance = 0;
+    config.enemy_chance = 0;
+
+    config
+}
+
+#[test]
+fn jumping_leaves_the_ground_and_lands_back_on_it() {
+    let mut simulation = start_simulation(obstacles_only_config());
+    assert!(simulation.report().is_on_ground);
+
+    simulation.step([Action::Jump]);
+
+    let mut peak_height = 0;
+    for _ in 0..60 {
+        simulation.step([]);
+
+        let report = simulation.report();
+        peak_height = peak_height.max(report.height);
+
+        if report.is_on_ground {
+            break;
+        }
+    }
+
+    let report = simulation.report();
+    assert!(peak_height > 0);
+    assert!(report.is_on_ground);
+    assert_eq!(report.height, 0);
+}
+
+#[test]
+fn running_into_an_obstacle_costs_a_life() {
+    let config = obstacles_only_config();
+    let mut simulation = start_simulation(config.clone());
+
+    for _ in 0..900 {
+        simulation.step([]);
+
+        if simulation.report().lives < config.max_lives {
+            break;
+        }
+    }
+
+    let report = simulation.report();
+    assert_eq!(report.lives, config.max_lives - 1);
+    assert_eq!(report.stats.lives_lost, 1);
+    if let [lost_life] = &report.stats.lost_lives[..] {
+        assert!(matches!(
+            lost_life.cause,
+            LifeLostTo::GroundObstacle | LifeLostTo::AirObstacle | LifeLostTo::Pit
+        ));
+        assert!(report.distance_traveled - lost_life.distance_traveled <= 1);
+    } else {
+        panic!("expected a lost life, got {:?}", report.stats.lost_lives);
+    }
+    assert!(report.is_invulnerable);
+}
+
+#[test]
+fn running_into_an_obstacle_made_of_many_cells_costs_a_single_life() {
+    let mut config = obstacles_only_config();
+    // Without any time to recover, the rest of an obstacle's cells would each cost a life if the obstacle stayed.
+    config.invulnerability_ticks = 0;
+    config.max_lives = u8::MAX;
+    let mut simulation = start_simulation(config);
+
+    let mut was_life_lost_last_frame = false;
+    let mut most_cells_hit_at_once = 0;
+    for _ in 0..3000 {
+        let obstacles_before = simulation.course().obstacles;
+        let lives_before = simulation.report().lives;
+
+        simulation.step([]);
+
+        let course = simulation.course();
+        let player_x = course.camera_x + PLAYER_X_OFFSET;
+        let lives_lost = lives_before - simulation.report().lives;
+
+        assert!(lives_lost <= 1);
+        assert!(!(was_life_lost_last_frame && lives_lost == 1));
+
+        if lives_lost == 1 {
+            let cells_removed = obstacles_before
+                .iter()
+                .filter(|obstacle| obstacle.x >= player_x - 1)
+                .filter(|obstacle| !course.obstacles.contains(obstacle))
+                .count();
+
+            most_cells_hit_at_once = most_cells_hit_at_once.max(cells_removed);
+        }
+        was_life_lost_last_frame = lives_lost == 1;
+    }
+
+    assert!(most_cells_hit_at_once > 1);
+}
+
+#[test]
+fn reaching_the_victory_score_wins_the_run() {
+    let mut config = obstacles_only_config();
+    config.victory_score = 20;
+    let mut simulation = start_simulation(config.clone());
+
+    // Jumping all the time clears most of what comes along, and there are enough lives to get past the rest.
+    let mut frames_played_past_victory_score = 0;
+    for frame in 0..2000 {
+        let report = simulation.report();
+        if report.game_state != GameState::Playing {
+            break;
+        }
+        if report.score >= config.victory_score {
+            frames_played_past_victory_score += 1;
+        }
+
+        simulation.step(if frame % 2 == 0 {
+            vec![Action::Jump]
+        } else {
+            vec![]
+        });
+    }
+
+    let report = simulation.report();
+    assert_eq!(report.game_state, GameState::Victory);
+    // Victory's checked once a frame, so the run carries on for at most one more frame once the score gets there.
+    assert!(frames_played_past_victory_score <= 1);
+    assert!(report.score >= config.victory_score);
+    assert!(report.lives > 0);
+}
+
+#[test]
+fn losing_the_last_life_loses_the_run() {
+    let config = obstacles_only_config();
+    let mut simulation = start_simulation(config.clone());
+
+    for _ in 0..5000 {
+        let report = simulation.report();
+        if report.game_state != GameState::Playing {
+            break;
+        }
+        assert!(report.lives > 0);
+
+        simulation.step([]);
+    }
+
+    let report = simulation.report();
+    assert_eq!(report.game_state, GameState::Defeat);
+    assert_eq!(report.lives, 0);
+    assert_eq!(report.stats.lives_lost, u64::from(config.max_lives));
+}
+
+#[test]
+fn endless_runs_pass_a_milestone_once_per_victory_score() {
+    let mut config = obstacles_only_config();
+    config.mode = GameMode::Endless;
+    config.victory_score = 20;
+    config.max_lives = u8::MAX;
+    let mut simulation = start_simulation(config.clone());
+
+    for frame in 0..2000 {
+        let milestones_reached = simulation.report().milestones_reached;
+        if milestones_reached == 3 {
+            break;
+        }
+
+        simulation.step(if frame % 2 == 0 {
+            vec![Action::Jump]
+        } else {
+            vec![]
+        });
+
+        let report = simulation.report();
+        assert_eq!(report.game_state, GameState::Playing);
+        assert!(report.milestones_reached <= milestones_reached + 1);
+        assert!(report.milestones_reached <= report.score / config.victory_score);
+    }
+
+    // Milestones are checked once a frame, like victory, so the score may have gone a frame's worth past the third.
+    let report = simulation.report();
+    assert_eq!(report.milestones_reached, 3);
+    assert_eq!(report.score / config.victory_score, 3);
+}
+
+#[test]
+fn restarting_after_a_run_is_lost_starts_a_fresh_run() {
+    let config = obstacles_only_config();
+    let mut simulation = start_simulation(config.clone());
+
+    while simulation.report().game_state == GameState::Playing {
+        simulation.step([]);
+    }
+    simulation.step([Action::Restart]);
+
+    let report = simulation.report();
+    assert_eq!(report.game_state, GameState::Playing);
+    assert_eq!(report.lives, config.max_lives);
+    assert_eq!(report.stats.outcome_option, None);
+    assert_eq!(report.stats.lives_lost, 0);
+}
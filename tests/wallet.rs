@@ -0,0 +1,32 @@
+mod common;
+
+use common::{quiet_config, simulation_options};
+use runner::{
+    components::{Action, GameState},
+    Simulation,
+};
+
+#[test]
+fn coins_picked_up_over_a_run_go_into_the_wallet_once_it_is_over() {
+    let mut config = quiet_config();
+    // Nothing but coins, so the lines along the ground are run straight through.
+    config.coin_wait_ticks = 1;
+    config.coin_chance = 100;
+    config.victory_score = 100;
+
+    let mut simulation = Simulation::new(simulation_options(config));
+    simulation.step([Action::AnyKey]);
+
+    let mut report = simulation.report();
+    while report.game_state == GameState::Playing {
+        assert_eq!(report.wallet_coins, 0);
+
+        simulation.step(vec![]);
+        report = simulation.report();
+    }
+
+    assert_eq!(report.game_state, GameState::Victory);
+    assert!(report.coins > 0);
+    assert_eq!(report.stats.coins_collected, report.coins);
+    assert_eq!(report.wallet_coins, report.coins);
+}